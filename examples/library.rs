@@ -0,0 +1,36 @@
+//! Search with the library alone, which builds without the terminal crates:
+//!
+//! cargo run --example library --no-default-features -- src "*.rs"
+
+use std::ops::ControlFlow;
+
+use seek::matchers::{CaseMode, create_matcher};
+use seek::search::{SearchEvent, SearchOptions, Searcher};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| ".".to_string());
+    let pattern = args.next().unwrap_or_else(|| "*".to_string());
+
+    let matcher = match create_matcher(&pattern, false, CaseMode::Sensitive) {
+        Ok(matcher) => matcher,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    };
+    let searcher = Searcher::with_options(matcher, path.into(), SearchOptions::default());
+    searcher.run_blocking(false, |event| {
+        match event {
+            SearchEvent::Match(entry) => println!("{}", entry.path().display()),
+            SearchEvent::Finished(result) => {
+                eprintln!(
+                    "{} matches in {} entries",
+                    result.matches, result.entries_examined
+                );
+            }
+            SearchEvent::Progress { .. } | SearchEvent::Status(_) => {}
+        }
+        ControlFlow::Continue(())
+    });
+}
@@ -0,0 +1,1618 @@
+use crate::matchers::CaseMode;
+use clap::error::ErrorKind;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use clap_complete::Shell;
+use colored::Color;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::search::SearchOptions;
+use crate::ui::{PathStyle, Template};
+
+mod presets;
+
+pub use presets::Preset;
+
+/// Files scanned before stopping when `--max-files` isn't given
+pub const DEFAULT_MAX_FILES: usize = 500_000;
+
+/// Thread counts above this draw a warning
+const MAX_USEFUL_THREADS: usize = 512;
+
+/// Seek - A fast file search tool
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = "Seek: Search files using glob or regex.\n\n\
+Usage:\n  seek <PATH> <PATTERN>          (glob by default)\n  seek <PATH> <PATTERN> -r         (regex mode)"
+)]
+#[command(group(ArgGroup::new("exec_mode").args(["exec", "exec_batch"])))]
+// Lets flags on the command line replace ones from SEEK_DEFAULT_OPTS
+#[command(args_override_self = true)]
+pub struct Args {
+    /// Enable regex mode instead of glob
+    #[arg(short = 'r', long = "regex")]
+    pub regex: bool,
+
+    /// Ignore case unless the pattern has an uppercase letter
+    #[arg(short = 'S', long = "smart-case", overrides_with_all = ["ignore_case", "case_sensitive"])]
+    pub smart_case: bool,
+
+    /// Ignore case, whatever the pattern (overrides --smart-case)
+    #[arg(short = 'i', long = "ignore-case", overrides_with_all = ["smart_case", "case_sensitive"])]
+    pub ignore_case: bool,
+
+    /// Match case exactly, the default (overrides --smart-case, including from the config file)
+    #[arg(short = 's', long = "case-sensitive", overrides_with_all = ["smart_case", "ignore_case"])]
+    pub case_sensitive: bool,
+
+    /// With --path or --relative-match, ignore case in the directory part of the pattern only
+    #[arg(
+        long = "ignore-case-path",
+        conflicts_with_all = ["regex", "smart_case", "ignore_case", "case_sensitive", "ends_with"]
+    )]
+    pub ignore_case_path: bool,
+
+    /// Ignore case in the final name only, keeping directory components case-sensitive
+    #[arg(
+        long = "ignore-case-name",
+        conflicts_with_all = ["regex", "smart_case", "ignore_case", "case_sensitive", "ends_with"]
+    )]
+    pub ignore_case_name: bool,
+
+    /// Search full path instead of just filename
+    #[arg(short = 'p', long = "path")]
+    pub full_path: bool,
+
+    /// Match the path relative to the search root, so `src/**/*.rs` works from anywhere
+    #[arg(long = "relative-match", conflicts_with_all = ["full_path", "link_target"])]
+    pub relative_match: bool,
+
+    /// Only match entries with one of these extensions, checked before the pattern
+    /// (comma-separated or repeated, e.g. `--ext rs,toml`)
+    #[arg(long = "ext", value_name = "EXT", value_delimiter = ',')]
+    pub extensions: Vec<String>,
+
+    /// Only match files with the extensions of a common category, on top of any --ext
+    #[arg(
+        long = "preset",
+        value_enum,
+        value_name = "NAME",
+        conflicts_with = "dirs_only"
+    )]
+    pub preset: Option<Preset>,
+
+    /// Print the extensions each --preset matches and exit
+    #[arg(long = "list-presets")]
+    pub list_presets: bool,
+
+    /// Match paths ending in the pattern's components, so `src/mod.rs` matches
+    /// `a/src/mod.rs` but `r.rs` doesn't match `bar.rs`
+    #[arg(long = "ends-with", conflicts_with_all = ["regex", "full_path", "relative_match"])]
+    pub ends_with: bool,
+
+    /// Only show files (not directories)
+    #[arg(short = 'f', long = "files-only")]
+    pub files_only: bool,
+
+    /// Only show directories (not files); matching directories are still searched inside
+    #[arg(
+        short = 'd',
+        long = "dirs-only",
+        conflicts_with_all = ["files_only", "min_links", "links_only", "link_target"]
+    )]
+    pub dirs_only: bool,
+
+    /// Maximum search depth (the search root is depth 0, its children depth 1)
+    #[arg(short = 'D', long = "max-depth")]
+    pub max_depth: Option<usize>,
+
+    /// Only match entries at least this deep, still walking the levels above
+    #[arg(long = "min-depth", value_name = "N")]
+    pub min_depth: Option<usize>,
+
+    /// Follow symlinks into the directories they point to
+    #[arg(short = 'L', long = "follow")]
+    pub follow: bool,
+
+    /// Skip entries whose names start with `.`, along with everything inside hidden directories
+    #[arg(long = "no-hidden")]
+    pub no_hidden: bool,
+
+    /// Below a matching directory, descend at most N more levels (unlike --max-depth,
+    /// which counts from the search root)
+    #[arg(long = "max-depth-from-match", value_name = "N")]
+    pub max_depth_from_match: Option<usize>,
+
+    /// Threads to search with [default: available CPUs]; -j 1 keeps the walk
+    /// order reproducible once traversal is parallel
+    #[arg(short = 'j', long = "threads", value_name = "N")]
+    pub threads: Option<usize>,
+
+    /// Visit shallower entries before deeper ones, at the cost of queueing
+    /// every directory of a level in memory on wide trees
+    #[arg(long = "breadth-first")]
+    pub breadth_first: bool,
+
+    /// Let a directory being searched match itself, as `.` matches `*`
+    #[arg(long = "include-root", overrides_with = "skip_root")]
+    pub include_root: bool,
+
+    /// Never list the directory being searched as a result of its own search (the default)
+    #[arg(long = "skip-root", overrides_with = "include_root")]
+    pub skip_root: bool,
+
+    /// Only search the immediate children of the path (same as --max-depth 1)
+    #[arg(short = 'R', long = "no-recursive", conflicts_with = "max_depth")]
+    pub no_recursive: bool,
+
+    /// Show permission and other walk errors, with a breakdown by kind after the search
+    /// (errors are always skipped)
+    #[arg(short = 'e', long = "show-permission-errors", alias = "show-errors")]
+    pub show_permission_errors: bool,
+
+    /// Maximum number of files to scan (0 = unlimited) [default: 500000]
+    #[arg(short = 'n', long = "max-files", value_name = "COUNT")]
+    pub max_files: Option<usize>,
+
+    /// Stop after printing this many matches (0 = unlimited)
+    #[arg(short = 'm', long = "limit", value_name = "N", default_value_t = 0)]
+    pub limit: usize,
+
+    /// Print the first match and stop searching right away (same as --limit 1)
+    #[arg(short = '1', long = "first", conflicts_with_all = ["limit", "count_all_with_limit"])]
+    pub first: bool,
+
+    /// Print only the first N matches but keep searching and count the rest (0 = show all)
+    #[arg(
+        long = "head",
+        value_name = "N",
+        conflicts_with_all = ["format", "print0", "exec_mode", "count", "tree", "group"]
+    )]
+    pub head: Option<usize>,
+
+    /// Write results to FILE (truncating it) instead of stdout; `-` means stdout
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_name = "FILE",
+        conflicts_with_all = ["exec_mode", "count", "quiet", "stats_only"]
+    )]
+    pub output: Option<PathBuf>,
+
+    /// Stop printing once the printed paths add up to more than N bytes, counting the rest (0 = no limit)
+    #[arg(
+        long = "max-output-bytes",
+        value_name = "N",
+        conflicts_with_all = ["exec_mode", "count", "tree", "group"]
+    )]
+    pub max_output_bytes: Option<usize>,
+
+    /// Keep searching past --limit, counting the remaining matches without printing them
+    #[arg(long = "count-all-with-limit", requires = "limit")]
+    pub count_all_with_limit: bool,
+
+    /// Search timeout in seconds (0 = no timeout)
+    #[arg(short = 't', long = "timeout", default_value = "600")]
+    pub timeout_seconds: u64,
+
+    /// Read additional patterns from a file (one per line, # for comments)
+    #[arg(long = "pattern-file", value_name = "FILE")]
+    pub pattern_file: Option<PathBuf>,
+
+    /// Read patterns to exclude from a file (one per line, # for comments)
+    #[arg(long = "exclude-file", value_name = "FILE")]
+    pub exclude_file: Option<PathBuf>,
+
+    /// Patterns to exclude from the config file's `exclude` list
+    #[arg(skip)]
+    pub config_excludes: Vec<String>,
+
+    /// Directories to skip from the config file's `prune` list
+    #[arg(skip)]
+    pub config_prunes: Vec<String>,
+
+    /// Read default options from this file instead of ~/.config/seek/config.toml
+    #[arg(long = "config", value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Ignore the config file
+    #[arg(long = "no-config", conflicts_with = "config")]
+    pub no_config: bool,
+
+    /// Print the last 20 searches (time, match count, and command line) and exit
+    #[arg(
+        long = "history",
+        conflicts_with_all = ["path_or_pattern", "maybe_pattern", "root", "pattern", "get"]
+    )]
+    pub history: bool,
+
+    /// Run the most recent search in the history again, in the directory it ran in
+    #[arg(
+        long = "again",
+        conflicts_with_all = ["path_or_pattern", "maybe_pattern", "root", "pattern", "get", "history"]
+    )]
+    pub again: bool,
+
+    /// Don't record this search in the history (`history = false` in the config turns it off)
+    #[arg(long = "no-history")]
+    pub no_history: bool,
+
+    /// Print nothing and stop at the first match, reporting only through the exit status
+    #[arg(short = 'q', long = "quiet", conflicts_with_all = ["watch", "stats"])]
+    pub quiet: bool,
+
+    /// Print only the number of matches
+    #[arg(
+        short = 'c',
+        long = "count",
+        conflicts_with_all = ["watch", "long", "format", "print0", "quiet", "group_by"]
+    )]
+    pub count: bool,
+
+    /// Always exit with status 0 after a completed search, even without matches
+    #[arg(long = "no-exit-code")]
+    pub no_exit_code: bool,
+
+    /// When to use colors in output
+    #[arg(long = "color", value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Color for directory results, such as blue, magenta, or "bright blue"
+    #[arg(long = "dir-color", value_name = "COLOR", value_parser = parse_color, default_value = "blue")]
+    pub dir_color: Color,
+
+    /// Follow symlink results with ` -> target`
+    #[arg(long = "show-link-targets", conflicts_with_all = ["format", "print0", "exec_mode"])]
+    pub show_link_targets: bool,
+
+    /// Prefix results with Nerd Font file-type icons (ignored with --format, -0, and --exec)
+    #[arg(long = "icons")]
+    pub icons: bool,
+
+    /// Use only ASCII for the spinner and --tree lines, and drop --icons (automatic when the locale isn't UTF-8)
+    #[arg(long = "ascii")]
+    pub ascii: bool,
+
+    /// Make printed paths clickable links to their files (a bare --hyperlink means auto)
+    #[arg(
+        long = "hyperlink",
+        value_enum,
+        value_name = "WHEN",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "auto",
+        default_value_t = HyperlinkChoice::Never
+    )]
+    pub hyperlink: HyperlinkChoice,
+
+    /// Output format: text, csv, tsv, jsonl, or a template like '{path}\t{size}'
+    /// (placeholders: path, name, dir, ext, size, mtime, depth, type)
+    #[arg(long = "format", value_name = "FORMAT", value_parser = parse_format, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Long listing with permissions, owner, group, size, and mtime before each path
+    #[arg(short = 'l', long = "long", conflicts_with_all = ["format", "print0"])]
+    pub long: bool,
+
+    /// Show human-readable sizes (K, M, G) in the long listing
+    #[arg(short = 'H', long = "human-readable", requires = "long")]
+    pub human_readable: bool,
+
+    /// Terminate each result with NUL instead of newline, for `xargs -0`
+    #[arg(short = '0', long = "print0", conflicts_with = "format")]
+    pub print0: bool,
+
+    /// Don't show the progress spinner (it is hidden automatically when stderr isn't a terminal)
+    #[arg(long = "no-progress")]
+    pub no_progress: bool,
+
+    /// Ring the bell and send a desktop notification when a long search finishes
+    #[arg(long = "notify", conflicts_with_all = ["count", "stats_only", "watch", "status_json"])]
+    pub notify: bool,
+
+    /// How long a search must run before --notify fires
+    #[arg(
+        long = "notify-after",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        default_value = "30s",
+        requires = "notify"
+    )]
+    pub notify_after: Duration,
+
+    /// Longest current path shown in the spinner, trimmed to `…/tail` (default: fit the terminal)
+    #[arg(long = "progress-path-width", value_name = "COLS")]
+    pub progress_path_width: Option<usize>,
+
+    /// Report progress as JSON lines on stderr instead of a spinner, for editor plugins
+    #[arg(long = "status-json", conflicts_with_all = ["count", "stats_only", "watch", "pick"])]
+    pub status_json: bool,
+
+    /// Milliseconds between --status-json heartbeats with running totals
+    #[arg(
+        long = "heartbeat",
+        value_name = "MS",
+        default_value = "1000",
+        requires = "status_json"
+    )]
+    pub heartbeat_ms: u64,
+
+    /// Print paths relative to the search path
+    #[arg(long = "relative", conflicts_with = "absolute")]
+    pub relative: bool,
+
+    /// Print absolute paths, resolving symlinks in the search path
+    #[arg(long = "absolute")]
+    pub absolute: bool,
+
+    /// Print Windows extended-length paths (\\?\) without stripping the prefix
+    #[arg(long = "raw-paths")]
+    pub raw_paths: bool,
+
+    /// Log skipped entries to stderr (-vv to log every filter decision)
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Log every filter decision to stderr (same as -vv)
+    #[arg(long = "debug")]
+    pub debug: bool,
+
+    /// Only match files git reports as modified or untracked
+    #[arg(long = "git-modified", alias = "changed-within-git")]
+    pub git_modified: bool,
+
+    /// Only match entries modified within this long, e.g. 30m, 2h, 7d
+    #[arg(long = "modified-within", value_name = "DURATION", value_parser = parse_duration)]
+    pub modified_within: Option<Duration>,
+
+    /// Only match entries last accessed within this long (filesystems mounted
+    /// with noatime or relatime may not update access times)
+    #[arg(long = "accessed-within", value_name = "DURATION", value_parser = parse_duration)]
+    pub accessed_within: Option<Duration>,
+
+    /// Only match entries not accessed for at least this long, e.g. 90d, for stale caches
+    /// (warns when access times look like they aren't being updated)
+    #[arg(long = "not-accessed-for", value_name = "DURATION", value_parser = parse_duration)]
+    pub not_accessed_for: Option<Duration>,
+
+    /// Only match entries created within this long, where the filesystem records
+    /// creation (birth) times; ignored with a warning elsewhere
+    #[arg(long = "created-within", value_name = "DURATION", value_parser = parse_duration)]
+    pub created_within: Option<Duration>,
+
+    /// Only match paths with a directory or file named exactly NAME along the way
+    #[arg(long, value_name = "NAME")]
+    pub component: Option<String>,
+
+    /// Only match entries whose name is more than N characters long
+    #[arg(long = "name-longer-than", value_name = "N")]
+    pub name_longer_than: Option<usize>,
+
+    /// Only match entries whose name is fewer than N characters long
+    #[arg(long = "name-shorter-than", value_name = "N")]
+    pub name_shorter_than: Option<usize>,
+
+    /// Match symlinks by the path they point to instead of their own name; other entries are skipped
+    #[arg(long = "link-target", conflicts_with_all = ["min_links", "links_only"])]
+    pub link_target: bool,
+
+    /// Only match files with at least this many hard links (Unix only)
+    #[arg(long = "min-links", value_name = "N")]
+    pub min_links: Option<u64>,
+
+    /// Only match files with more than one hard link (same as --min-links 2)
+    #[arg(long = "links-only", conflicts_with = "min_links")]
+    pub links_only: bool,
+
+    /// Number each result and save the list, so `seek --get N` can print result N later
+    #[arg(
+        long = "numbers",
+        conflicts_with_all = [
+            "format", "print0", "long", "exec_mode", "count", "quiet", "tree", "group", "watch",
+            "pick", "stats_only",
+        ]
+    )]
+    pub numbers: bool,
+
+    /// Print result N of the last search run with --numbers, and nothing else
+    #[arg(
+        long = "get",
+        value_name = "N",
+        conflicts_with_all = ["path_or_pattern", "maybe_pattern", "root", "pattern", "numbers"]
+    )]
+    pub get: Option<usize>,
+
+    /// Print matches as a tree below the search path once the search finishes
+    #[arg(long, conflicts_with_all = ["watch", "long", "format", "print0"])]
+    pub tree: bool,
+
+    /// Print only the N most recently modified matches, newest first
+    #[arg(long, value_name = "N", conflicts_with_all = ["watch", "tree", "count"])]
+    pub recent: Option<usize>,
+
+    /// Print matches under a header for each parent directory once the search finishes
+    #[arg(long, conflicts_with_all = ["watch", "tree", "recent", "count", "format", "print0"])]
+    pub group: bool,
+
+    /// Order of directory headers printed by --group
+    #[arg(long = "group-sort", value_enum, value_name = "ORDER", default_value_t = GroupSort::Name, requires = "group")]
+    pub group_sort: GroupSort,
+
+    /// Run a command for each match; {} is the path, {.} the path without extension,
+    /// {/} the file name, and {//} the parent directory. An optional ';' ends the command
+    #[arg(
+        short = 'x',
+        long = "exec",
+        value_name = "CMD",
+        num_args = 1..,
+        allow_hyphen_values = true,
+        value_terminator = ";",
+        conflicts_with_all = ["format", "long", "print0", "count", "quiet", "tree", "group"]
+    )]
+    pub exec: Option<Vec<String>>,
+
+    /// Run a command once with all matches in place of {} (split into several
+    /// runs if the arguments would be too long). An optional ';' ends the command
+    #[arg(
+        short = 'X',
+        long = "exec-batch",
+        value_name = "CMD",
+        num_args = 1..,
+        allow_hyphen_values = true,
+        value_terminator = ";",
+        conflicts_with_all = ["format", "long", "print0", "count", "quiet", "tree", "group", "watch"]
+    )]
+    pub exec_batch: Option<Vec<String>>,
+
+    /// Maximum bytes of arguments per --exec-batch run
+    #[arg(long = "exec-arg-max", hide = true, default_value_t = crate::ui::DEFAULT_ARG_LIMIT, requires = "exec_batch")]
+    pub exec_arg_max: usize,
+
+    /// Number of --exec commands to run at once
+    #[arg(
+        long = "exec-jobs",
+        value_name = "N",
+        default_value_t = 1,
+        requires = "exec"
+    )]
+    pub exec_jobs: usize,
+
+    /// Run --exec or --exec-batch commands through the system shell, with paths quoted
+    #[arg(long = "exec-shell", requires = "exec_mode")]
+    pub exec_shell: bool,
+
+    /// Print the --exec or --exec-batch commands instead of running them
+    #[arg(long = "dry-run", requires = "exec_mode")]
+    pub dry_run: bool,
+
+    /// Print each match with the first occurrence of OLD replaced by NEW, leaving files untouched
+    #[arg(
+        long = "replace",
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        allow_hyphen_values = true,
+        conflicts_with_all = ["exec_mode", "tree", "group"]
+    )]
+    pub replace: Option<Vec<String>>,
+
+    /// Replace every occurrence of OLD rather than just the first
+    #[arg(long = "replace-all", requires = "replace")]
+    pub replace_all: bool,
+
+    /// Part of each displayed path that --replace rewrites
+    #[arg(long = "replace-in", value_enum, value_name = "PART", default_value_t = ReplaceScope::Path, requires = "replace")]
+    pub replace_in: ReplaceScope,
+
+    /// Only match text files with a line matching this regex, printing those lines
+    /// with their numbers under each path
+    #[arg(long = "grep", value_name = "REGEX", conflicts_with_all = ["dirs_only", "exec_mode"])]
+    pub grep: Option<String>,
+
+    /// Most matching lines to print per file with --grep
+    #[arg(
+        long = "grep-max-lines",
+        value_name = "N",
+        default_value_t = 5,
+        requires = "grep"
+    )]
+    pub grep_max_lines: usize,
+
+    /// Show how many entries each matched directory holds, or `?` if it can't be read
+    #[arg(long = "count-entries", conflicts_with_all = ["format", "print0", "exec_mode", "tree", "count"])]
+    pub count_entries: bool,
+
+    /// Print a breakdown of matches by the given key after the search
+    #[arg(long = "group-by", alias = "stats-by", value_enum, value_name = "KEY")]
+    pub group_by: Option<GroupBy>,
+
+    /// Only list this many of the largest groups in the --group-by breakdown
+    #[arg(long = "top", value_name = "N", requires = "group_by")]
+    pub top: Option<usize>,
+
+    /// Summarize the tree (file and directory counts, total size, largest file) without matching
+    #[arg(
+        long = "stats-only",
+        conflicts_with_all = ["watch", "count", "quiet", "tree", "recent", "group", "exec_mode"]
+    )]
+    pub stats_only: bool,
+
+    /// Print throughput statistics after the search
+    #[arg(long = "stats")]
+    pub stats: bool,
+
+    /// Choose among the matches in an interactive, filterable list and print the
+    /// chosen paths (Tab marks several, Escape cancels)
+    #[arg(
+        long = "pick",
+        conflicts_with_all = [
+            "format", "print0", "exec_mode", "count", "quiet", "watch", "tree", "group",
+            "recent", "output", "long", "stats_only"
+        ]
+    )]
+    pub pick: bool,
+
+    /// Keep watching for new matches after the initial scan (until Ctrl-C)
+    #[arg(short = 'w', long = "watch")]
+    pub watch: bool,
+
+    /// Also report matching files deleted while watching
+    #[arg(long = "watch-deletes", requires = "watch")]
+    pub watch_deletes: bool,
+
+    /// Maximum matches queued for display before the search waits for output to catch up
+    #[arg(long = "channel-capacity", default_value_t = crate::search::DEFAULT_CHANNEL_CAPACITY)]
+    pub channel_capacity: usize,
+
+    /// Walk the whole tree before matching anything, instead of matching entries as they're found
+    #[arg(long = "two-pass", overrides_with = "stream")]
+    pub two_pass: bool,
+
+    /// Match entries as they're walked (the default; overrides an earlier --two-pass)
+    #[arg(long = "stream", overrides_with = "two_pass")]
+    pub stream: bool,
+
+    /// Minimum milliseconds between progress updates from the search
+    #[arg(long = "status-interval", value_name = "MS", default_value = "100")]
+    pub status_interval_ms: u64,
+
+    /// Directory to search, overriding the guess made from positional arguments
+    #[arg(long = "root", value_name = "DIR")]
+    pub root: Option<String>,
+
+    /// Pattern to search for, overriding the guess made from positional arguments
+    #[arg(long = "pattern", value_name = "PAT", allow_hyphen_values = true)]
+    pub pattern: Option<String>,
+
+    /// Path to search in (default: current dir if only pattern given)
+    #[arg(index = 1, required_unless_present_any = ["stats_only", "root", "pattern", "preset", "list_presets", "get", "history", "again"])]
+    pub path_or_pattern: Option<String>,
+
+    /// Pattern to search for (required if path is given)
+    #[arg(index = 2)]
+    pub maybe_pattern: Option<String>,
+}
+
+/// Color output setting
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when the terminal supports it
+    Auto,
+    /// Always emit colors
+    Always,
+    /// Never emit colors
+    Never,
+}
+
+/// Hyperlink output setting
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HyperlinkChoice {
+    /// Link results when stdout is a terminal
+    Auto,
+    /// Always link results
+    Always,
+    /// Never link results
+    Never,
+}
+
+/// Output format for results
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One path per line
+    Text,
+    /// Comma-separated rows with a header and file metadata
+    Csv,
+    /// Tab-separated rows with a header and file metadata
+    Tsv,
+    /// One compact JSON object per line with file metadata
+    JsonLines,
+    /// One rendered template line per result
+    Template(Template),
+}
+
+/// Parse a color name as understood by the `colored` crate
+fn parse_color(value: &str) -> Result<Color, String> {
+    value.parse().map_err(|()| {
+        format!("unknown color `{value}` (try blue, cyan, magenta, or \"bright blue\")")
+    })
+}
+
+/// Parse a human-friendly duration such as `90s`, `2h 30m`, or `7days`
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    humantime::parse_duration(value).map_err(|e| e.to_string())
+}
+
+/// Parse `--format`, treating anything other than a format name as a template
+fn parse_format(value: &str) -> Result<OutputFormat, String> {
+    match value {
+        "text" => Ok(OutputFormat::Text),
+        "csv" => Ok(OutputFormat::Csv),
+        "tsv" => Ok(OutputFormat::Tsv),
+        "jsonl" => Ok(OutputFormat::JsonLines),
+        _ if value.contains('{') => Template::parse(value).map(OutputFormat::Template),
+        _ => Err(format!(
+            "expected text, csv, tsv, jsonl, or a template with {{placeholders}}, got `{value}`"
+        )),
+    }
+}
+
+/// Key for the match breakdown printed after the search
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    /// File extension, with extensionless matches under "(none)"
+    Ext,
+    /// Directory containing the match
+    Dir,
+}
+
+/// Order of directory headers printed by `--group`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupSort {
+    /// Alphabetically by directory path
+    Name,
+    /// Directories with the most matches first
+    Count,
+}
+
+/// Part of a displayed path rewritten by `--replace`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplaceScope {
+    /// The whole path as it would be printed
+    Path,
+    /// Only the final component, keeping the directory as is
+    Name,
+}
+
+impl ColorChoice {
+    /// Apply this setting to all colored output
+    pub fn apply(self) {
+        match self {
+            // Piped output stays plain so downstream tools don't see escape codes
+            ColorChoice::Auto if !std::io::stdout().is_terminal() => {
+                colored::control::set_override(false)
+            }
+            ColorChoice::Auto => colored::control::unset_override(),
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+        }
+    }
+}
+
+impl HyperlinkChoice {
+    /// Whether results should be linked, falling back to plain paths when piped
+    pub fn enabled(self) -> bool {
+        self.enabled_for(std::io::stdout().is_terminal())
+    }
+
+    /// Whether results should be linked when stdout is or isn't a terminal
+    fn enabled_for(self, terminal: bool) -> bool {
+        match self {
+            HyperlinkChoice::Auto => terminal,
+            HyperlinkChoice::Always => true,
+            HyperlinkChoice::Never => false,
+        }
+    }
+}
+
+/// Why a combination of options was rejected by [`Args::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgsError {
+    ZeroExecJobs,
+    ZeroRecent,
+    CountAllWithoutLimit,
+    /// More paths and patterns than `--root`, `--pattern`, and the positionals leave room for
+    TooManyPaths,
+    IgnoreCasePathUnused,
+    ZeroThreads,
+    /// `--min-depth` deeper than the walk goes, so nothing could match
+    MinDepthBeyondMax,
+    /// A flag whose cargo feature was left out of the build
+    Unavailable {
+        flag: &'static str,
+        feature: &'static str,
+    },
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::ZeroExecJobs => write!(f, "--exec-jobs must be at least 1"),
+            ArgsError::ZeroRecent => write!(f, "--recent must be at least 1 to print anything"),
+            ArgsError::CountAllWithoutLimit => {
+                write!(f, "--count-all-with-limit needs a non-zero --limit")
+            }
+            ArgsError::TooManyPaths => write!(
+                f,
+                "too many paths and patterns: with --root or --pattern, give at most one positional argument for the other"
+            ),
+            ArgsError::IgnoreCasePathUnused => {
+                write!(
+                    f,
+                    "--ignore-case-path only applies with --path or --relative-match"
+                )
+            }
+            ArgsError::ZeroThreads => write!(f, "--threads must be at least 1"),
+            ArgsError::MinDepthBeyondMax => {
+                write!(f, "--min-depth can't be deeper than --max-depth")
+            }
+            ArgsError::Unavailable { flag, feature } => write!(
+                f,
+                "{flag} isn't available: seek was built without the `{feature}` feature"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+impl Args {
+    /// Parse command line arguments and resolve the path and pattern
+    pub fn parse_args() -> (Self, String, String, Interpretation) {
+        print_completions_if_requested();
+        let argv = with_default_opts(std::env::args_os().collect())
+            .unwrap_or_else(|e| Self::command().error(ErrorKind::InvalidValue, e).exit());
+        let matches = Self::command().get_matches_from(argv);
+        let mut args = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        if args.list_presets {
+            Preset::print_table();
+            std::process::exit(0);
+        }
+        if let Err(e) = args.load_config(&matches) {
+            Self::command().error(ErrorKind::InvalidValue, e).exit();
+        }
+        match args.validate() {
+            Ok(warnings) => {
+                // Quiet mode answers with its exit status alone
+                for warning in warnings.into_iter().filter(|_| !args.quiet) {
+                    eprintln!("Warning: {warning}");
+                }
+            }
+            Err(e) => Self::command().error(ErrorKind::ValueValidation, e).exit(),
+        }
+        let (path, pattern, interpretation) = resolve_path_pattern(
+            args.path_or_pattern.as_deref(),
+            args.maybe_pattern.as_deref(),
+            args.root.as_deref(),
+            args.pattern.as_deref(),
+            |path| Path::new(path).is_dir(),
+        );
+        if args.verbosity() >= 1 {
+            match interpretation {
+                Interpretation::DirectoryOnly => eprintln!(
+                    "Searching everything in directory `{path}` (use --pattern {path} to search for it by name)"
+                ),
+                Interpretation::PatternOnly => eprintln!(
+                    "Searching for pattern `{pattern}` in {path} (use --root to give a directory explicitly)"
+                ),
+                _ => {}
+            }
+        }
+
+        (args, path, pattern, interpretation)
+    }
+
+    /// Apply the config file beneath the options given on the command line
+    fn load_config(&mut self, matches: &ArgMatches) -> Result<(), String> {
+        if self.no_config {
+            return Ok(());
+        }
+        let config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => match Config::default_path().filter(|path| path.is_file()) {
+                Some(path) => Config::load(&path)?,
+                None => return Ok(()),
+            },
+        };
+        config.apply(self, matches);
+        Ok(())
+    }
+
+    /// Reject option values that can never produce a useful search, returning
+    /// warnings for ones that are allowed but probably not what was meant
+    pub fn validate(&self) -> Result<Vec<String>, ArgsError> {
+        if self.exec.is_some() && self.exec_jobs == 0 {
+            return Err(ArgsError::ZeroExecJobs);
+        }
+        if self.recent == Some(0) {
+            return Err(ArgsError::ZeroRecent);
+        }
+        if self.count_all_with_limit && self.limit == 0 {
+            return Err(ArgsError::CountAllWithoutLimit);
+        }
+
+        let positionals = [&self.path_or_pattern, &self.maybe_pattern]
+            .into_iter()
+            .filter(|arg| arg.is_some())
+            .count();
+        let named = [&self.root, &self.pattern]
+            .into_iter()
+            .filter(|arg| arg.is_some())
+            .count();
+        if named + positionals > 2 {
+            return Err(ArgsError::TooManyPaths);
+        }
+
+        if self.ignore_case_path && !(self.full_path || self.relative_match) {
+            return Err(ArgsError::IgnoreCasePathUnused);
+        }
+
+        if self.threads == Some(0) {
+            return Err(ArgsError::ZeroThreads);
+        }
+        if let (Some(min), Some(max)) = (self.min_depth, self.search_depth())
+            && min > max
+        {
+            return Err(ArgsError::MinDepthBeyondMax);
+        }
+        if self.pick && !cfg!(feature = "picker") {
+            return Err(ArgsError::Unavailable {
+                flag: "--pick",
+                feature: "picker",
+            });
+        }
+        if self.watch && !cfg!(feature = "watch") {
+            return Err(ArgsError::Unavailable {
+                flag: "--watch",
+                feature: "watch",
+            });
+        }
+
+        let mut warnings = Vec::new();
+        if self
+            .threads
+            .is_some_and(|threads| threads > MAX_USEFUL_THREADS)
+        {
+            warnings.push(format!(
+                "--threads above {MAX_USEFUL_THREADS} is unlikely to help and may exhaust file handles"
+            ));
+        }
+        if self.max_depth == Some(0) {
+            warnings.push(
+                "--max-depth 0 only examines the search path itself; use -D 1 to include its contents"
+                    .to_string(),
+            );
+        }
+        if self.max_files == Some(0) && self.timeout_seconds == 0 {
+            warnings.push(
+                "--max-files 0 with --timeout 0 searches without any limit and may run for a long time"
+                    .to_string(),
+            );
+        }
+        Ok(warnings)
+    }
+
+    /// Extensions from `--ext` and `--preset` together
+    pub fn extensions(&self) -> Vec<String> {
+        let preset = self.preset.map_or(&[][..], Preset::extensions);
+        self.extensions
+            .iter()
+            .cloned()
+            .chain(preset.iter().map(|ext| ext.to_string()))
+            .collect()
+    }
+
+    /// Whether only files are searched for, as `--preset` implies
+    pub fn files_only(&self) -> bool {
+        self.files_only || self.preset.is_some()
+    }
+
+    /// Threads to search with, defaulting to the available parallelism
+    pub fn threads(&self) -> usize {
+        self.threads.unwrap_or_else(|| {
+            std::thread::available_parallelism().map_or(1, |threads| threads.get())
+        })
+    }
+
+    /// Depth limit to walk with, where `--no-recursive` means depth 1 since
+    /// the walker counts the search root itself as depth 0
+    pub fn search_depth(&self) -> Option<usize> {
+        if self.no_recursive {
+            Some(1)
+        } else {
+            self.max_depth
+        }
+    }
+
+    /// Command given to `--exec` or `--exec-batch`
+    pub fn exec_command(&self) -> Option<&[String]> {
+        self.exec.as_deref().or(self.exec_batch.as_deref())
+    }
+
+    /// The file named by `--output`, unless it's `-` for stdout
+    pub fn output_file(&self) -> Option<&Path> {
+        self.output
+            .as_deref()
+            .filter(|path| path.as_os_str() != "-")
+    }
+
+    /// The `--replace` substitution as (old, new)
+    pub fn replacement(&self) -> Option<(&str, &str)> {
+        match self.replace.as_deref() {
+            Some([old, new]) => Some((old, new)),
+            _ => None,
+        }
+    }
+
+    /// Number of matches to print before stopping, where 0 means no limit
+    pub fn match_limit(&self) -> usize {
+        if self.first { 1 } else { self.limit }
+    }
+
+    /// Maximum number of files to scan, falling back to the default when not given
+    pub fn file_limit(&self) -> usize {
+        self.max_files.unwrap_or(DEFAULT_MAX_FILES)
+    }
+
+    /// The search settings given on the command line
+    pub fn to_search_options(&self) -> SearchOptions {
+        SearchOptions {
+            max_depth: self.search_depth(),
+            min_depth: self.min_depth,
+            max_files: self.file_limit(),
+            max_matches: self.match_limit(),
+            count_all_matches: self.count_all_with_limit,
+            timeout: (self.timeout_seconds > 0).then(|| Duration::from_secs(self.timeout_seconds)),
+            follow_links: self.follow,
+            files_only: self.files_only(),
+            dirs_only: self.dirs_only,
+            hidden: !self.no_hidden,
+            show_permission_errors: self.show_permission_errors,
+            collect_metrics: self.stats,
+            verbosity: self.verbosity(),
+            channel_capacity: self.channel_capacity,
+            status_interval: Duration::from_millis(self.status_interval_ms),
+            breadth_first: self.breadth_first,
+            stats_only: self.stats_only,
+            threads: self.threads(),
+            depth_below_match: self.max_depth_from_match,
+            two_pass: self.two_pass,
+            include_root: self.include_root,
+            // Only the summary shows the total, so skip the stats when it isn't printed
+            sum_matched_bytes: !(self.quiet || self.pick || self.count || self.stats_only),
+        }
+    }
+
+    /// How the pattern treats case, where an explicit `-i` or `-s` beats
+    /// `--smart-case` from the config file
+    pub fn case_mode(&self) -> CaseMode {
+        if self.ignore_case {
+            CaseMode::Insensitive
+        } else if self.case_sensitive {
+            CaseMode::Sensitive
+        } else if self.smart_case {
+            CaseMode::Smart
+        } else {
+            CaseMode::Sensitive
+        }
+    }
+
+    /// Hard-link threshold from `--min-links` or `--links-only`
+    pub fn min_links(&self) -> Option<u64> {
+        if self.links_only {
+            Some(2)
+        } else {
+            self.min_links
+        }
+    }
+
+    /// How result paths should be printed
+    pub fn path_style(&self) -> PathStyle {
+        if self.relative {
+            PathStyle::Relative
+        } else if self.absolute {
+            PathStyle::Absolute
+        } else {
+            PathStyle::AsWalked
+        }
+    }
+
+    /// Verbosity level for entry tracing, with --debug implying -vv
+    pub fn verbosity(&self) -> u8 {
+        if self.debug {
+            self.verbose.max(2)
+        } else {
+            self.verbose
+        }
+    }
+
+    /// Whether a pattern was given on the command line rather than defaulted
+    pub fn has_explicit_pattern(&self) -> bool {
+        let (_, _, interpretation) = resolve_path_pattern(
+            self.path_or_pattern.as_deref(),
+            self.maybe_pattern.as_deref(),
+            self.root.as_deref(),
+            self.pattern.as_deref(),
+            |path| Path::new(path).is_dir(),
+        );
+        interpretation.has_pattern()
+    }
+}
+
+/// Environment variable holding options applied before the command line's own
+pub const DEFAULT_OPTS_VAR: &str = "SEEK_DEFAULT_OPTS";
+
+/// Insert the words of `SEEK_DEFAULT_OPTS` after the program name, so that
+/// the same flags given on the command line come later and take precedence
+fn with_default_opts(argv: Vec<OsString>) -> Result<Vec<OsString>, String> {
+    prepend_default_opts(argv, std::env::var_os(DEFAULT_OPTS_VAR))
+}
+
+/// [`with_default_opts`] with the variable's value passed in
+pub(crate) fn prepend_default_opts(
+    mut argv: Vec<OsString>,
+    value: Option<OsString>,
+) -> Result<Vec<OsString>, String> {
+    let Some(value) = value else {
+        return Ok(argv);
+    };
+    let value = value
+        .into_string()
+        .map_err(|_| format!("{DEFAULT_OPTS_VAR} is not valid UTF-8"))?;
+    let words = split_words(&value).map_err(|e| format!("{DEFAULT_OPTS_VAR}: {e}"))?;
+    if words.is_empty() {
+        return Ok(argv);
+    }
+
+    // Check the variable alone so mistakes in it aren't blamed on the command line
+    let alone = std::iter::once("seek".to_string()).chain(words.iter().cloned());
+    if let Err(e) = Args::command().try_get_matches_from(alone)
+        && !matches!(
+            e.kind(),
+            ErrorKind::MissingRequiredArgument | ErrorKind::DisplayHelp | ErrorKind::DisplayVersion
+        )
+    {
+        let message = e.to_string();
+        let first_line = message.lines().next().unwrap_or_default();
+        return Err(format!(
+            "{DEFAULT_OPTS_VAR}: {}",
+            first_line.trim_start_matches("error: ")
+        ));
+    }
+
+    let program = if argv.is_empty() {
+        OsString::from("seek")
+    } else {
+        argv.remove(0)
+    };
+    Ok(std::iter::once(program)
+        .chain(words.into_iter().map(OsString::from))
+        .chain(argv)
+        .collect())
+}
+
+/// Split a string into words like a POSIX shell: whitespace separates words,
+/// single quotes are literal, and double quotes allow `\"` and `\\` escapes
+pub fn split_words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("unbalanced single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("unbalanced double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("unbalanced double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some(c) => current.push(c),
+                    None => return Err("trailing backslash".to_string()),
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// Print a shell completion script and exit when run as `seek completions <SHELL>`,
+/// unless `completions` names an existing path to search
+fn print_completions_if_requested() {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    let [command, shell] = argv.as_slice() else {
+        return;
+    };
+    if command != "completions" || Path::new(command).exists() {
+        return;
+    }
+    match Shell::from_str(shell, true) {
+        Ok(shell) => {
+            clap_complete::generate(shell, &mut Args::command(), "seek", &mut std::io::stdout());
+            std::process::exit(0);
+        }
+        Err(_) => Args::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("unknown shell `{shell}` for completions: expected bash, zsh, fish, elvish, or powershell"),
+            )
+            .exit(),
+    }
+}
+
+/// How the search path and pattern were worked out from the arguments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpretation {
+    /// `--root` or `--pattern` was given, with any positional filling in the other
+    Named,
+    /// Only `--root` was given, so everything under it matches
+    RootOnly,
+    /// Two positionals: a path followed by a pattern
+    PathAndPattern,
+    /// A single positional naming an existing directory, searched for everything
+    DirectoryOnly,
+    /// A single positional that isn't a directory, searched for in the current one
+    PatternOnly,
+    /// No path or pattern at all, as with `--stats-only`
+    Default,
+}
+
+impl Interpretation {
+    /// Whether the pattern came from the arguments rather than defaulting to `*`
+    pub fn has_pattern(self) -> bool {
+        matches!(
+            self,
+            Interpretation::Named | Interpretation::PathAndPattern | Interpretation::PatternOnly
+        )
+    }
+}
+
+/// Work out the search path and pattern from the positionals and the `--root`
+/// and `--pattern` flags, where named flags always win over guessing
+pub fn resolve_path_pattern(
+    first: Option<&str>,
+    second: Option<&str>,
+    root: Option<&str>,
+    pattern: Option<&str>,
+    is_dir: impl Fn(&str) -> bool,
+) -> (String, String, Interpretation) {
+    match (root, pattern) {
+        (Some(root), Some(pattern)) => (root.into(), pattern.into(), Interpretation::Named),
+        (Some(root), None) => match first {
+            Some(pattern) => (root.into(), pattern.into(), Interpretation::Named),
+            None => (root.into(), "*".into(), Interpretation::RootOnly),
+        },
+        (None, Some(pattern)) => (
+            first.unwrap_or(".").into(),
+            pattern.into(),
+            Interpretation::Named,
+        ),
+        (None, None) => match (first, second) {
+            (Some(path), Some(pattern)) => {
+                (path.into(), pattern.into(), Interpretation::PathAndPattern)
+            }
+            (Some(first), None) if is_dir(first) => {
+                (first.into(), "*".into(), Interpretation::DirectoryOnly)
+            }
+            (Some(first), None) => (".".into(), first.into(), Interpretation::PatternOnly),
+            (None, _) => (".".into(), "*".into(), Interpretation::Default),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_from(std::iter::once("seek").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn conflicting_flags_are_rejected() {
+        for args in [
+            &["--first", "--limit", "5", "x"][..],
+            &["--no-recursive", "--max-depth", "2", "x"],
+            &["--quiet", "--watch", "x"],
+            &["--relative", "--absolute", "x"],
+            &["--files-only", "--dirs-only", "x"],
+            &["--print0", "--format", "csv", "x"],
+        ] {
+            let err = parse(args)
+                .err()
+                .unwrap_or_else(|| panic!("{args:?} parsed"));
+            assert_eq!(err.kind(), ErrorKind::ArgumentConflict, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_useless_values() {
+        for (args, expected) in [
+            (&["--threads", "0", "x"][..], ArgsError::ZeroThreads),
+            (&["--recent", "0", "x"], ArgsError::ZeroRecent),
+            (
+                &["--limit", "0", "--count-all-with-limit", "x"],
+                ArgsError::CountAllWithoutLimit,
+            ),
+            (
+                &["--exec-jobs", "0", "x", "--exec", "echo"],
+                ArgsError::ZeroExecJobs,
+            ),
+            (
+                &["--root", "a", "--pattern", "b", "c"],
+                ArgsError::TooManyPaths,
+            ),
+            (
+                &["--ignore-case-path", "x"],
+                ArgsError::IgnoreCasePathUnused,
+            ),
+            (
+                &["--min-depth", "2", "-R", "x"],
+                ArgsError::MinDepthBeyondMax,
+            ),
+        ] {
+            let args = parse(args).unwrap();
+            assert_eq!(args.validate(), Err(expected));
+        }
+    }
+
+    #[test]
+    fn each_flag_lands_on_its_search_option() {
+        let defaults = parse(&["x"]).unwrap().to_search_options();
+        assert_eq!(defaults.max_files, DEFAULT_MAX_FILES);
+        assert_eq!(defaults.timeout, Some(Duration::from_secs(600)));
+        assert!(defaults.hidden && defaults.sum_matched_bytes);
+
+        type Change = fn(&mut SearchOptions);
+        let cases: [(&[&str], Change); 23] = [
+            (&["-D", "3"], |o| o.max_depth = Some(3)),
+            (&["--no-recursive"], |o| o.max_depth = Some(1)),
+            (&["--min-depth", "2"], |o| o.min_depth = Some(2)),
+            (&["-n", "50"], |o| o.max_files = 50),
+            (&["-m", "4"], |o| o.max_matches = 4),
+            (&["--first"], |o| o.max_matches = 1),
+            (&["-m", "4", "--count-all-with-limit"], |o| {
+                o.max_matches = 4;
+                o.count_all_matches = true;
+            }),
+            (&["-t", "0"], |o| o.timeout = None),
+            (&["-L"], |o| o.follow_links = true),
+            (&["-f"], |o| o.files_only = true),
+            (&["--preset", "images"], |o| o.files_only = true),
+            (&["-d"], |o| o.dirs_only = true),
+            (&["--no-hidden"], |o| o.hidden = false),
+            (&["-e"], |o| o.show_permission_errors = true),
+            (&["--stats"], |o| o.collect_metrics = true),
+            (&["-vv"], |o| o.verbosity = 2),
+            (&["--debug"], |o| o.verbosity = 2),
+            (&["--channel-capacity", "7"], |o| o.channel_capacity = 7),
+            (&["--status-interval", "5"], |o| {
+                o.status_interval = Duration::from_millis(5)
+            }),
+            (&["--breadth-first"], |o| o.breadth_first = true),
+            (&["--stats-only"], |o| {
+                o.stats_only = true;
+                o.sum_matched_bytes = false;
+            }),
+            (&["-j", "3"], |o| o.threads = 3),
+            (
+                &[
+                    "--max-depth-from-match",
+                    "1",
+                    "--two-pass",
+                    "--include-root",
+                ],
+                |o| {
+                    o.depth_below_match = Some(1);
+                    o.two_pass = true;
+                    o.include_root = true;
+                },
+            ),
+        ];
+        for (flags, change) in cases {
+            let args = parse(&[flags, &["x"]].concat()).unwrap();
+            let mut expected = defaults.clone();
+            change(&mut expected);
+            assert_eq!(args.to_search_options(), expected, "{flags:?}");
+        }
+
+        for flags in [&["-q"][..], &["-c"], &["--pick"]] {
+            let args = parse(&[flags, &["x"]].concat()).unwrap();
+            assert!(!args.to_search_options().sum_matched_bytes, "{flags:?}");
+        }
+    }
+
+    #[test]
+    fn the_last_case_flag_wins() {
+        for (flags, expected) in [
+            (&[][..], CaseMode::Sensitive),
+            (&["-S"], CaseMode::Smart),
+            (&["-S", "-i"], CaseMode::Insensitive),
+            (&["-i", "-S"], CaseMode::Smart),
+            (&["-i", "-s"], CaseMode::Sensitive),
+            (&["--smart-case", "--case-sensitive"], CaseMode::Sensitive),
+        ] {
+            let args = parse(&[flags, &["x"]].concat()).unwrap();
+            assert_eq!(args.case_mode(), expected, "{flags:?}");
+        }
+
+        // `smart_case = true` from the config file doesn't beat an explicit flag
+        for (flag, expected) in [("-s", CaseMode::Sensitive), ("-i", CaseMode::Insensitive)] {
+            let mut args = parse(&[flag, "x"]).unwrap();
+            args.smart_case = true;
+            assert_eq!(args.case_mode(), expected, "{flag}");
+        }
+    }
+
+    #[test]
+    fn threads_default_to_the_available_parallelism() {
+        let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let args = parse(&["x"]).unwrap();
+        assert_eq!(args.threads(), available);
+        assert_eq!(args.to_search_options().threads, available);
+        for (flag, threads) in [(&["-j", "3"][..], 3), (&["--threads", "1"], 1)] {
+            let args = parse(&[flag, &["x"]].concat()).unwrap();
+            assert_eq!(args.threads(), threads, "{flag:?}");
+            assert_eq!(args.to_search_options().threads, threads, "{flag:?}");
+        }
+    }
+
+    #[test]
+    fn links_only_means_at_least_two_links() {
+        assert_eq!(parse(&["x"]).unwrap().min_links(), None);
+        assert_eq!(parse(&["--links-only", "x"]).unwrap().min_links(), Some(2));
+        assert_eq!(
+            parse(&["--min-links", "5", "x"]).unwrap().min_links(),
+            Some(5)
+        );
+        assert!(parse(&["--links-only", "--min-links", "5", "x"]).is_err());
+    }
+
+    #[test]
+    fn presets_add_their_extensions_to_ext() {
+        let args = parse(&["--preset", "archives", "--ext", "rs,toml", "x"]).unwrap();
+        let extensions = args.extensions();
+        assert_eq!(extensions[..2], ["rs", "toml"]);
+        assert_eq!(extensions[2..], *Preset::Archives.extensions());
+        assert!(args.files_only());
+
+        let args = parse(&["--ext", "rs", "x"]).unwrap();
+        assert_eq!(args.extensions(), ["rs"]);
+        assert!(!args.files_only());
+    }
+
+    #[test]
+    fn presets_are_checked_by_name() {
+        let args = parse(&["--preset", "code"]).unwrap();
+        assert_eq!(args.preset, Some(Preset::Code));
+        assert!(args.extensions().iter().any(|ext| ext == "rs"));
+
+        let err = parse(&["--preset", "pictures", "x"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("images"), "{err}");
+        let err = parse(&["--preset", "code", "--dirs-only", "x"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn preset_extensions_are_bare_and_distinct() {
+        for preset in Preset::value_variants() {
+            let extensions = preset.extensions();
+            for ext in extensions {
+                assert!(
+                    !ext.is_empty() && !ext.starts_with('.') && *ext == ext.to_ascii_lowercase(),
+                    "{preset:?}: {ext}"
+                );
+            }
+            let distinct: std::collections::HashSet<_> = extensions.iter().collect();
+            assert_eq!(distinct.len(), extensions.len(), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn hyperlinks_are_off_when_piped_unless_forced() {
+        for (args, terminal, piped) in [
+            (&["x"][..], false, false),
+            (&["--hyperlink", "x"], true, false),
+            (&["--hyperlink=auto", "x"], true, false),
+            (&["--hyperlink=always", "x"], true, true),
+            (&["--hyperlink=never", "x"], false, false),
+        ] {
+            let choice = parse(args).unwrap().hyperlink;
+            assert_eq!(choice.enabled_for(true), terminal, "{args:?}");
+            assert_eq!(choice.enabled_for(false), piped, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn validate_warns_about_suspicious_values() {
+        let args = parse(&["--max-depth", "0", "x"]).unwrap();
+        assert_eq!(args.validate().unwrap().len(), 1);
+        let args = parse(&["--threads", "4", "x"]).unwrap();
+        assert_eq!(args.validate(), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn validate_error_messages_name_the_flag() {
+        assert_eq!(
+            ArgsError::Unavailable {
+                flag: "--pick",
+                feature: "picker"
+            }
+            .to_string(),
+            "--pick isn't available: seek was built without the `picker` feature"
+        );
+        assert_eq!(
+            ArgsError::ZeroThreads.to_string(),
+            "--threads must be at least 1"
+        );
+    }
+
+    #[test]
+    fn positionals_are_told_apart_by_what_exists() {
+        use Interpretation::*;
+        let is_dir = |path: &str| ["src", "/tmp", "my dir"].contains(&path);
+        for ((first, second, root, pattern), expected) in [
+            // An existing directory is searched for everything
+            ((Some("src"), None, None, None), ("src", "*", DirectoryOnly)),
+            (
+                (Some("my dir"), None, None, None),
+                ("my dir", "*", DirectoryOnly),
+            ),
+            // Anything else is a pattern, even when it looks like a path
+            ((Some("*.rs"), None, None, None), (".", "*.rs", PatternOnly)),
+            (
+                (Some("src/*.rs"), None, None, None),
+                (".", "src/*.rs", PatternOnly),
+            ),
+            (
+                (Some("/no/such/dir"), None, None, None),
+                (".", "/no/such/dir", PatternOnly),
+            ),
+            // Two positionals are always a path and a pattern, existing or not
+            (
+                (Some("src"), Some("*.rs"), None, None),
+                ("src", "*.rs", PathAndPattern),
+            ),
+            (
+                (Some("missing"), Some("src"), None, None),
+                ("missing", "src", PathAndPattern),
+            ),
+            (
+                (Some("*.rs"), Some("src"), None, None),
+                ("*.rs", "src", PathAndPattern),
+            ),
+            ((None, None, None, None), (".", "*", Default)),
+            // Named flags win, with a positional filling in the other
+            (
+                (Some("src"), None, None, Some("*.rs")),
+                ("src", "*.rs", Named),
+            ),
+            (
+                (Some("*.rs"), None, Some("/tmp"), None),
+                ("/tmp", "*.rs", Named),
+            ),
+            ((None, None, Some("/tmp"), None), ("/tmp", "*", RootOnly)),
+            ((None, None, None, Some("x")), (".", "x", Named)),
+            ((None, None, Some("/tmp"), Some("x")), ("/tmp", "x", Named)),
+        ] {
+            let (path, found, interpretation) =
+                resolve_path_pattern(first, second, root, pattern, is_dir);
+            assert_eq!(
+                (path.as_str(), found.as_str(), interpretation),
+                expected,
+                "{first:?} {second:?} --root {root:?} --pattern {pattern:?}"
+            );
+        }
+    }
+
+    fn words(text: &str) -> Vec<String> {
+        split_words(text).unwrap()
+    }
+
+    #[test]
+    fn split_words_on_whitespace() {
+        assert_eq!(words("  -m 5\t--hidden\n"), ["-m", "5", "--hidden"]);
+        assert!(words("   ").is_empty());
+    }
+
+    #[test]
+    fn split_words_keeps_quoted_segments_together() {
+        assert_eq!(
+            words(r"--format '{size}\t{path}'"),
+            ["--format", r"{size}\t{path}"]
+        );
+        assert_eq!(
+            words(r#"--pattern "my file" x"#),
+            ["--pattern", "my file", "x"]
+        );
+        assert_eq!(words(r#"a"b c"'d e'f"#), ["ab cd ef"]);
+        assert_eq!(words(r#""say \"hi\" \\ \n""#), [r#"say "hi" \ \n"#]);
+        assert_eq!(words(r"'it''s' a\ b"), ["its", "a b"]);
+        assert_eq!(words("''"), [""]);
+    }
+
+    #[test]
+    fn split_words_rejects_unbalanced_quotes() {
+        assert_eq!(
+            split_words("--pattern 'open"),
+            Err("unbalanced single quote".to_string())
+        );
+        assert_eq!(
+            split_words(r#"--pattern "open \""#),
+            Err("unbalanced double quote".to_string())
+        );
+        assert_eq!(split_words("x\\"), Err("trailing backslash".to_string()));
+    }
+
+    #[test]
+    fn command_line_overrides_default_opts() {
+        let argv = ["seek", "--max-files", "5", "x"]
+            .map(OsString::from)
+            .to_vec();
+        let env = Some(OsString::from("--max-files 100 --smart-case"));
+        let argv = prepend_default_opts(argv, env).unwrap();
+        let args = Args::try_parse_from(argv).unwrap();
+        assert_eq!(args.max_files, Some(5));
+        assert!(args.smart_case);
+    }
+
+    #[test]
+    fn bad_default_opts_are_blamed_on_the_variable() {
+        let argv = vec![OsString::from("seek"), OsString::from("x")];
+        let err = prepend_default_opts(argv, Some(OsString::from("--no-such-flag"))).unwrap_err();
+        assert!(err.starts_with("SEEK_DEFAULT_OPTS: "), "{err}");
+    }
+}
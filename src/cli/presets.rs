@@ -0,0 +1,58 @@
+use clap::ValueEnum;
+
+/// Named sets of extensions for common kinds of files
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// Photos and other images
+    Images,
+    /// Video files
+    Videos,
+    /// Music and other audio
+    Audio,
+    /// Text documents, spreadsheets, and slides
+    Documents,
+    /// Source code
+    Code,
+    /// Compressed archives and disk images
+    Archives,
+}
+
+impl Preset {
+    /// Extensions matched by this preset, without the leading dot
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Preset::Images => &[
+                "jpg", "jpeg", "png", "gif", "webp", "heic", "heif", "svg", "bmp", "tif", "tiff",
+                "ico", "raw", "cr2", "nef", "arw", "dng",
+            ],
+            Preset::Videos => &[
+                "mp4", "m4v", "mkv", "mov", "avi", "wmv", "webm", "flv", "mpg", "mpeg", "3gp",
+            ],
+            Preset::Audio => &[
+                "mp3", "m4a", "aac", "flac", "wav", "ogg", "opus", "wma", "aiff", "alac",
+            ],
+            Preset::Documents => &[
+                "pdf", "doc", "docx", "odt", "rtf", "txt", "md", "xls", "xlsx", "ods", "csv",
+                "ppt", "pptx", "odp", "epub",
+            ],
+            Preset::Code => &[
+                "rs", "c", "h", "cc", "cpp", "hpp", "cs", "go", "java", "kt", "swift", "py", "rb",
+                "js", "jsx", "ts", "tsx", "php", "sh", "lua", "scala", "hs", "ex", "exs", "zig",
+            ],
+            Preset::Archives => &[
+                "zip", "tar", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar", "iso", "dmg",
+            ],
+        }
+    }
+
+    /// Print every preset with its extensions
+    pub fn print_table() {
+        for preset in Preset::value_variants() {
+            let name = preset
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default();
+            println!("{name:<10} {}", preset.extensions().join(", "));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use clap::ArgMatches;
+use clap::ValueEnum;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{Args, ColorChoice};
+
+/// Defaults read from the config file, applied beneath command-line flags
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Same as `--max-files`
+    pub max_files: Option<usize>,
+    /// Same as `--timeout`, in seconds
+    pub timeout: Option<u64>,
+    /// Patterns to exclude, as if listed in an `--exclude-file`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Directory names or globs to skip without descending into them
+    #[serde(default)]
+    pub prune: Vec<String>,
+    /// Same as `--color`
+    pub color: Option<String>,
+    /// Same as `--smart-case`
+    pub smart_case: Option<bool>,
+    /// Set to false to stop recording searches in the history
+    pub history: Option<bool>,
+}
+
+impl Config {
+    /// Where the config file lives when `--config` isn't given
+    pub fn default_path() -> Option<PathBuf> {
+        if cfg!(windows) {
+            let appdata = std::env::var_os("APPDATA")?;
+            return Some(PathBuf::from(appdata).join("seek").join("config.toml"));
+        }
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_home.join("seek").join("config.toml"))
+    }
+
+    /// Read and check a config file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read config file {}: {e}", path.display()))?;
+        Self::parse(&text, path)
+    }
+
+    /// Parse config file contents, naming `path` and the offending key in errors
+    pub fn parse(text: &str, path: &Path) -> Result<Self, String> {
+        let config: Config = toml::from_str(text)
+            .map_err(|e| format!("Invalid config file {}: {e}", path.display()))?;
+        if let Some(color) = &config.color
+            && ColorChoice::from_str(color, true).is_err()
+        {
+            return Err(format!(
+                "Invalid config file {}: `color` must be auto, always, or never, got `{color}`",
+                path.display()
+            ));
+        }
+        Ok(config)
+    }
+
+    /// Fill in options that weren't given on the command line
+    pub fn apply(self, args: &mut Args, matches: &ArgMatches) {
+        let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        if !from_cli("max_files") && self.max_files.is_some() {
+            args.max_files = self.max_files;
+        }
+        if !from_cli("timeout_seconds")
+            && let Some(timeout) = self.timeout
+        {
+            args.timeout_seconds = timeout;
+        }
+        if !from_cli("color")
+            && let Some(color) = self
+                .color
+                .and_then(|c| ColorChoice::from_str(&c, true).ok())
+        {
+            args.color = color;
+        }
+        if !from_cli("smart_case")
+            && let Some(smart_case) = self.smart_case
+        {
+            args.smart_case = smart_case;
+        }
+        if self.history == Some(false) {
+            args.no_history = true;
+        }
+        args.config_excludes = self.exclude;
+        args.config_prunes = self.prune;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+    use std::ffi::OsString;
+
+    use super::*;
+    use crate::cli::prepend_default_opts;
+
+    /// Resolve options the way `Args::parse_args` does, from the command
+    /// line, a `SEEK_DEFAULT_OPTS` value, and config file contents
+    fn resolve(cli: &[&str], env: Option<&str>, config: &str) -> Args {
+        let argv = std::iter::once("seek")
+            .chain(cli.iter().copied())
+            .map(OsString::from)
+            .collect();
+        let argv = prepend_default_opts(argv, env.map(OsString::from)).unwrap();
+        let matches = Args::command().get_matches_from(argv);
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        Config::parse(config, Path::new("config.toml"))
+            .unwrap()
+            .apply(&mut args, &matches);
+        args
+    }
+
+    fn parse_err(config: &str) -> String {
+        Config::parse(config, Path::new("/home/me/.config/seek/config.toml")).unwrap_err()
+    }
+
+    #[test]
+    fn command_line_beats_env_beats_config() {
+        let config = "max_files = 10\ntimeout = 5\ncolor = \"never\"";
+
+        let args = resolve(&["x"], None, "");
+        assert_eq!((args.max_files, args.timeout_seconds), (None, 600));
+        assert_eq!(args.color, ColorChoice::Auto);
+
+        let args = resolve(&["x"], None, config);
+        assert_eq!((args.max_files, args.timeout_seconds), (Some(10), 5));
+        assert_eq!(args.color, ColorChoice::Never);
+
+        let args = resolve(&["x"], Some("--max-files 20 --color always"), config);
+        assert_eq!((args.max_files, args.timeout_seconds), (Some(20), 5));
+        assert_eq!(args.color, ColorChoice::Always);
+
+        let args = resolve(&["--max-files", "30", "x"], Some("--max-files 20"), config);
+        assert_eq!(args.max_files, Some(30));
+    }
+
+    #[test]
+    fn lists_and_switches_apply() {
+        let config = "exclude = [\"*.o\"]\nprune = [\".git\"]\nsmart_case = true\nhistory = false";
+        let args = resolve(&["x"], None, config);
+        assert_eq!(args.config_excludes, ["*.o"]);
+        assert_eq!(args.config_prunes, [".git"]);
+        assert!(args.smart_case);
+        assert!(args.no_history);
+    }
+
+    #[test]
+    fn bad_values_name_the_file_and_key() {
+        for (config, key) in [
+            ("max_files = \"lots\"", "max_files"),
+            ("timeout = -1", "timeout"),
+            ("prune = \".git\"", "prune"),
+            ("colour = \"never\"", "colour"),
+        ] {
+            let err = parse_err(config);
+            assert!(err.contains("/home/me/.config/seek/config.toml"), "{err}");
+            assert!(err.contains(key), "{err}");
+        }
+    }
+
+    #[test]
+    fn bad_color_lists_the_choices() {
+        assert_eq!(
+            parse_err("color = \"purple\""),
+            "Invalid config file /home/me/.config/seek/config.toml: \
+             `color` must be auto, always, or never, got `purple`"
+        );
+    }
+}
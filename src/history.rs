@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Searches kept in the history file; older ones are dropped once it grows
+/// to twice this many
+pub const MAX_ENTRIES: usize = 1000;
+
+/// One recorded search, stored as a single JSON line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Arguments after the program name, exactly as given
+    pub args: Vec<String>,
+    /// Working directory the search ran in, so `--again` resolves paths the same way
+    pub cwd: PathBuf,
+    pub path: String,
+    pub pattern: String,
+    pub matches: usize,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+impl Entry {
+    /// Record the current invocation
+    pub fn now(path: &str, pattern: &str, matches: usize) -> Self {
+        Entry {
+            args: std::env::args_os()
+                .skip(1)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            cwd: std::env::current_dir().unwrap_or_default(),
+            path: path.to_string(),
+            pattern: pattern.to_string(),
+            matches,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        }
+    }
+
+    /// When the search ran, as an RFC 3339 UTC timestamp
+    pub fn time(&self) -> String {
+        let time = UNIX_EPOCH + Duration::from_secs(self.timestamp);
+        humantime::format_rfc3339_seconds(time).to_string()
+    }
+
+    /// The invocation as it could be typed into a POSIX shell
+    pub fn command_line(&self) -> String {
+        let mut line = "seek".to_string();
+        for arg in &self.args {
+            line.push(' ');
+            line.push_str(&shell_quote(arg));
+        }
+        line
+    }
+}
+
+/// Quote `arg` for a POSIX shell when it has anything beyond plain characters
+fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Where the history lives
+pub fn default_path() -> Option<PathBuf> {
+    let state_home = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("LOCALAPPDATA")?)
+    } else {
+        std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".local").join("state"))
+            })?
+    };
+    Some(state_home.join("seek").join("history.jsonl"))
+}
+
+/// Add an entry to the end of the history.
+///
+/// The file stays exclusively locked while the line is appended and any
+/// trimming is done, so concurrent searches can't lose each other's entries.
+pub fn append(path: &Path, entry: &Entry) -> io::Result<()> {
+    append_keeping(path, entry, MAX_ENTRIES)
+}
+
+fn append_keeping(path: &Path, entry: &Entry, keep: usize) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    file.lock()?;
+    file.write_all(line.as_bytes())?;
+    trim(&mut file, keep)
+}
+
+/// Once the locked file holds twice `keep` lines, cut it down to the newest
+/// `keep` in place
+fn trim(file: &mut File, keep: usize) -> io::Result<()> {
+    let mut text = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut text)?;
+    let count = text.lines().count();
+    if count < keep * 2 {
+        return Ok(());
+    }
+    let mut kept = String::new();
+    for line in text.lines().skip(count - keep) {
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    // Writes in append mode land at the new end, the start of the file
+    file.set_len(0)?;
+    file.write_all(kept.as_bytes())
+}
+
+/// The newest `limit` entries, oldest first, skipping any line that isn't a
+/// complete entry
+pub fn recent(path: &Path, limit: usize) -> Result<Vec<Entry>, String> {
+    let read = || -> io::Result<String> {
+        let mut file = File::open(path)?;
+        // Wait out an append that may be trimming the file
+        file.lock_shared()?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        Ok(text)
+    };
+    let text = match read() {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Cannot read {}: {e}", path.display())),
+    };
+    let entries: Vec<Entry> = text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pattern: &str, args: &[&str]) -> Entry {
+        Entry {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: PathBuf::from("/work"),
+            path: ".".to_string(),
+            pattern: pattern.to_string(),
+            matches: 2,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn appended_entries_come_back_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state").join("history.jsonl");
+        assert_eq!(recent(&file, 5), Ok(Vec::new()));
+
+        let first = entry("*.rs", &[".", "*.rs"]);
+        let second = entry("*.md", &[".", "*.md"]);
+        append(&file, &first).unwrap();
+        append(&file, &second).unwrap();
+        assert_eq!(recent(&file, 5), Ok(vec![first, second.clone()]));
+        assert_eq!(recent(&file, 1), Ok(vec![second]));
+    }
+
+    #[test]
+    fn broken_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("history.jsonl");
+        let kept = entry("*.rs", &[]);
+        append(&file, &kept).unwrap();
+        let mut text = fs::read_to_string(&file).unwrap();
+        text.push_str("{\"args\":[\n");
+        fs::write(&file, text).unwrap();
+        assert_eq!(recent(&file, 5), Ok(vec![kept]));
+    }
+
+    #[test]
+    fn history_is_trimmed_to_the_newest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("history.jsonl");
+        for i in 0..7 {
+            append_keeping(&file, &entry(&i.to_string(), &[]), 4).unwrap();
+        }
+        // The eighth line brings the file to twice the limit
+        let lines = |file: &Path| fs::read_to_string(file).unwrap().lines().count();
+        assert_eq!(lines(&file), 7);
+        append_keeping(&file, &entry("7", &[]), 4).unwrap();
+        assert_eq!(lines(&file), 4);
+
+        let patterns: Vec<String> = recent(&file, 10)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.pattern)
+            .collect();
+        assert_eq!(patterns, ["4", "5", "6", "7"]);
+    }
+
+    #[test]
+    fn concurrent_appends_trim_consistently() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("history.jsonl");
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let file = &file;
+                scope.spawn(move || {
+                    for i in 0..25 {
+                        let pattern = format!("{thread}-{i}");
+                        append_keeping(file, &entry(&pattern, &[]), 10).unwrap();
+                    }
+                });
+            }
+        });
+        // Every 10th append from the 20th on trims back to 10, so the 100th
+        // leaves exactly 10 unless an append was lost or a trim clobbered one
+        let mut patterns: Vec<String> = recent(&file, 100)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.pattern)
+            .collect();
+        assert_eq!(fs::read_to_string(&file).unwrap().lines().count(), 10);
+        patterns.sort();
+        patterns.dedup();
+        assert_eq!(patterns.len(), 10);
+    }
+
+    #[test]
+    fn command_line_quotes_what_the_shell_would_split() {
+        let entry = entry("", &["/tmp/my files", "*.rs", "--max-depth=3", "it's", ""]);
+        assert_eq!(
+            entry.command_line(),
+            r"seek '/tmp/my files' '*.rs' --max-depth=3 'it'\''s' ''"
+        );
+        assert_eq!(shell_quote("src/main.rs"), "src/main.rs");
+        assert_eq!(shell_quote("a;b"), "'a;b'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn time_is_utc_rfc3339() {
+        let mut entry = entry("", &[]);
+        entry.timestamp = 86_400 + 3661;
+        assert_eq!(entry.time(), "1970-01-02T01:01:01Z");
+    }
+}
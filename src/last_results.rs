@@ -0,0 +1,160 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Most results saved for `--get`; numbering goes on past this, but later
+/// results can't be fetched again
+pub const MAX_SAVED: usize = 10_000;
+
+/// Where the results of the last `--numbers` search are kept
+pub fn default_path() -> Option<PathBuf> {
+    let cache_home = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("LOCALAPPDATA")?)
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?
+    };
+    Some(cache_home.join("seek").join("last-results"))
+}
+
+/// Replace the saved results with `results`, one path per line.
+///
+/// The list is written to a temporary file beside `path` and renamed over it,
+/// so a concurrent `--get` sees either the old list or the new one.
+pub fn save(path: &Path, results: &[PathBuf]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension(format!("tmp.{}", std::process::id()));
+    let write = || -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(&temp)?);
+        for result in results.iter().take(MAX_SAVED) {
+            // An empty line keeps the numbering for a name that can't go on one line
+            let line = result.display().to_string();
+            writeln!(file, "{}", if line.contains('\n') { "" } else { &line })?;
+        }
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()
+    };
+    let written = write().and_then(|()| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+/// The saved result numbered `index`, counting from 1, if it still exists
+pub fn get(path: &Path, index: usize) -> Result<PathBuf, String> {
+    if index == 0 {
+        return Err("results are numbered from 1".to_string());
+    }
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err("no saved results; run a search with --numbers first".to_string());
+        }
+        Err(e) => return Err(format!("Cannot read {}: {e}", path.display())),
+    };
+    let count = text.lines().count();
+    let result = text
+        .lines()
+        .nth(index - 1)
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            format!("no result {index}; the last search with --numbers saved {count}")
+        })?;
+    if result.as_os_str().is_empty() {
+        return Err(format!(
+            "result {index} has a newline in its name and wasn't saved"
+        ));
+    }
+    if fs::symlink_metadata(&result).is_err() {
+        return Err(format!(
+            "result {index} ({}) no longer exists; run the search again",
+            result.display()
+        ));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_returns_saved_results_by_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("cache").join("last-results");
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        fs::write(&first, "").unwrap();
+        fs::write(&second, "").unwrap();
+
+        save(&list, &[first.clone(), second.clone()]).unwrap();
+        assert_eq!(get(&list, 1), Ok(first));
+        assert_eq!(get(&list, 2), Ok(second));
+        assert_eq!(
+            get(&list, 3),
+            Err("no result 3; the last search with --numbers saved 2".to_string())
+        );
+        assert_eq!(
+            get(&list, 0),
+            Err("results are numbered from 1".to_string())
+        );
+    }
+
+    #[test]
+    fn get_explains_missing_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("last-results");
+        assert_eq!(
+            get(&list, 1),
+            Err("no saved results; run a search with --numbers first".to_string())
+        );
+
+        let gone = dir.path().join("gone.txt");
+        save(&list, std::slice::from_ref(&gone)).unwrap();
+        assert_eq!(
+            get(&list, 1),
+            Err(format!(
+                "result 1 ({}) no longer exists; run the search again",
+                gone.display()
+            ))
+        );
+    }
+
+    #[test]
+    fn newline_names_keep_their_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("last-results");
+        let after = dir.path().join("after.txt");
+        fs::write(&after, "").unwrap();
+
+        save(&list, &[PathBuf::from("odd\nname"), after.clone()]).unwrap();
+        assert_eq!(
+            get(&list, 1),
+            Err("result 1 has a newline in its name and wasn't saved".to_string())
+        );
+        assert_eq!(get(&list, 2), Ok(after));
+    }
+
+    #[test]
+    fn save_replaces_the_list_and_caps_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("last-results");
+        let many: Vec<PathBuf> = (0..MAX_SAVED + 5)
+            .map(|i| PathBuf::from(format!("f{i}")))
+            .collect();
+        save(&list, &many).unwrap();
+        assert_eq!(
+            fs::read_to_string(&list).unwrap().lines().count(),
+            MAX_SAVED
+        );
+
+        save(&list, &many[..1]).unwrap();
+        assert_eq!(fs::read_to_string(&list).unwrap(), "f0\n");
+        // Nothing is left behind from the temporary file
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,8 +1,23 @@
+#[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "cli")]
+pub mod config;
+#[cfg(feature = "cli")]
+pub mod history;
+#[cfg(feature = "cli")]
+pub mod last_results;
 pub mod matchers;
+pub mod paths;
 pub mod search;
+#[cfg(feature = "cli")]
 pub mod ui;
 
 // Re-export common types
 pub use matchers::EntryMatcher;
-pub use search::{SearchResult, StatusMessage};
+#[cfg(feature = "async")]
+pub use search::MatchStream;
+#[cfg(feature = "watch")]
+pub use search::WatchEvent;
+pub use search::{
+    SearchEvent, SearchMetrics, SearchOptions, SearchResult, StatusMessage, TreeStats,
+};
@@ -1,65 +1,756 @@
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-use seek::cli::Args;
+use seek::EntryMatcher;
+use seek::cli::{Args, ColorChoice, Interpretation, OutputFormat};
+use seek::history;
+use seek::last_results;
 use seek::matchers;
-use seek::search::Searcher;
-use seek::ui::DisplayManager;
+use seek::search::{self, SearchResult, Searcher};
+use seek::ui::{
+    DisplayConfig, DisplayManager, ExecConfig, GrepLines, Output, Replacement,
+    print_default_limit_hint, print_permission_hint, utf8_locale,
+};
+
+/// Exit status when at least one match was found
+const EXIT_MATCHED: i32 = 0;
+/// Exit status when the search completed without matches
+const EXIT_NO_MATCHES: i32 = 1;
+/// Exit status for usage, pattern, and path errors
+const EXIT_ERROR: i32 = 2;
+/// Exit status when interrupted with Ctrl-C
+const EXIT_INTERRUPTED: i32 = 130;
 
 fn main() {
     // Parse command line arguments
-    let (args, path, pattern) = Args::parse_args();
+    let (args, path, pattern, interpretation) = Args::parse_args();
+
+    if let Some(index) = args.get {
+        process::exit(print_saved_result(index));
+    }
+    if args.history {
+        process::exit(print_history());
+    }
+    if args.again {
+        process::exit(run_again());
+    }
+
+    if args.print0
+        || args.exec_command().is_some()
+        || args.format != OutputFormat::Text
+        || args.output_file().is_some()
+    {
+        ColorChoice::Never.apply();
+    } else {
+        args.color.apply();
+    }
+
+    // A lone argument that names a missing path was probably meant as the search root
+    if interpretation == Interpretation::PatternOnly && looks_like_path(&pattern) && !args.quiet {
+        eprintln!(
+            "Warning: `{pattern}` does not exist, so it is treated as a pattern to search for in `{path}`"
+        );
+    }
 
     // Create the base path
     let base_path = PathBuf::from(&path);
+    let timeout = (args.timeout_seconds > 0).then(|| Duration::from_secs(args.timeout_seconds));
+    if let Err(e) = check_base_path(&base_path, timeout) {
+        eprintln!("Error: {e}");
+        process::exit(EXIT_ERROR);
+    }
 
     // Create the appropriate matcher
-    let matcher = match matchers::create_matcher(&pattern, args.regex) {
+    let access_check = Arc::new(matchers::AccessTimeCheck::default());
+    let matcher = match build_matcher(&args, &pattern, &base_path, &access_check) {
         Ok(m) => m,
         Err(e) => {
             eprintln!("Error: {e}");
-            process::exit(1);
+            process::exit(EXIT_ERROR);
         }
     };
 
-    // Record search start time
-    let start_time = std::time::Instant::now();
-
     // Create the searcher
-    let searcher = Searcher::new(
-        matcher,
-        base_path.clone(),
-        args.max_depth,
-        args.max_files,
-        args.timeout_seconds,
-        args.files_only,
-        args.dirs_only,
-        args.show_permission_errors,
-    );
+    let mut searcher = Searcher::with_options(matcher, base_path.clone(), args.to_search_options());
+    if !args.config_prunes.is_empty() {
+        match matchers::create_any_matcher(
+            &args.config_prunes,
+            false,
+            matchers::CaseMode::Sensitive,
+        ) {
+            Ok(prune) => searcher = searcher.with_prune(prune),
+            Err(e) => {
+                eprintln!("Error: Invalid `prune` pattern in the config file: {e}");
+                process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    // In quiet mode only the first match matters
+    if args.quiet {
+        let (result_rx, _status_rx, _handle) = searcher.search(args.full_path);
+        let found = result_rx.recv().is_ok();
+        searcher.cancel();
+        process::exit(if found || args.no_exit_code {
+            EXIT_MATCHED
+        } else {
+            EXIT_NO_MATCHES
+        });
+    }
+
+    // Let the user choose among the matches once they're all in
+    if args.pick {
+        let (matches, _result) = searcher.search_collect(args.full_path);
+        process::exit(pick_matches(matches));
+    }
+
+    // Open the output file before searching so a bad path fails fast
+    let output = match args.output_file() {
+        Some(file) => Output::create(file).unwrap_or_else(|e| {
+            eprintln!("Error: Cannot create {}: {e}", file.display());
+            process::exit(EXIT_ERROR);
+        }),
+        None => Output::stdout(),
+    };
+
+    // Fall back to ASCII glyphs when asked or when the terminal can't show UTF-8
+    let ascii = args.ascii || !utf8_locale();
 
     // Create the display manager
-    let mut display = DisplayManager::new();
+    let mut display = DisplayManager::writing_to(
+        DisplayConfig {
+            raw_paths: args.raw_paths,
+            null_terminated: args.print0,
+            format: args.format.clone(),
+            base_path: base_path.clone(),
+            long: args.long,
+            human_sizes: args.human_readable,
+            path_style: args.path_style(),
+            group_by: args.group_by,
+            group_top: args.top,
+            progress_total: args.max_files.filter(|&limit| limit > 0),
+            tree: args.tree,
+            recent: args.recent,
+            group_dirs: args.group.then_some(args.group_sort),
+            exec: args.exec_command().map(|command| ExecConfig {
+                command: command.to_vec(),
+                jobs: args.exec_jobs,
+                shell: args.exec_shell,
+                dry_run: args.dry_run,
+                batch: args.exec_batch.is_some().then_some(args.exec_arg_max),
+            }),
+            replace: args.replacement().map(|(from, to)| Replacement {
+                from: from.to_string(),
+                to: to.to_string(),
+                all: args.replace_all,
+                scope: args.replace_in,
+            }),
+            count_entries: args.count_entries,
+            head: args.head.filter(|&head| head > 0),
+            max_output_bytes: args.max_output_bytes.filter(|&budget| budget > 0),
+            watch: args.watch,
+            classify: args.format == OutputFormat::Text
+                && !args.print0
+                && args.exec_command().is_none()
+                && args.output_file().is_none()
+                && std::io::stdout().is_terminal(),
+            dir_color: args.dir_color,
+            show_link_targets: args.show_link_targets,
+            ascii,
+            progress_path_width: args.progress_path_width,
+            numbers: args.numbers,
+            status_json: args
+                .status_json
+                .then(|| Duration::from_millis(args.heartbeat_ms)),
+            icons: args.icons
+                && !ascii
+                && args.format == OutputFormat::Text
+                && !args.print0
+                && args.exec_command().is_none()
+                && args.output_file().is_none(),
+            hyperlinks: args.hyperlink.enabled()
+                && args.format == OutputFormat::Text
+                && !args.print0
+                && args.exec_command().is_none()
+                && args.output_file().is_none(),
+            grep: args
+                .grep
+                .as_deref()
+                .filter(|_| args.format == OutputFormat::Text && !args.print0)
+                .and_then(|pattern| regex::Regex::new(pattern).ok())
+                .map(|regex| GrepLines {
+                    regex,
+                    max_lines: args.grep_max_lines,
+                }),
+            ..DisplayConfig::detect(
+                args.no_progress
+                    || (args.print0 && args.output_file().is_none())
+                    || args.exec_command().is_some(),
+            )
+        },
+        output,
+    );
+    display.set_highlighter(
+        searcher.matcher(),
+        args.full_path || args.relative_match || args.ends_with,
+    );
+
+    // Ctrl-C ends the search early with what was found so far; a second one exits at once
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&interrupted);
+    let stop = searcher.clone();
+    let handler = ctrlc::set_handler(move || {
+        if flag.swap(true, Ordering::SeqCst) {
+            process::exit(EXIT_INTERRUPTED);
+        }
+        stop.cancel();
+    });
+    if let Err(e) = handler {
+        eprintln!("Warning: Failed to install Ctrl-C handler: {e}");
+    }
 
     // Start the search
-    let (result_rx, status_rx, _) = searcher.search(args.full_path);
+    let (result_rx, status_rx, search_thread) = searcher.search(args.full_path);
+
+    // In count mode only the total is printed
+    if args.count {
+        display.count_results(result_rx, status_rx);
+        let result = search_thread.join().expect("search thread panicked");
+        println!("{}", result.matches);
+        record_history(&args, &path, &pattern, result.matches);
+        process::exit(exit_code(
+            &args,
+            &result,
+            interrupted.load(Ordering::SeqCst),
+        ));
+    }
 
     // Process and display results in real-time
-    let (found_count, file_count, permission_errors, limit_reached, timed_out, _) =
-        display.process_results(result_rx, status_rx);
+    display.process_results(result_rx, status_rx);
+    if display.output_failed() {
+        searcher.cancel();
+        let _ = search_thread.join();
+        display.finish_output();
+        process::exit(EXIT_ERROR);
+    }
+    let mut result = search_thread.join().expect("search thread panicked");
 
-    // Create the final search result with proper elapsed time
-    let result = seek::search::SearchResult {
-        matches: found_count,
-        files_scanned: file_count,
-        permission_errors,
-        elapsed: start_time.elapsed(), // Use actual elapsed time since search started
-        limit_reached,
-        timed_out,
-    };
+    if let Some(stats) = &result.tree_stats {
+        display.print_tree_stats(stats, &result);
+        if interrupted.load(Ordering::SeqCst) {
+            process::exit(EXIT_INTERRUPTED);
+        }
+        process::exit(EXIT_MATCHED);
+    }
+    let mut output_ok = display.finish_output();
+
+    // Keep reporting new matches until interrupted
+    if args.watch && !interrupted.load(Ordering::SeqCst) {
+        watch_for_changes(&args, &searcher, &mut display, &mut result, &interrupted);
+        output_ok &= display.finish_output();
+    }
 
     // Show final results
     display.finish(&result, &base_path);
 
+    // Remember the numbered results for `--get`
+    if args.numbers {
+        let saved = last_results::default_path()
+            .ok_or_else(|| "no cache directory".to_string())
+            .and_then(|file| {
+                last_results::save(&file, display.numbered())
+                    .map_err(|e| format!("{}: {e}", file.display()))
+            });
+        if let Err(e) = saved {
+            eprintln!("Warning: Cannot save results for --get: {e}");
+        }
+    }
+
+    // Let the user know a long search is over in case they looked away
+    if should_notify(args.notify, result.elapsed, args.notify_after) {
+        notify_finished(&result, &pattern, &base_path);
+    }
+
+    if args.show_permission_errors {
+        display.print_errors(&result);
+    }
+
+    if let Some(metrics) = &result.metrics {
+        display.print_stats(metrics);
+    }
+
     // Show permission hints if needed
-    Searcher::print_permission_hint(permission_errors, &path, &pattern);
+    print_permission_hint(&result, &path, &pattern);
+
+    // A cap the user never asked for shouldn't pass for a complete search
+    if args.max_files.is_none() {
+        print_default_limit_hint(&result);
+    }
+
+    if access_check.looks_unreliable() {
+        eprintln!(
+            "\nWarning: most files checked have an access time equal to their change time, so \
+             access times may not be updated here (noatime?) and --not-accessed-for is unreliable"
+        );
+    }
+
+    // Commands killed by the same Ctrl-C don't turn an interruption into an error
+    let interrupted = interrupted.load(Ordering::SeqCst);
+    if !output_ok && !interrupted {
+        process::exit(EXIT_ERROR);
+    }
+    record_history(&args, &path, &pattern, result.matches);
+
+    process::exit(exit_code(&args, &result, interrupted));
+}
+
+/// Report matches created or modified under the search root until Ctrl-C
+#[cfg(feature = "watch")]
+fn watch_for_changes(
+    args: &Args,
+    searcher: &Searcher,
+    display: &mut DisplayManager,
+    result: &mut SearchResult,
+    interrupted: &Arc<AtomicBool>,
+) {
+    let watch_start = std::time::Instant::now();
+    match searcher.watch(args.full_path, args.watch_deletes) {
+        Ok((event_rx, _watcher)) => {
+            let new_matches = display.process_watch_events(event_rx, interrupted);
+            result.matches += new_matches;
+            result.matches_sent += new_matches;
+        }
+        Err(e) => eprintln!("Error: {e}"),
+    }
+    result.elapsed += watch_start.elapsed();
+}
+
+/// Without the `watch` feature, `--watch` is rejected while parsing arguments
+#[cfg(not(feature = "watch"))]
+fn watch_for_changes(
+    _args: &Args,
+    _searcher: &Searcher,
+    _display: &mut DisplayManager,
+    _result: &mut SearchResult,
+    _interrupted: &Arc<AtomicBool>,
+) {
+}
+
+/// Print the matches chosen in the picker, returning the exit status
+#[cfg(feature = "picker")]
+fn pick_matches(matches: Vec<PathBuf>) -> i32 {
+    if matches.is_empty() {
+        eprintln!("No matches to pick from");
+        return EXIT_NO_MATCHES;
+    }
+    let items = matches
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    match seek::ui::pick(items) {
+        Ok(Some(picked)) => {
+            for path in picked {
+                println!("{path}");
+            }
+            EXIT_MATCHED
+        }
+        Ok(None) => EXIT_NO_MATCHES,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Without the `picker` feature, `--pick` is rejected while parsing arguments
+#[cfg(not(feature = "picker"))]
+fn pick_matches(_matches: Vec<PathBuf>) -> i32 {
+    EXIT_ERROR
+}
+
+/// Print a result saved by the last `--numbers` search, returning the exit status
+fn print_saved_result(index: usize) -> i32 {
+    let Some(file) = last_results::default_path() else {
+        eprintln!("Error: no cache directory for saved results");
+        return EXIT_ERROR;
+    };
+    match last_results::get(&file, index) {
+        Ok(path) => {
+            println!("{}", path.display());
+            EXIT_MATCHED
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Add this search to the history unless that's turned off
+fn record_history(args: &Args, path: &str, pattern: &str, matches: usize) {
+    if args.no_history {
+        return;
+    }
+    let Some(file) = history::default_path() else {
+        return;
+    };
+    let entry = history::Entry::now(path, pattern, matches);
+    if let Err(e) = history::append(&file, &entry) {
+        eprintln!(
+            "Warning: Cannot record the search in {}: {e}",
+            file.display()
+        );
+    }
+}
+
+/// Print the most recent searches, oldest first, returning the exit status
+fn print_history() -> i32 {
+    let entries = match history::default_path().map(|file| history::recent(&file, 20)) {
+        Some(Ok(entries)) => entries,
+        Some(Err(e)) => {
+            eprintln!("Error: {e}");
+            return EXIT_ERROR;
+        }
+        None => Vec::new(),
+    };
+    if entries.is_empty() {
+        eprintln!("No searches in the history yet");
+        return EXIT_NO_MATCHES;
+    }
+    let width = entries
+        .iter()
+        .map(|entry| entry.matches.to_string().len())
+        .max()
+        .unwrap_or(1);
+    for entry in entries {
+        println!(
+            "{}  {:>width$} {}  {}",
+            entry.time(),
+            entry.matches,
+            if entry.matches == 1 {
+                "match  "
+            } else {
+                "matches"
+            },
+            entry.command_line()
+        );
+    }
+    EXIT_MATCHED
+}
+
+/// Run the latest search in the history again, returning its exit status
+fn run_again() -> i32 {
+    let latest = match history::default_path().map(|file| history::recent(&file, 1)) {
+        Some(Ok(mut entries)) => entries.pop(),
+        Some(Err(e)) => {
+            eprintln!("Error: {e}");
+            return EXIT_ERROR;
+        }
+        None => None,
+    };
+    let Some(entry) = latest else {
+        eprintln!("Error: no searches in the history to run again");
+        return EXIT_ERROR;
+    };
+    eprintln!("{}", entry.command_line());
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Error: Cannot find the seek executable: {e}");
+            return EXIT_ERROR;
+        }
+    };
+    match process::Command::new(exe)
+        .args(&entry.args)
+        .current_dir(&entry.cwd)
+        .status()
+    {
+        Ok(status) => status.code().unwrap_or(EXIT_INTERRUPTED),
+        Err(e) => {
+            eprintln!("Error: Cannot run the search again: {e}");
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Whether `--notify` should fire: only when asked for and the search ran at least `after`
+fn should_notify(requested: bool, elapsed: Duration, after: Duration) -> bool {
+    requested && elapsed >= after
+}
+
+/// Notification text, e.g. "3 matches for *.log in /var after 1m 5s"
+fn notification_body(matches: usize, elapsed: Duration, pattern: &str, base_path: &Path) -> String {
+    let plural = if matches == 1 { "match" } else { "matches" };
+    format!(
+        "{matches} {plural} for {pattern} in {} after {}",
+        base_path.display(),
+        humantime::format_duration(Duration::from_secs(elapsed.as_secs()))
+    )
+}
+
+/// Ring the terminal bell and, where supported, send a desktop notification
+fn notify_finished(result: &SearchResult, pattern: &str, base_path: &Path) {
+    if std::io::stderr().is_terminal() {
+        eprint!("\x07");
+    }
+    let body = notification_body(result.matches, result.elapsed, pattern, base_path);
+    send_notification("seek finished", &body);
+}
+
+/// Pop up a desktop notification by running notify-send (osascript on macOS), ignoring failures
+#[cfg(feature = "notifications")]
+fn send_notification(title: &str, body: &str) {
+    use std::process::{Command, Stdio};
+
+    let mut command = if cfg!(target_os = "macos") {
+        // AppleScript strings only need quotes and backslashes escaped
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            quote(body),
+            quote(title)
+        ));
+        command
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        command.arg(title).arg(body);
+        command
+    } else {
+        return;
+    };
+    let _ = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Without the `notifications` feature, `--notify` only rings the bell
+#[cfg(not(feature = "notifications"))]
+fn send_notification(_title: &str, _body: &str) {}
+
+/// Exit status reflecting the outcome of a completed search
+fn exit_code(args: &Args, result: &SearchResult, interrupted: bool) -> i32 {
+    if interrupted {
+        EXIT_INTERRUPTED
+    } else if args.no_exit_code || result.matches > 0 {
+        EXIT_MATCHED
+    } else {
+        EXIT_NO_MATCHES
+    }
+}
+
+/// Ensure the search root exists and, for directories, can be listed within `timeout`
+fn check_base_path(base_path: &Path, timeout: Option<Duration>) -> Result<(), String> {
+    let root = base_path.to_path_buf();
+    let opened = search::with_timeout(timeout, move || {
+        let metadata = std::fs::metadata(&root).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("Path does not exist: {}", root.display()),
+            _ => format!("Cannot access {}: {e}", root.display()),
+        })?;
+        if metadata.is_dir() {
+            std::fs::read_dir(&root)
+                .map_err(|e| format!("Cannot read directory {}: {e}", root.display()))?;
+        }
+        Ok(())
+    });
+    opened.unwrap_or_else(|| {
+        Err(format!(
+            "Timed out opening {} (is it on an unresponsive mount?)",
+            base_path.display()
+        ))
+    })
+}
+
+/// Whether a pattern has path separators but no glob syntax, like a mistyped directory
+fn looks_like_path(pattern: &str) -> bool {
+    pattern.contains(std::path::MAIN_SEPARATOR)
+        && !pattern.contains(['*', '?', '[', '{'])
+        && !Path::new(pattern).exists()
+}
+
+/// Combine the command-line pattern with any pattern and exclusion files
+fn build_matcher(
+    args: &Args,
+    pattern: &str,
+    base_path: &Path,
+    access_check: &Arc<matchers::AccessTimeCheck>,
+) -> Result<Box<dyn EntryMatcher>, String> {
+    let mut patterns = Vec::new();
+    if args.pattern_file.is_none() || args.has_explicit_pattern() {
+        patterns.push(pattern.to_string());
+    }
+    if let Some(file) = &args.pattern_file {
+        patterns.extend(matchers::read_pattern_file(file)?);
+    }
+    let matcher = if args.ends_with {
+        matchers::create_suffix_matcher(&patterns)?
+    } else if args.ignore_case_path || args.ignore_case_name {
+        let split = patterns
+            .iter()
+            .map(|pattern| {
+                matchers::SplitCaseMatcher::new(
+                    pattern,
+                    args.ignore_case_path,
+                    args.ignore_case_name,
+                )
+                .map(|m| Box::new(m) as Box<dyn EntryMatcher>)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Box::new(matchers::AnyMatcher::new(split))
+    } else {
+        matchers::create_any_matcher(&patterns, args.regex, args.case_mode())
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut excludes = args.config_excludes.clone();
+    if let Some(file) = &args.exclude_file {
+        excludes.extend(matchers::read_pattern_file(file)?);
+    }
+    let matcher: Box<dyn EntryMatcher> = if excludes.is_empty() {
+        matcher
+    } else {
+        let exclude = matchers::create_any_matcher(&excludes, args.regex, args.case_mode())
+            .map_err(|e| e.to_string())?;
+        Box::new(matchers::ExcludeMatcher::new(matcher, exclude))
+    };
+
+    let matcher: Box<dyn EntryMatcher> = if args.link_target {
+        Box::new(matchers::LinkTargetMatcher::new(matcher))
+    } else if args.relative_match {
+        Box::new(matchers::RelativePathMatcher::new(matcher, base_path))
+    } else {
+        matcher
+    };
+
+    let matcher: Box<dyn EntryMatcher> = match &args.grep {
+        Some(pattern) => Box::new(matchers::ContentMatcher::new(matcher, pattern)?),
+        None => matcher,
+    };
+
+    let matcher: Box<dyn EntryMatcher> = match &args.component {
+        Some(name) => Box::new(matchers::ComponentMatcher::new(matcher, name)?),
+        None => matcher,
+    };
+
+    let matcher: Box<dyn EntryMatcher> =
+        if args.name_longer_than.is_some() || args.name_shorter_than.is_some() {
+            Box::new(matchers::NameLengthMatcher::new(
+                matcher,
+                args.name_longer_than,
+                args.name_shorter_than,
+            )?)
+        } else {
+            matcher
+        };
+
+    let time_filters = [
+        (matchers::TimeField::Modified, args.modified_within),
+        (matchers::TimeField::Accessed, args.accessed_within),
+        (matchers::TimeField::Created, args.created_within),
+    ];
+    let mut matcher = matcher;
+    for (field, within) in time_filters {
+        let Some(within) = within else {
+            continue;
+        };
+        if field == matchers::TimeField::Created && !field.is_supported(base_path) {
+            if !args.quiet {
+                eprintln!(
+                    "Warning: creation times aren't available for {}, ignoring --created-within",
+                    base_path.display()
+                );
+            }
+            continue;
+        }
+        matcher = Box::new(matchers::TimeMatcher::new(matcher, field, within));
+    }
+    if let Some(age) = args.not_accessed_for {
+        matcher = Box::new(
+            matchers::TimeMatcher::older_than(matcher, matchers::TimeField::Accessed, age)
+                .with_access_check(Arc::clone(access_check)),
+        );
+    }
+
+    let matcher: Box<dyn EntryMatcher> = if args.git_modified {
+        Box::new(matchers::GitModifiedMatcher::new(matcher, base_path)?)
+    } else {
+        matcher
+    };
+
+    let matcher: Box<dyn EntryMatcher> = match args.min_links() {
+        Some(min_links) => Box::new(matchers::LinkCountMatcher::new(matcher, min_links)?),
+        None => matcher,
+    };
+
+    // Outermost so the cheap extension check runs before any other matcher
+    let extensions = args.extensions();
+    if extensions.is_empty() {
+        Ok(matcher)
+    } else {
+        Ok(Box::new(matchers::ExtensionMatcher::new(
+            matcher,
+            &extensions,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifies_only_when_asked_and_slow_enough() {
+        let after = Duration::from_secs(30);
+        assert!(should_notify(true, Duration::from_secs(30), after));
+        assert!(should_notify(true, Duration::from_secs(95), after));
+        assert!(!should_notify(true, Duration::from_millis(29_999), after));
+        assert!(!should_notify(false, Duration::from_secs(95), after));
+        assert!(should_notify(true, Duration::ZERO, Duration::ZERO));
+    }
+
+    #[test]
+    fn notification_body_counts_and_rounds() {
+        let base = Path::new("/var/log");
+        assert_eq!(
+            notification_body(1, Duration::from_millis(65_900), "*.log", base),
+            "1 match for *.log in /var/log after 1m 5s"
+        );
+        assert_eq!(
+            notification_body(0, Duration::from_secs(30), "*.log", base),
+            "0 matches for *.log in /var/log after 30s"
+        );
+    }
+
+    #[test]
+    fn base_path_errors_name_the_cause() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "").unwrap();
+
+        assert_eq!(check_base_path(dir.path(), None), Ok(()));
+        assert_eq!(check_base_path(&file, None), Ok(()));
+
+        let missing = dir.path().join("missing");
+        assert_eq!(
+            check_base_path(&missing, None),
+            Err(format!("Path does not exist: {}", missing.display()))
+        );
+
+        // Looking inside a regular file fails with something other than
+        // NotFound on Unix; Windows reports it as a missing path
+        if cfg!(unix) {
+            let inside_file = file.join("child");
+            let err = check_base_path(&inside_file, None).unwrap_err();
+            assert!(
+                err.starts_with(&format!("Cannot access {}: ", inside_file.display())),
+                "{err}"
+            );
+        }
+    }
 }
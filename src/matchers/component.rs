@@ -0,0 +1,89 @@
+use std::ffi::OsString;
+use std::ops::Range;
+use std::path::{Component, Path};
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Restricts another matcher to paths with a component named exactly `name`
+pub struct ComponentMatcher {
+    inner: Box<dyn EntryMatcher>,
+    name: OsString,
+}
+
+impl ComponentMatcher {
+    pub fn new(inner: Box<dyn EntryMatcher>, name: &str) -> Result<Self, String> {
+        let mut components = Path::new(name).components();
+        match (components.next(), components.next()) {
+            // Stored without any trailing separator, so `node_modules/` still matches
+            (Some(Component::Normal(part)), None) => Ok(ComponentMatcher {
+                inner,
+                name: part.to_os_string(),
+            }),
+            _ => Err(format!(
+                "Invalid component `{name}`: expected a single path segment"
+            )),
+        }
+    }
+
+    fn has_component(&self, path: &Path) -> bool {
+        path.components()
+            .any(|c| matches!(c, Component::Normal(part) if part == self.name))
+    }
+}
+
+impl EntryMatcher for ComponentMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.has_component(entry.path()) && self.inner.is_match(entry, full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.has_component(path) && self.inner.is_path_match(path, full_path)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+
+    fn matcher(name: &str) -> Result<ComponentMatcher, String> {
+        ComponentMatcher::new(Box::new(GlobEntryMatcher::new("*").unwrap()), name)
+    }
+
+    #[test]
+    fn matches_whole_components_anywhere_in_the_path() {
+        let matcher = matcher("target").unwrap();
+        let matches = |path: &str| matcher.is_path_match(Path::new(path), false);
+        assert!(matches("target"));
+        assert!(matches("a/target/debug/x"));
+        assert!(matches("/abs/target"));
+        assert!(!matches("a/targets/x"));
+        assert!(!matches("a/my-target/x"));
+        assert!(!matches("a/Target/x"));
+    }
+
+    #[test]
+    fn trailing_separators_are_ignored() {
+        let matcher = matcher("node_modules/").unwrap();
+        assert!(matcher.is_path_match(Path::new("web/node_modules/x.js"), false));
+    }
+
+    #[test]
+    fn only_single_segments_are_accepted() {
+        for name in ["a/b", "/", ".", "..", "", "/abs"] {
+            let err = matcher(name)
+                .err()
+                .unwrap_or_else(|| panic!("{name:?} accepted"));
+            assert_eq!(
+                err,
+                format!("Invalid component `{name}`: expected a single path segment")
+            );
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use regex::Regex;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+use std::path::Path;
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Bytes inspected for a NUL when deciding whether a file is binary
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Restricts another matcher to text files with a line matching `regex`
+pub struct ContentMatcher {
+    inner: Box<dyn EntryMatcher>,
+    regex: Regex,
+}
+
+impl ContentMatcher {
+    pub fn new(inner: Box<dyn EntryMatcher>, pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern).map_err(|e| format!("Invalid --grep regex: {e}"))?;
+        Ok(ContentMatcher { inner, regex })
+    }
+
+    fn has_match(&self, path: &Path) -> bool {
+        !matching_lines(path, &self.regex, 1).is_empty()
+    }
+}
+
+impl EntryMatcher for ContentMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        entry.file_type().is_file()
+            && self.inner.is_match(entry, full_path)
+            && self.has_match(entry.path())
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        path.is_file() && self.inner.is_path_match(path, full_path) && self.has_match(path)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+/// Up to `limit` lines of a text file matching `regex`, as 1-based line numbers
+/// and contents, or none if the file is binary or can't be read
+pub fn matching_lines(path: &Path, regex: &Regex, limit: usize) -> Vec<(usize, String)> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut head = vec![0; BINARY_SNIFF_LEN];
+    let Ok(read) = file.read(&mut head) else {
+        return Vec::new();
+    };
+    head.truncate(read);
+    if head.contains(&0) {
+        return Vec::new();
+    }
+
+    let reader = BufReader::new(head.as_slice().chain(file));
+    let mut lines = Vec::new();
+    for (index, line) in reader.split(b'\n').enumerate() {
+        let Ok(line) = line else {
+            break;
+        };
+        let line = String::from_utf8_lossy(&line);
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if regex.is_match(line) {
+            lines.push((index + 1, line.to_string()));
+            if lines.len() >= limit {
+                break;
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+    use std::fs;
+
+    fn lines(contents: &[u8], pattern: &str, limit: usize) -> Vec<(usize, String)> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        fs::write(&path, contents).unwrap();
+        matching_lines(&path, &Regex::new(pattern).unwrap(), limit)
+    }
+
+    #[test]
+    fn numbers_matching_lines_from_one() {
+        assert_eq!(
+            lines(b"alpha\nbeta\nalphabet\n", "alpha", 10),
+            [(1, "alpha".to_string()), (3, "alphabet".to_string())]
+        );
+        assert_eq!(lines(b"a\na\na\n", "a", 2).len(), 2);
+        assert!(lines(b"alpha\n", "gamma", 10).is_empty());
+    }
+
+    #[test]
+    fn crlf_endings_are_stripped() {
+        assert_eq!(
+            lines(b"one\r\ntwo\r\n", "^two$", 10),
+            [(2, "two".to_string())]
+        );
+    }
+
+    #[test]
+    fn binary_files_are_skipped() {
+        assert!(lines(b"text\0more text\n", "text", 10).is_empty());
+        // Only the start of a file is sniffed for NULs
+        let mut late_nul = vec![b'x'; BINARY_SNIFF_LEN];
+        late_nul.extend_from_slice(b"\0\nneedle\n");
+        assert_eq!(lines(&late_nul, "needle", 10), [(2, "needle".to_string())]);
+    }
+
+    #[test]
+    fn invalid_utf8_is_still_searched() {
+        assert_eq!(
+            lines(b"caf\xe9 needle\n", "needle", 10),
+            [(1, "caf\u{fffd} needle".to_string())]
+        );
+    }
+
+    #[test]
+    fn only_readable_files_match() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("hit.txt"), "needle\n").unwrap();
+        fs::create_dir(dir.path().join("needle")).unwrap();
+        let any = Box::new(GlobEntryMatcher::new("*").unwrap());
+        let matcher = ContentMatcher::new(any, "needle").unwrap();
+        assert!(matcher.is_path_match(&dir.path().join("hit.txt"), false));
+        assert!(!matcher.is_path_match(&dir.path().join("needle"), false));
+        assert!(!matcher.is_path_match(&dir.path().join("gone.txt"), false));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let any = Box::new(GlobEntryMatcher::new("*").unwrap());
+        let err = ContentMatcher::new(any, "(unclosed").err().unwrap();
+        assert!(err.starts_with("Invalid --grep regex:"), "{err}");
+    }
+}
@@ -0,0 +1,94 @@
+use std::ops::Range;
+use std::path::Path;
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Skips entries without one of the given extensions before running another matcher
+pub struct ExtensionMatcher {
+    inner: Box<dyn EntryMatcher>,
+    extensions: Vec<String>,
+}
+
+impl ExtensionMatcher {
+    pub fn new(inner: Box<dyn EntryMatcher>, extensions: &[String]) -> Result<Self, String> {
+        let extensions = extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_ascii_lowercase())
+            .collect::<Vec<_>>();
+        if let Some(bad) = extensions
+            .iter()
+            .find(|ext| ext.is_empty() || ext.contains(['/', '\\']))
+        {
+            return Err(format!("Invalid extension `{bad}`"));
+        }
+        Ok(ExtensionMatcher { inner, extensions })
+    }
+
+    fn has_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|want| want.eq_ignore_ascii_case(ext))
+            })
+    }
+}
+
+impl EntryMatcher for ExtensionMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.has_extension(entry.path()) && self.inner.is_match(entry, full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.has_extension(path) && self.inner.is_path_match(path, full_path)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+
+    fn matcher(extensions: &[&str]) -> Result<ExtensionMatcher, String> {
+        let extensions: Vec<String> = extensions.iter().map(|ext| ext.to_string()).collect();
+        ExtensionMatcher::new(Box::new(GlobEntryMatcher::new("*").unwrap()), &extensions)
+    }
+
+    #[test]
+    fn extensions_are_normalized() {
+        let matcher = matcher(&[".RS", "Toml"]).unwrap();
+        assert_eq!(matcher.extensions, ["rs", "toml"]);
+        let matches = |path: &str| matcher.is_path_match(Path::new(path), false);
+        assert!(matches("src/main.rs"));
+        assert!(matches("MAIN.RS"));
+        assert!(matches("Cargo.toml"));
+        assert!(!matches("main.rsx"));
+        assert!(!matches("rs"));
+        assert!(!matches("archive.tar.gz"));
+    }
+
+    #[test]
+    fn only_the_last_extension_counts() {
+        let matcher = matcher(&["gz"]).unwrap();
+        assert!(matcher.is_path_match(Path::new("archive.tar.gz"), false));
+        assert!(!matcher.is_path_match(Path::new("archive.gz.tar"), false));
+    }
+
+    #[test]
+    fn separators_and_empty_extensions_are_rejected() {
+        assert_eq!(matcher(&["a/b"]).err().unwrap(), "Invalid extension `a/b`");
+        assert_eq!(
+            matcher(&[r"a\b"]).err().unwrap(),
+            r"Invalid extension `a\b`"
+        );
+        assert_eq!(matcher(&["rs", "."]).err().unwrap(), "Invalid extension ``");
+        assert!(matcher(&[""]).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Restricts another matcher to files git reports as modified or untracked
+pub struct GitModifiedMatcher {
+    inner: Box<dyn EntryMatcher>,
+    changed: HashSet<PathBuf>,
+    base_path: PathBuf,
+    canonical_base: PathBuf,
+}
+
+impl GitModifiedMatcher {
+    /// Query git for changes in the repository containing `base_path`
+    pub fn new(inner: Box<dyn EntryMatcher>, base_path: &Path) -> Result<Self, String> {
+        let canonical_base = base_path
+            .canonicalize()
+            .map_err(|e| format!("Cannot resolve {}: {e}", base_path.display()))?;
+        let root = PathBuf::from(git(&canonical_base, &["rev-parse", "--show-toplevel"])?.trim());
+        let status = git(
+            &canonical_base,
+            &["status", "--porcelain=v1", "-z", "--untracked-files=all"],
+        )?;
+
+        Ok(GitModifiedMatcher {
+            inner,
+            changed: parse_porcelain(&status).map(|rel| root.join(rel)).collect(),
+            base_path: base_path.to_path_buf(),
+            canonical_base,
+        })
+    }
+
+    /// Whether a walked path is among git's changed paths
+    fn is_changed(&self, path: &Path) -> bool {
+        let path = crate::paths::strip_extended_prefix(path);
+        match path.strip_prefix(&self.base_path) {
+            Ok(rel) => self.changed.contains(&self.canonical_base.join(rel)),
+            Err(_) => self.changed.contains(&path),
+        }
+    }
+}
+
+impl EntryMatcher for GitModifiedMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.is_changed(entry.path()) && self.inner.is_match(entry, full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.is_changed(path) && self.inner.is_path_match(path, full_path)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<std::ops::Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+/// Run git in `dir` and return its stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("{} is not inside a git repository", dir.display()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Paths from `git status --porcelain=v1 -z`, relative to the repository root
+fn parse_porcelain(status: &str) -> impl Iterator<Item = &str> {
+    let mut fields = status.split('\0').filter(|f| !f.is_empty());
+    std::iter::from_fn(move || {
+        let field = fields.next()?;
+        let (code, path) = (field.get(..2)?, field.get(3..)?);
+        // Renames and copies are followed by the original path, which no longer applies
+        if code.contains('R') || code.contains('C') {
+            fields.next();
+        }
+        Some(path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+    use std::fs;
+
+    #[test]
+    fn porcelain_paths_skip_rename_and_copy_origins() {
+        let status = " M src/lib.rs\0?? new file.txt\0R  moved.rs\0old.rs\0\
+                      C  copy.rs\0orig.rs\0A  added.rs\0";
+        assert_eq!(
+            parse_porcelain(status).collect::<Vec<_>>(),
+            [
+                "src/lib.rs",
+                "new file.txt",
+                "moved.rs",
+                "copy.rs",
+                "added.rs"
+            ]
+        );
+        assert_eq!(parse_porcelain("").count(), 0);
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn matches_modified_and_untracked_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        run_git(repo, &["init", "-q"]);
+        fs::create_dir(repo.join("sub")).unwrap();
+        for name in ["clean.rs", "edited.rs", "sub/renamed.rs"] {
+            fs::write(repo.join(name), name).unwrap();
+        }
+        run_git(repo, &["add", "."]);
+        run_git(repo, &["commit", "-qm", "init"]);
+        fs::write(repo.join("edited.rs"), "changed").unwrap();
+        fs::write(repo.join("sub/untracked.rs"), "").unwrap();
+        run_git(repo, &["mv", "sub/renamed.rs", "sub/moved.rs"]);
+
+        // Searching from a subdirectory still resolves paths against the repository root
+        let base = repo.join("sub");
+        let any = Box::new(GlobEntryMatcher::new("*").unwrap());
+        let matcher = GitModifiedMatcher::new(any, &base).unwrap();
+        let changed = |path: &Path| matcher.is_path_match(path, false);
+        assert!(changed(&base.join("untracked.rs")));
+        assert!(changed(&base.join("moved.rs")));
+        assert!(changed(&repo.join("edited.rs")));
+        assert!(!changed(&repo.join("clean.rs")));
+        assert!(!changed(&base.join("renamed.rs")));
+    }
+
+    #[test]
+    fn outside_a_repository_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let any = Box::new(GlobEntryMatcher::new("*").unwrap());
+        let err = GitModifiedMatcher::new(any, dir.path()).err().unwrap();
+        assert!(err.ends_with("is not inside a git repository"), "{err}");
+    }
+}
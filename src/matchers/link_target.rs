@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::Path;
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Runs another matcher against where symlinks point, skipping everything else
+pub struct LinkTargetMatcher {
+    inner: Box<dyn EntryMatcher>,
+}
+
+impl LinkTargetMatcher {
+    pub fn new(inner: Box<dyn EntryMatcher>) -> Self {
+        LinkTargetMatcher { inner }
+    }
+}
+
+impl EntryMatcher for LinkTargetMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        entry.path_is_symlink() && self.is_path_match(entry.path(), full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        fs::read_link(path).is_ok_and(|target| self.inner.is_path_match(&target, full_path))
+    }
+
+    // No match_ranges: the matched text is the target, not the displayed link path
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+    use std::os::unix::fs::symlink;
+    use walkdir::WalkDir;
+
+    fn targets(pattern: &str) -> LinkTargetMatcher {
+        LinkTargetMatcher::new(Box::new(GlobEntryMatcher::new(pattern).unwrap()))
+    }
+
+    #[test]
+    fn symlinks_match_by_where_they_point() {
+        let dir = tempfile::tempdir().unwrap();
+        let link = dir.path().join("current");
+        symlink("releases/v2.tar", &link).unwrap();
+
+        // Names match the target's last component, full paths the whole target
+        assert!(targets("*.tar").is_path_match(&link, false));
+        assert!(!targets("current").is_path_match(&link, false));
+        assert!(targets("releases/*.tar").is_path_match(&link, true));
+        assert!(!targets("*.tar").is_path_match(&link, true));
+        // Dangling links still have a target to match
+        let entry = WalkDir::new(dir.path())
+            .min_depth(1)
+            .into_iter()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(targets("v2*").is_match(&entry, false));
+    }
+
+    #[test]
+    fn everything_else_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("plain.tar");
+        fs::write(&file, "").unwrap();
+        let entry = WalkDir::new(&file).into_iter().next().unwrap().unwrap();
+        assert!(!targets("*").is_match(&entry, false));
+        assert!(!targets("*").is_path_match(&file, false));
+        assert!(targets("*").match_ranges("plain.tar").is_none());
+    }
+}
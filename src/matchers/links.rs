@@ -0,0 +1,116 @@
+use std::ops::Range;
+use std::path::Path;
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Restricts another matcher to files with at least `min_links` hard links
+pub struct LinkCountMatcher {
+    inner: Box<dyn EntryMatcher>,
+    min_links: u64,
+}
+
+impl LinkCountMatcher {
+    /// Fails on platforms without hard-link counts in file metadata
+    pub fn new(inner: Box<dyn EntryMatcher>, min_links: u64) -> Result<Self, String> {
+        if cfg!(unix) {
+            Ok(LinkCountMatcher { inner, min_links })
+        } else {
+            Err("Hard-link filters (--min-links, --links-only) are only supported on Unix".into())
+        }
+    }
+
+    #[cfg(unix)]
+    fn has_enough_links(&self, metadata: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+        metadata.is_file() && metadata.nlink() >= self.min_links
+    }
+
+    #[cfg(not(unix))]
+    fn has_enough_links(&self, _metadata: &std::fs::Metadata) -> bool {
+        false
+    }
+}
+
+impl EntryMatcher for LinkCountMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.inner.is_match(entry, full_path)
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| self.has_enough_links(&metadata))
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.inner.is_path_match(path, full_path)
+            && path
+                .symlink_metadata()
+                .is_ok_and(|metadata| self.has_enough_links(&metadata))
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+    use std::fs;
+    use walkdir::WalkDir;
+
+    fn matcher(min_links: u64) -> LinkCountMatcher {
+        let any = Box::new(GlobEntryMatcher::new("*").unwrap());
+        LinkCountMatcher::new(any, min_links).unwrap()
+    }
+
+    /// Whether the walked entry at `path` matches
+    fn entry_matches(matcher: &LinkCountMatcher, path: &Path) -> bool {
+        let entry = WalkDir::new(path).into_iter().next().unwrap().unwrap();
+        matcher.is_match(&entry, false)
+    }
+
+    #[test]
+    fn counts_hard_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let single = dir.path().join("single");
+        let linked = dir.path().join("linked");
+        fs::write(&single, "").unwrap();
+        fs::write(&linked, "").unwrap();
+        fs::hard_link(&linked, dir.path().join("other")).unwrap();
+
+        for (min_links, single_matches, linked_matches) in
+            [(1, true, true), (2, false, true), (3, false, false)]
+        {
+            let matcher = matcher(min_links);
+            assert_eq!(entry_matches(&matcher, &single), single_matches);
+            assert_eq!(entry_matches(&matcher, &linked), linked_matches);
+            assert_eq!(matcher.is_path_match(&linked, false), linked_matches);
+        }
+    }
+
+    #[test]
+    fn only_files_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        fs::create_dir_all(sub.join("child")).unwrap();
+        // A directory with a subdirectory has at least three links
+        assert!(!entry_matches(&matcher(2), &sub));
+
+        let target = dir.path().join("target");
+        fs::write(&target, "").unwrap();
+        fs::hard_link(&target, dir.path().join("twin")).unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        // Symlinks are counted as themselves, not their target
+        assert!(!entry_matches(&matcher(2), &link));
+        assert!(!matcher(2).is_path_match(&link, false));
+    }
+
+    #[test]
+    fn removed_paths_do_not_match() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!matcher(1).is_path_match(&dir.path().join("gone"), false));
+    }
+}
@@ -1,34 +1,195 @@
-use globset::{Glob, GlobMatcher};
-use regex::Regex;
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
-use walkdir::DirEntry;
+use walkdir::{DirEntry, WalkDir};
+
+mod component;
+mod content;
+mod extension;
+mod git;
+mod link_target;
+mod links;
+mod name_length;
+mod relative;
+mod split_case;
+mod time;
+
+pub use component::ComponentMatcher;
+pub use content::{ContentMatcher, matching_lines};
+pub use extension::ExtensionMatcher;
+pub use git::GitModifiedMatcher;
+pub use link_target::LinkTargetMatcher;
+pub use links::LinkCountMatcher;
+pub use name_length::NameLengthMatcher;
+pub use relative::RelativePathMatcher;
+pub use split_case::SplitCaseMatcher;
+pub use time::{AccessTimeCheck, TimeField, TimeMatcher};
 
 /// A trait for matching file entries against patterns
 pub trait EntryMatcher: Send + Sync {
     fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool;
+
+    /// Match a bare path, for entries that no longer exist on disk.
+    ///
+    /// The default looks the path up and defers to `is_match`, so it never
+    /// matches a path that's gone; override it to match removed entries.
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        WalkDir::new(path)
+            .max_depth(0)
+            .into_iter()
+            .next()
+            .and_then(Result::ok)
+            .is_some_and(|entry| self.is_match(&entry, full_path))
+    }
+
+    /// Byte ranges of the matched portions of `text`, for matchers that can locate them.
+    ///
+    /// Ranges are in ascending order and always fall on character boundaries.
+    fn match_ranges(&self, _text: &str) -> Option<Vec<Range<usize>>> {
+        None
+    }
+}
+
+/// The name a path would have as a walked entry
+fn entry_name(path: &Path) -> &std::ffi::OsStr {
+    path.file_name().unwrap_or(path.as_os_str())
 }
 
 /// Glob-based matcher
 pub struct GlobEntryMatcher {
     matcher: GlobMatcher,
+    /// Full-path variant where `*` stops at separators and only `**` crosses them
+    path_matcher: GlobMatcher,
+    /// Literal runs of the pattern, used to approximate match highlighting;
+    /// ASCII-lowercased up front when ignoring case
+    literals: Vec<String>,
+    ignore_case: bool,
 }
 
 impl GlobEntryMatcher {
     pub fn new(pattern: &str) -> Result<Self, globset::Error> {
+        Self::with_case(pattern, false)
+    }
+
+    /// Build a matcher that optionally ignores case
+    pub fn with_case(pattern: &str, ignore_case: bool) -> Result<Self, globset::Error> {
+        let literals = glob_literals(pattern)
+            .into_iter()
+            .map(|literal| {
+                if ignore_case {
+                    literal.to_ascii_lowercase()
+                } else {
+                    literal
+                }
+            })
+            .collect();
         Ok(GlobEntryMatcher {
-            matcher: Glob::new(pattern)?.compile_matcher(),
+            matcher: GlobBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()?
+                .compile_matcher(),
+            path_matcher: GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .case_insensitive(ignore_case)
+                .build()?
+                .compile_matcher(),
+            literals,
+            ignore_case,
         })
     }
 }
 
+/// Split a glob into its literal runs, dropping wildcards, classes, and alternations
+fn glob_literals(pattern: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut current = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            '*' | '?' | '[' | '{' => {
+                if !current.is_empty() {
+                    literals.push(std::mem::take(&mut current));
+                }
+                let close = match c {
+                    '[' => Some(']'),
+                    '{' => Some('}'),
+                    _ => None,
+                };
+                if let Some(close) = close {
+                    chars.by_ref().take_while(|&c| c != close).for_each(drop);
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        literals.push(current);
+    }
+    literals
+}
+
 impl EntryMatcher for GlobEntryMatcher {
     fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
         if full_path {
-            self.matcher.is_match(entry.path())
+            self.path_matcher.is_match(entry.path())
         } else {
             self.matcher.is_match(entry.file_name())
         }
     }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        if full_path {
+            self.path_matcher.is_match(path)
+        } else {
+            self.matcher.is_match(entry_name(path))
+        }
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        // Find each literal run in order; wildcards cover whatever lies between them
+        let mut ranges = Vec::new();
+        let mut pos = 0;
+        for literal in &self.literals {
+            let found = if self.ignore_case {
+                find_ascii_lowercase(&text[pos..], literal)
+            } else {
+                text[pos..].find(literal.as_str())
+            };
+            let start = pos + found?;
+            pos = start + literal.len();
+            ranges.push(start..pos);
+        }
+        (!ranges.is_empty()).then_some(ranges)
+    }
+}
+
+/// Find `needle`, already ASCII-lowercased, in `haystack` ignoring ASCII case,
+/// without allocating a lowercased copy of every name
+fn find_ascii_lowercase(haystack: &str, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    if needle.is_empty() {
+        return Some(0);
+    }
+    // Non-ASCII bytes compare exactly, so a match always starts on a char boundary
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| {
+            window
+                .iter()
+                .zip(needle)
+                .all(|(&b, &n)| b.to_ascii_lowercase() == n)
+        })
 }
 
 /// Regex-based matcher
@@ -38,8 +199,15 @@ pub struct RegexEntryMatcher {
 
 impl RegexEntryMatcher {
     pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Self::with_case(pattern, false)
+    }
+
+    /// Build a matcher that optionally ignores case
+    pub fn with_case(pattern: &str, ignore_case: bool) -> Result<Self, regex::Error> {
         Ok(RegexEntryMatcher {
-            regex: Regex::new(pattern)?,
+            regex: RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()?,
         })
     }
 }
@@ -53,30 +221,461 @@ impl EntryMatcher for RegexEntryMatcher {
         };
         self.regex.is_match(&text)
     }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        let text = if full_path {
+            path.to_string_lossy()
+        } else {
+            entry_name(path).to_string_lossy()
+        };
+        self.regex.is_match(&text)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        let ranges: Vec<Range<usize>> = self
+            .regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect();
+        (!ranges.is_empty()).then_some(ranges)
+    }
 }
 
-/// Create a matcher based on the pattern type
-pub fn create_matcher(pattern: &str, use_regex: bool) -> Result<Box<dyn EntryMatcher>, String> {
-    if use_regex {
-        match RegexEntryMatcher::new(pattern) {
-            Ok(m) => Ok(Box::new(m)),
-            Err(e) => Err(format!("Invalid regex pattern: {e}")),
+/// Matches paths ending in the given components, so `src/mod.rs` matches
+/// `a/src/mod.rs` but `r.rs` doesn't match `bar.rs`
+pub struct SuffixEntryMatcher {
+    suffix: PathBuf,
+}
+
+impl SuffixEntryMatcher {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        if Path::new(pattern).components().next().is_none() {
+            return Err(format!(
+                "Invalid suffix `{pattern}`: expected at least one path component"
+            ));
+        }
+        Ok(SuffixEntryMatcher {
+            suffix: PathBuf::from(pattern),
+        })
+    }
+}
+
+impl EntryMatcher for SuffixEntryMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.is_path_match(entry.path(), full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, _full_path: bool) -> bool {
+        path.ends_with(&self.suffix)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        let suffix = self.suffix.to_str()?;
+        let start = text.len().checked_sub(suffix.len())?;
+        let at_boundary = start == 0 || text[..start].ends_with(std::path::is_separator);
+        let range = start..text.len();
+        (text.ends_with(suffix) && at_boundary).then(|| vec![range])
+    }
+}
+
+/// Create a component-wise suffix matcher for each pattern
+pub fn create_suffix_matcher(patterns: &[String]) -> Result<Box<dyn EntryMatcher>, String> {
+    let mut matchers = patterns
+        .iter()
+        .map(|p| SuffixEntryMatcher::new(p).map(|m| Box::new(m) as Box<dyn EntryMatcher>))
+        .collect::<Result<Vec<_>, _>>()?;
+    if matchers.len() == 1 {
+        return Ok(matchers.remove(0));
+    }
+    Ok(Box::new(AnyMatcher::new(matchers)))
+}
+
+/// Why a pattern couldn't be turned into a matcher
+#[derive(Debug)]
+pub enum MatcherError {
+    InvalidGlob(globset::Error),
+    InvalidRegex(regex::Error),
+    EmptyPattern,
+}
+
+impl fmt::Display for MatcherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatcherError::InvalidGlob(e) => write!(f, "Invalid glob pattern: {e}"),
+            MatcherError::InvalidRegex(e) => write!(f, "Invalid regex pattern: {e}"),
+            MatcherError::EmptyPattern => write!(f, "Empty pattern: give a glob such as `*`"),
+        }
+    }
+}
+
+impl Error for MatcherError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MatcherError::InvalidGlob(e) => Some(e),
+            MatcherError::InvalidRegex(e) => Some(e),
+            MatcherError::EmptyPattern => None,
         }
+    }
+}
+
+/// How a pattern treats letter case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseMode {
+    #[default]
+    Sensitive,
+    Insensitive,
+    /// Ignore case unless the pattern has an uppercase letter
+    Smart,
+}
+
+/// Create a matcher based on the pattern type and case mode
+pub fn create_matcher(
+    pattern: &str,
+    use_regex: bool,
+    case: CaseMode,
+) -> Result<Box<dyn EntryMatcher>, MatcherError> {
+    if pattern.is_empty() {
+        return Err(MatcherError::EmptyPattern);
+    }
+    let ignore_case = match case {
+        CaseMode::Sensitive => false,
+        CaseMode::Insensitive => true,
+        CaseMode::Smart => !has_uppercase(pattern, use_regex),
+    };
+    if use_regex {
+        RegexEntryMatcher::with_case(pattern, ignore_case)
+            .map(|m| Box::new(m) as Box<dyn EntryMatcher>)
+            .map_err(MatcherError::InvalidRegex)
     } else {
-        match GlobEntryMatcher::new(pattern) {
-            Ok(m) => Ok(Box::new(m)),
-            Err(e) => Err(format!("Invalid glob pattern: {e}")),
+        GlobEntryMatcher::with_case(pattern, ignore_case)
+            .map(|m| Box::new(m) as Box<dyn EntryMatcher>)
+            .map_err(MatcherError::InvalidGlob)
+    }
+}
+
+/// Whether a pattern has uppercase letters it would match literally, skipping
+/// escapes such as `\W` and `\p{Greek}` in regexes
+fn has_uppercase(pattern: &str, use_regex: bool) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if use_regex => {
+                if let Some('p' | 'P') = chars.next()
+                    && chars.clone().next() == Some('{')
+                {
+                    chars.by_ref().take_while(|&c| c != '}').for_each(drop);
+                }
+            }
+            c if c.is_uppercase() => return true,
+            _ => {}
         }
     }
+    false
+}
+
+/// Matches when any of its inner matchers match
+pub struct AnyMatcher {
+    matchers: Vec<Box<dyn EntryMatcher>>,
+}
+
+impl AnyMatcher {
+    pub fn new(matchers: Vec<Box<dyn EntryMatcher>>) -> Self {
+        AnyMatcher { matchers }
+    }
+}
+
+impl EntryMatcher for AnyMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.matchers.iter().any(|m| m.is_match(entry, full_path))
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.matchers
+            .iter()
+            .any(|m| m.is_path_match(path, full_path))
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.matchers.iter().find_map(|m| m.match_ranges(text))
+    }
+}
+
+/// Matches entries accepted by `include` that are not matched by `exclude`
+pub struct ExcludeMatcher {
+    include: Box<dyn EntryMatcher>,
+    exclude: Box<dyn EntryMatcher>,
+}
+
+impl ExcludeMatcher {
+    pub fn new(include: Box<dyn EntryMatcher>, exclude: Box<dyn EntryMatcher>) -> Self {
+        ExcludeMatcher { include, exclude }
+    }
+}
+
+impl EntryMatcher for ExcludeMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.include.is_match(entry, full_path) && !self.exclude.is_match(entry, full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.include.is_path_match(path, full_path) && !self.exclude.is_path_match(path, full_path)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.include.match_ranges(text)
+    }
+}
+
+/// Create a matcher that accepts any of the given patterns
+pub fn create_any_matcher(
+    patterns: &[String],
+    use_regex: bool,
+    case: CaseMode,
+) -> Result<Box<dyn EntryMatcher>, MatcherError> {
+    if patterns.len() == 1 {
+        return create_matcher(&patterns[0], use_regex, case);
+    }
+    let matchers = patterns
+        .iter()
+        .map(|p| create_matcher(p, use_regex, case))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Box::new(AnyMatcher::new(matchers)))
+}
+
+/// Read newline-delimited patterns from a file, skipping blank lines and `#` comments
+pub fn read_pattern_file(path: &Path) -> Result<Vec<String>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read pattern file {}: {e}", path.display()))?;
+    let patterns: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if patterns.is_empty() {
+        return Err(format!("No patterns found in {}", path.display()));
+    }
+    Ok(patterns)
 }
 
 /// Helper function to determine if an entry should be processed based on file type filters
 pub fn should_process_entry(entry: &DirEntry, files_only: bool, dirs_only: bool) -> bool {
-    if files_only && !entry.file_type().is_file() {
-        return false;
+    type_filter_reason(entry, files_only, dirs_only).is_none()
+}
+
+/// Explain why the file type filters reject an entry, if they do
+pub fn type_filter_reason(
+    entry: &DirEntry,
+    files_only: bool,
+    dirs_only: bool,
+) -> Option<&'static str> {
+    let file_type = entry.file_type();
+    kind_filter_reason(
+        file_type.is_file(),
+        file_type.is_dir(),
+        files_only,
+        dirs_only,
+    )
+}
+
+/// [`type_filter_reason`] for an entry already known to be a file or directory
+pub(crate) fn kind_filter_reason(
+    is_file: bool,
+    is_dir: bool,
+    files_only: bool,
+    dirs_only: bool,
+) -> Option<&'static str> {
+    if files_only && !is_file {
+        return Some("not a file (--files-only)");
+    }
+    if dirs_only && !is_dir {
+        return Some("not a directory (--dirs-only)");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(pattern: &str, use_regex: bool) -> MatcherError {
+        create_matcher(pattern, use_regex, CaseMode::Sensitive)
+            .err()
+            .unwrap_or_else(|| panic!("{pattern} compiled"))
+    }
+
+    #[test]
+    fn errors_keep_what_went_wrong() {
+        assert!(matches!(error("", false), MatcherError::EmptyPattern));
+        assert!(matches!(error("", true), MatcherError::EmptyPattern));
+        assert!(matches!(error("a[b", false), MatcherError::InvalidGlob(_)));
+        assert!(matches!(error("a(b", true), MatcherError::InvalidRegex(_)));
+        // The same text is fine as the other kind of pattern
+        assert!(create_matcher("a(b", false, CaseMode::Sensitive).is_ok());
+    }
+
+    #[test]
+    fn errors_explain_themselves() {
+        let empty = error("", false);
+        assert_eq!(empty.to_string(), "Empty pattern: give a glob such as `*`");
+        assert!(empty.source().is_none());
+
+        let glob = error("a[b", false);
+        assert!(
+            glob.to_string().starts_with("Invalid glob pattern: "),
+            "{glob}"
+        );
+        assert!(glob.source().is_some());
+
+        let regex = error("a(b", true);
+        assert!(
+            regex.to_string().starts_with("Invalid regex pattern: "),
+            "{regex}"
+        );
+        assert!(regex.source().is_some());
+    }
+
+    #[test]
+    fn any_matcher_fails_on_any_bad_pattern() {
+        let patterns = ["*.rs", "a[b"].map(String::from);
+        let err = create_any_matcher(&patterns, false, CaseMode::Sensitive)
+            .err()
+            .unwrap();
+        assert!(matches!(err, MatcherError::InvalidGlob(_)));
+    }
+
+    fn full_path_matches(pattern: &str, path: &str) -> bool {
+        GlobEntryMatcher::new(pattern)
+            .unwrap()
+            .is_path_match(Path::new(path), true)
+    }
+
+    #[test]
+    fn single_star_stays_within_a_component() {
+        assert!(full_path_matches("*/foo", "a/foo"));
+        assert!(!full_path_matches("*/foo", "a/b/foo"));
+        assert!(!full_path_matches("*.rs", "src/main.rs"));
+        assert!(full_path_matches("src/*.rs", "src/main.rs"));
+        assert!(!full_path_matches("src/*.rs", "src/bin/main.rs"));
+    }
+
+    #[test]
+    fn double_star_crosses_separators() {
+        for path in ["foo", "a/foo", "a/b/foo"] {
+            assert!(full_path_matches("**/foo", path), "{path}");
+        }
+        assert!(!full_path_matches("**/foo", "a/foobar"));
+        assert!(full_path_matches("**/*.rs", "src/bin/main.rs"));
+        assert!(full_path_matches("src/**", "src/bin/main.rs"));
+    }
+
+    #[test]
+    fn names_ignore_separator_rules() {
+        let matcher = GlobEntryMatcher::new("*.rs").unwrap();
+        assert!(matcher.is_path_match(Path::new("src/bin/main.rs"), false));
+        assert!(!matcher.is_path_match(Path::new("src.rs/main"), false));
+    }
+
+    fn name_matches(pattern: &str, use_regex: bool, case: CaseMode, name: &str) -> bool {
+        create_matcher(pattern, use_regex, case)
+            .ok()
+            .unwrap()
+            .is_path_match(Path::new(name), false)
+    }
+
+    #[test]
+    fn smart_case_ignores_case_for_lowercase_patterns() {
+        assert!(name_matches("*.txt", false, CaseMode::Smart, "README.TXT"));
+        assert!(name_matches("read", true, CaseMode::Smart, "ReadMe"));
+        assert!(!name_matches(
+            "*.txt",
+            false,
+            CaseMode::Sensitive,
+            "README.TXT"
+        ));
+    }
+
+    #[test]
+    fn smart_case_respects_uppercase_patterns() {
+        assert!(name_matches("*.TXT", false, CaseMode::Smart, "a.TXT"));
+        assert!(!name_matches("*.TXT", false, CaseMode::Smart, "a.txt"));
+        assert!(!name_matches("Read", true, CaseMode::Smart, "readme"));
+    }
+
+    #[test]
+    fn regex_escapes_are_not_uppercase_letters() {
+        for pattern in [r"\S+\.rs", r"\W\D\B", r"\p{Greek}", r"\P{Lu}x"] {
+            assert!(!has_uppercase(pattern, true), "{pattern}");
+        }
+        assert!(has_uppercase(r"\SA", true));
+        assert!(has_uppercase(r"\p{Greek}X", true));
+        // Globs have no such escapes
+        assert!(has_uppercase(r"\S", false));
+        assert!(name_matches(r"\S+\.rs", true, CaseMode::Smart, "MAIN.RS"));
+    }
+
+    #[test]
+    fn explicit_case_modes_ignore_the_pattern() {
+        assert!(name_matches("*.TXT", false, CaseMode::Insensitive, "a.txt"));
+        assert!(name_matches("*.txt", false, CaseMode::Insensitive, "A.TXT"));
+        assert!(!name_matches("*.txt", false, CaseMode::Sensitive, "a.TXT"));
+    }
+
+    /// Matched ranges as `(start, end)` pairs, empty when none were located
+    fn spans(matcher: &dyn EntryMatcher, text: &str) -> Vec<(usize, usize)> {
+        matcher
+            .match_ranges(text)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|range| (range.start, range.end))
+            .collect()
     }
-    if dirs_only && !entry.file_type().is_dir() {
-        return false;
+
+    #[test]
+    fn glob_literals_drop_wildcards_classes_and_alternations() {
+        assert_eq!(glob_literals("*foo?bar*"), ["foo", "bar"]);
+        assert_eq!(glob_literals("a[xyz]b{c,d}e"), ["a", "b", "e"]);
+        assert_eq!(glob_literals(r"lit\*eral"), ["lit*eral"]);
+        assert!(glob_literals("**").is_empty());
+    }
+
+    #[test]
+    fn glob_ranges_cover_literal_runs_in_order() {
+        let glob = |pattern: &str| GlobEntryMatcher::new(pattern).unwrap();
+        assert_eq!(spans(&glob("*test*"), "my_test.rs"), [(3, 7)]);
+        assert_eq!(spans(&glob("a*b*a"), "a_b_a"), [(0, 1), (2, 3), (4, 5)]);
+        assert!(glob("*.rs").match_ranges("main.txt").is_none());
+        assert!(glob("*").match_ranges("anything").is_none());
+        let ignoring = GlobEntryMatcher::with_case("*readme*", true).unwrap();
+        assert_eq!(spans(&ignoring, "My-README.md"), [(3, 9)]);
+    }
+
+    #[test]
+    fn regex_ranges_find_every_match() {
+        let matcher = RegexEntryMatcher::new("o+").unwrap();
+        assert_eq!(spans(&matcher, "foo_bo.o"), [(1, 3), (5, 6), (7, 8)]);
+        // Empty matches would highlight nothing
+        let empty = RegexEntryMatcher::new("x*").unwrap();
+        assert!(empty.match_ranges("abc").is_none());
+    }
+
+    #[test]
+    fn suffix_ranges_start_at_a_component() {
+        let matcher = SuffixEntryMatcher::new("src/mod.rs").unwrap();
+        assert_eq!(spans(&matcher, "a/src/mod.rs"), [(2, 12)]);
+        assert!(matcher.match_ranges("a/xsrc/mod.rs").is_none());
+    }
+
+    #[test]
+    fn any_matcher_highlights_with_the_first_that_locates_a_match() {
+        let matcher = create_any_matcher(
+            &["*.md".to_string(), "*main*".to_string()],
+            false,
+            CaseMode::Sensitive,
+        )
+        .unwrap();
+        assert_eq!(spans(matcher.as_ref(), "main.rs"), [(0, 4)]);
+        assert_eq!(spans(matcher.as_ref(), "main.md"), [(4, 7)]);
     }
-    true
 }
@@ -0,0 +1,114 @@
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::path::Path;
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Restricts another matcher to entries whose name is longer and/or shorter
+/// than the given number of characters
+pub struct NameLengthMatcher {
+    inner: Box<dyn EntryMatcher>,
+    longer_than: Option<usize>,
+    shorter_than: Option<usize>,
+}
+
+impl NameLengthMatcher {
+    pub fn new(
+        inner: Box<dyn EntryMatcher>,
+        longer_than: Option<usize>,
+        shorter_than: Option<usize>,
+    ) -> Result<Self, String> {
+        if let (Some(longer), Some(shorter)) = (longer_than, shorter_than)
+            && longer.checked_add(1).is_none_or(|limit| shorter <= limit)
+        {
+            return Err(format!(
+                "No name is longer than {longer} and shorter than {shorter} characters"
+            ));
+        }
+        Ok(NameLengthMatcher {
+            inner,
+            longer_than,
+            shorter_than,
+        })
+    }
+
+    /// Length in characters, counting each invalid UTF-8 sequence as one
+    fn length_ok(&self, name: &OsStr) -> bool {
+        let len = name.to_string_lossy().chars().count();
+        self.longer_than.is_none_or(|min| len > min)
+            && self.shorter_than.is_none_or(|max| len < max)
+    }
+}
+
+impl EntryMatcher for NameLengthMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.length_ok(entry.file_name()) && self.inner.is_match(entry, full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        path.file_name().is_some_and(|name| self.length_ok(name))
+            && self.inner.is_path_match(path, full_path)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+
+    fn matcher(longer_than: Option<usize>, shorter_than: Option<usize>) -> NameLengthMatcher {
+        let any = Box::new(GlobEntryMatcher::new("*").unwrap());
+        NameLengthMatcher::new(any, longer_than, shorter_than).unwrap()
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        // Four characters, twelve bytes
+        let name = "日本語字";
+        assert_eq!(name.len(), 12);
+        assert!(matcher(Some(3), Some(5)).is_path_match(Path::new(name), false));
+        assert!(!matcher(Some(4), None).is_path_match(Path::new(name), false));
+        assert!(!matcher(None, Some(5)).is_path_match(Path::new("dir/ÅÄÖöü"), false));
+        assert!(matcher(None, Some(6)).is_path_match(Path::new("dir/ÅÄÖöü"), false));
+    }
+
+    #[test]
+    fn bounds_are_strict() {
+        let between = matcher(Some(2), Some(5));
+        let matches = |name: &str| between.is_path_match(Path::new(name), false);
+        assert!(!matches("ab"));
+        assert!(matches("abc"));
+        assert!(matches("abcd"));
+        assert!(!matches("abcde"));
+        // Only the name counts, not the directories above it
+        assert!(matches("a/very/long/path/abc"));
+    }
+
+    #[test]
+    fn empty_ranges_are_rejected() {
+        let any = || Box::new(GlobEntryMatcher::new("*").unwrap()) as Box<dyn EntryMatcher>;
+        let err = NameLengthMatcher::new(any(), Some(3), Some(4))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            "No name is longer than 3 and shorter than 4 characters"
+        );
+        assert!(NameLengthMatcher::new(any(), Some(3), Some(3)).is_err());
+        assert!(NameLengthMatcher::new(any(), Some(3), Some(5)).is_ok());
+    }
+
+    #[test]
+    fn huge_lower_bound_does_not_overflow() {
+        let any = || Box::new(GlobEntryMatcher::new("*").unwrap()) as Box<dyn EntryMatcher>;
+        assert!(NameLengthMatcher::new(any(), Some(usize::MAX), Some(3)).is_err());
+        assert!(NameLengthMatcher::new(any(), Some(usize::MAX), Some(usize::MAX)).is_err());
+        assert!(!matcher(Some(usize::MAX), None).is_path_match(Path::new("a"), false));
+    }
+}
@@ -0,0 +1,69 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Runs another matcher against each path relative to the search root
+pub struct RelativePathMatcher {
+    inner: Box<dyn EntryMatcher>,
+    base: PathBuf,
+}
+
+impl RelativePathMatcher {
+    pub fn new(inner: Box<dyn EntryMatcher>, base: &Path) -> Self {
+        RelativePathMatcher {
+            inner,
+            base: crate::paths::strip_extended_prefix(base),
+        }
+    }
+}
+
+impl EntryMatcher for RelativePathMatcher {
+    fn is_match(&self, entry: &DirEntry, _full_path: bool) -> bool {
+        self.is_path_match(entry.path(), true)
+    }
+
+    fn is_path_match(&self, path: &Path, _full_path: bool) -> bool {
+        let path = crate::paths::strip_extended_prefix(path);
+        let relative = path.strip_prefix(&self.base).unwrap_or(&path);
+        self.inner.is_path_match(relative, true)
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+
+    fn relative(pattern: &str, base: &str) -> RelativePathMatcher {
+        let glob = Box::new(GlobEntryMatcher::new(pattern).unwrap());
+        RelativePathMatcher::new(glob, Path::new(base))
+    }
+
+    #[test]
+    fn patterns_are_anchored_at_the_search_root() {
+        let matcher = relative("src/*.rs", "/work/project");
+        let matches = |path: &str| matcher.is_path_match(Path::new(path), false);
+        assert!(matches("/work/project/src/main.rs"));
+        assert!(!matches("/work/project/vendor/src/main.rs"));
+        assert!(!matches("/work/project/src/bin/main.rs"));
+    }
+
+    #[test]
+    fn the_root_prefix_is_matched_as_given() {
+        let matcher = relative("*.rs", ".");
+        assert!(matcher.is_path_match(Path::new("./main.rs"), false));
+        assert!(!matcher.is_path_match(Path::new("./src/main.rs"), false));
+        // Paths outside the root are matched whole
+        assert!(!matcher.is_path_match(Path::new("/elsewhere/main.rs"), false));
+        assert!(
+            relative("/elsewhere/*.rs", ".").is_path_match(Path::new("/elsewhere/main.rs"), false)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use globset::{GlobBuilder, GlobMatcher};
+use std::ops::Range;
+use std::path::Path;
+
+use walkdir::DirEntry;
+
+use super::{EntryMatcher, GlobEntryMatcher};
+
+/// Full-path glob matcher with separate case settings for the directory
+/// components and the final name
+pub struct SplitCaseMatcher {
+    /// Everything before the last separator; `None` when the pattern is only a name
+    dir: Option<GlobMatcher>,
+    /// Whether `dir` ends in `/**`, which should also match zero directories
+    dir_any_depth: bool,
+    name: GlobEntryMatcher,
+}
+
+impl SplitCaseMatcher {
+    pub fn new(
+        pattern: &str,
+        ignore_case_path: bool,
+        ignore_case_name: bool,
+    ) -> Result<Self, String> {
+        let split = last_separator(pattern);
+        let (dir, name) = match split {
+            // A leading separator is the root directory itself
+            Some(pos) => (Some(&pattern[..pos.max(1)]), &pattern[pos + 1..]),
+            None => (None, pattern),
+        };
+        if name.contains("**") {
+            return Err(format!(
+                "Invalid pattern `{pattern}`: the last component can't contain `**` when path and name case differ"
+            ));
+        }
+        let dir = dir
+            .map(|dir| {
+                GlobBuilder::new(dir)
+                    .literal_separator(true)
+                    .case_insensitive(ignore_case_path)
+                    .build()
+                    .map(|glob| glob.compile_matcher())
+            })
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        Ok(SplitCaseMatcher {
+            dir,
+            dir_any_depth: pattern[..split.unwrap_or(0)].ends_with("/**"),
+            name: GlobEntryMatcher::with_case(name, ignore_case_name).map_err(|e| e.to_string())?,
+        })
+    }
+
+    fn dir_matches(&self, parent: &Path) -> bool {
+        match &self.dir {
+            Some(dir) => {
+                dir.is_match(parent) || (self.dir_any_depth && dir.is_match(parent.join("")))
+            }
+            None => parent.as_os_str().is_empty(),
+        }
+    }
+}
+
+/// Byte offset of the last `/` outside of `[...]`, `{...}`, and escapes
+fn last_separator(pattern: &str) -> Option<usize> {
+    let mut last = None;
+    let mut depth = 0usize;
+    let mut chars = pattern.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            '/' if depth == 0 => last = Some(i),
+            _ => {}
+        }
+    }
+    last
+}
+
+impl EntryMatcher for SplitCaseMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.is_path_match(entry.path(), full_path)
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        if !self.name.is_path_match(path, false) {
+            return false;
+        }
+        !full_path || self.dir_matches(path.parent().unwrap_or(Path::new("")))
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        // Only the name is highlighted, within the last component of the shown path
+        let start = text.rfind(std::path::is_separator).map_or(0, |pos| pos + 1);
+        let ranges = self.name.match_ranges(&text[start..])?;
+        Some(
+            ranges
+                .into_iter()
+                .map(|r| r.start + start..r.end + start)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path_folds: bool, name_folds: bool, path: &str) -> bool {
+        SplitCaseMatcher::new(pattern, path_folds, name_folds)
+            .unwrap()
+            .is_path_match(Path::new(path), true)
+    }
+
+    #[test]
+    fn path_and_name_fold_case_independently() {
+        // (ignore case in path, in name, candidate, matched)
+        for (path_folds, name_folds, path, matched) in [
+            (true, false, "src/Docs/Main.rs", true),
+            (true, false, "src/Docs/main.rs", false),
+            (false, true, "src/Docs/main.RS", true),
+            (false, true, "src/docs/Main.rs", false),
+            (true, true, "SRC/docs/MAIN.RS", true),
+            (false, false, "src/docs/Main.rs", false),
+            (false, false, "src/Docs/Main.rs", true),
+        ] {
+            assert_eq!(
+                matches("src/Docs/Main.rs", path_folds, name_folds, path),
+                matched,
+                "path {path_folds}, name {name_folds}: {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn wildcards_keep_each_sides_case() {
+        assert!(matches("**/Build/*.rs", true, false, "a/BUILD/x.rs"));
+        assert!(matches("**/Build/*.rs", true, false, "build/x.rs"));
+        assert!(!matches("**/Build/*.rs", true, false, "build/x.RS"));
+        assert!(matches("**/Build/*.rs", false, true, "a/Build/x.RS"));
+        assert!(!matches("**/Build/*.rs", false, true, "a/build/x.rs"));
+    }
+
+    #[test]
+    fn names_alone_ignore_the_path_unless_matching_full_paths() {
+        let matcher = SplitCaseMatcher::new("Readme.md", true, false).unwrap();
+        assert!(matcher.is_path_match(Path::new("any/DIR/Readme.md"), false));
+        assert!(!matcher.is_path_match(Path::new("any/DIR/README.md"), false));
+        assert!(!matcher.is_path_match(Path::new("any/DIR/Readme.md"), true));
+        assert!(matcher.is_path_match(Path::new("Readme.md"), true));
+    }
+
+    #[test]
+    fn separators_inside_brackets_and_braces_are_not_splits() {
+        assert_eq!(last_separator("a/b/c"), Some(3));
+        assert_eq!(last_separator("a/{b,c/d}"), Some(1));
+        assert_eq!(last_separator("a/[/]x"), Some(1));
+        assert_eq!(last_separator("a\\/b"), None);
+        assert_eq!(last_separator("name"), None);
+    }
+
+    #[test]
+    fn double_star_in_the_name_is_rejected() {
+        assert!(SplitCaseMatcher::new("src/**", true, false).is_err());
+        assert!(SplitCaseMatcher::new("src/**/x", true, false).is_ok());
+    }
+
+    #[test]
+    fn only_the_name_is_highlighted() {
+        let matcher = SplitCaseMatcher::new("Src/L*.RS", true, true).unwrap();
+        assert_eq!(matcher.match_ranges("src/lib.rs"), Some(vec![4..5, 7..10]));
+    }
+}
@@ -0,0 +1,205 @@
+use std::fs::Metadata;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+use walkdir::DirEntry;
+
+use super::EntryMatcher;
+
+/// Which timestamp a [`TimeMatcher`] compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeField {
+    Modified,
+    Accessed,
+    Created,
+}
+
+impl TimeField {
+    fn read(self, metadata: &Metadata) -> io::Result<SystemTime> {
+        match self {
+            TimeField::Modified => metadata.modified(),
+            TimeField::Accessed => metadata.accessed(),
+            TimeField::Created => metadata.created(),
+        }
+    }
+
+    /// Whether the filesystem at `path` records this timestamp
+    pub fn is_supported(self, path: &Path) -> bool {
+        std::fs::metadata(path).is_ok_and(|metadata| self.read(&metadata).is_ok())
+    }
+}
+
+/// Files an [`AccessTimeCheck`] needs to see before judging access times
+const ACCESS_CHECK_MIN_FILES: usize = 20;
+
+/// Counts files whose access time equals their status-change time, as on a
+/// filesystem mounted with `noatime`, where access times say little about use
+#[derive(Debug, Default)]
+pub struct AccessTimeCheck {
+    checked: AtomicUsize,
+    unchanged: AtomicUsize,
+}
+
+impl AccessTimeCheck {
+    fn record(&self, metadata: &Metadata) {
+        #[cfg(unix)]
+        if metadata.is_file() {
+            use std::os::unix::fs::MetadataExt;
+            self.count(
+                metadata.atime() == metadata.ctime()
+                    && metadata.atime_nsec() == metadata.ctime_nsec(),
+            );
+        }
+        #[cfg(not(unix))]
+        let _ = metadata;
+    }
+
+    fn count(&self, unchanged: bool) {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        if unchanged {
+            self.unchanged.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether most of a fair number of files had never had their access time updated
+    pub fn looks_unreliable(&self) -> bool {
+        let checked = self.checked.load(Ordering::Relaxed);
+        checked >= ACCESS_CHECK_MIN_FILES && self.unchanged.load(Ordering::Relaxed) * 2 > checked
+    }
+}
+
+/// Restricts another matcher to entries whose timestamp falls within a recent
+/// window, or with `older_than`, outside it
+pub struct TimeMatcher {
+    inner: Box<dyn EntryMatcher>,
+    field: TimeField,
+    cutoff: SystemTime,
+    older: bool,
+    access_check: Option<Arc<AccessTimeCheck>>,
+}
+
+impl TimeMatcher {
+    pub fn new(inner: Box<dyn EntryMatcher>, field: TimeField, within: Duration) -> Self {
+        let cutoff = SystemTime::now()
+            .checked_sub(within)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        TimeMatcher {
+            inner,
+            field,
+            cutoff,
+            older: false,
+            access_check: None,
+        }
+    }
+
+    /// Match entries whose timestamp is at least `age` old instead
+    pub fn older_than(inner: Box<dyn EntryMatcher>, field: TimeField, age: Duration) -> Self {
+        TimeMatcher {
+            older: true,
+            ..Self::new(inner, field, age)
+        }
+    }
+
+    /// Record each tested file's timestamps in `check`
+    pub fn with_access_check(mut self, check: Arc<AccessTimeCheck>) -> Self {
+        self.access_check = Some(check);
+        self
+    }
+
+    fn in_window(&self, metadata: &Metadata) -> bool {
+        if let Some(check) = &self.access_check {
+            check.record(metadata);
+        }
+        self.field
+            .read(metadata)
+            .is_ok_and(|time| (time >= self.cutoff) != self.older)
+    }
+}
+
+impl EntryMatcher for TimeMatcher {
+    fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+        self.inner.is_match(entry, full_path)
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| self.in_window(&metadata))
+    }
+
+    fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+        self.inner.is_path_match(path, full_path)
+            && path
+                .symlink_metadata()
+                .is_ok_and(|metadata| self.in_window(&metadata))
+    }
+
+    fn match_ranges(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        self.inner.match_ranges(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+
+    fn check(checked: usize, unchanged: usize) -> AccessTimeCheck {
+        let check = AccessTimeCheck::default();
+        for i in 0..checked {
+            check.count(i < unchanged);
+        }
+        check
+    }
+
+    #[test]
+    fn access_times_need_enough_files_to_judge() {
+        assert!(!check(0, 0).looks_unreliable());
+        assert!(!check(19, 19).looks_unreliable());
+        assert!(check(20, 20).looks_unreliable());
+    }
+
+    #[test]
+    fn access_times_are_unreliable_when_most_never_changed() {
+        assert!(!check(20, 10).looks_unreliable());
+        assert!(check(20, 11).looks_unreliable());
+        assert!(!check(101, 50).looks_unreliable());
+        assert!(check(101, 51).looks_unreliable());
+        assert!(!check(40, 0).looks_unreliable());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn matching_records_each_file_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let access_check = Arc::new(AccessTimeCheck::default());
+        let matcher = TimeMatcher::older_than(
+            Box::new(GlobEntryMatcher::new("*").unwrap()),
+            TimeField::Accessed,
+            Duration::from_secs(3600),
+        )
+        .with_access_check(Arc::clone(&access_check));
+
+        // Setting the access time also moves the change time to now, so these
+        // two differ; a new, untouched file has them equal
+        let read_long_ago = SystemTime::now() - Duration::from_secs(86_400);
+        for i in 0..20 {
+            let path = dir.path().join(format!("file{i}"));
+            let file = std::fs::File::create(&path).unwrap();
+            if i < 10 {
+                let times = std::fs::FileTimes::new().set_accessed(read_long_ago);
+                file.set_times(times).unwrap();
+            }
+            drop(file);
+            assert_eq!(matcher.is_path_match(&path, false), i < 10);
+        }
+        assert_eq!(access_check.checked.load(Ordering::Relaxed), 20);
+        assert_eq!(access_check.unchanged.load(Ordering::Relaxed), 10);
+        assert!(!access_check.looks_unreliable());
+
+        // Directories aren't counted
+        matcher.is_path_match(dir.path(), false);
+        assert_eq!(access_check.checked.load(Ordering::Relaxed), 20);
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+/// Prefix for Windows extended-length paths that bypass the MAX_PATH limit
+#[cfg(windows)]
+const VERBATIM_PREFIX: &str = r"\\?\";
+
+/// Prefix for extended-length UNC paths
+#[cfg(windows)]
+const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// Convert an absolute path to its extended-length form on Windows.
+///
+/// Relative paths are returned unchanged since the `\\?\` prefix disables
+/// relative path resolution. On other platforms this is the identity.
+pub fn to_extended_length(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        if !path.is_absolute() {
+            return path.to_path_buf();
+        }
+        let Some(text) = path.to_str() else {
+            return path.to_path_buf();
+        };
+        if text.starts_with(VERBATIM_PREFIX) {
+            return path.to_path_buf();
+        }
+        // `absolute` normalizes `.` and `..`, which verbatim paths don't resolve
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let Some(text) = absolute.to_str() else {
+            return absolute;
+        };
+        match text.strip_prefix(r"\\") {
+            Some(share) => PathBuf::from(format!("{VERBATIM_UNC_PREFIX}{share}")),
+            None => PathBuf::from(format!("{VERBATIM_PREFIX}{text}")),
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        path.to_path_buf()
+    }
+}
+
+/// Strip the extended-length prefix from a path for display
+pub fn strip_extended_prefix(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let Some(text) = path.to_str() else {
+            return path.to_path_buf();
+        };
+        if let Some(share) = text.strip_prefix(VERBATIM_UNC_PREFIX) {
+            PathBuf::from(format!(r"\\{share}"))
+        } else if let Some(rest) = text.strip_prefix(VERBATIM_PREFIX) {
+            PathBuf::from(rest)
+        } else {
+            path.to_path_buf()
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        path.to_path_buf()
+    }
+}
+
+/// Whether the path argument names the root of every drive (`/` or `\` on Windows)
+pub fn is_all_drives(path: &Path) -> bool {
+    cfg!(windows) && matches!(path.to_str(), Some("/") | Some("\\"))
+}
+
+/// Drive roots that currently exist, such as `C:\` and `D:\`
+pub fn available_drives() -> Vec<PathBuf> {
+    (b'A'..=b'Z')
+        .map(|letter| PathBuf::from(format!("{}:\\", letter as char)))
+        .filter(|root| root.exists())
+        .collect()
+}
+
+/// Resolve the roots to walk for a base path, expanding `/` to every drive on Windows
+pub fn search_roots(base_path: &Path) -> Vec<PathBuf> {
+    let roots = if is_all_drives(base_path) {
+        available_drives()
+    } else {
+        vec![base_path.to_path_buf()]
+    };
+    roots.iter().map(|root| to_extended_length(root)).collect()
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_drive_paths() {
+        let extended = to_extended_length(Path::new(r"C:\Users\me"));
+        assert_eq!(extended, PathBuf::from(r"\\?\C:\Users\me"));
+        assert_eq!(
+            strip_extended_prefix(&extended),
+            PathBuf::from(r"C:\Users\me")
+        );
+    }
+
+    #[test]
+    fn normalizes_dots_before_prefixing() {
+        let extended = to_extended_length(Path::new(r"C:\Users\me\..\you\.\docs"));
+        assert_eq!(extended, PathBuf::from(r"\\?\C:\Users\you\docs"));
+    }
+
+    #[test]
+    fn prefixes_unc_paths() {
+        let extended = to_extended_length(Path::new(r"\\server\share\dir"));
+        assert_eq!(extended, PathBuf::from(r"\\?\UNC\server\share\dir"));
+        assert_eq!(
+            strip_extended_prefix(&extended),
+            PathBuf::from(r"\\server\share\dir")
+        );
+    }
+
+    #[test]
+    fn leaves_relative_paths_alone() {
+        for relative in [r"src\main.rs", r"..\other", "."] {
+            assert_eq!(
+                to_extended_length(Path::new(relative)),
+                PathBuf::from(relative)
+            );
+        }
+    }
+
+    #[test]
+    fn leaves_extended_paths_alone() {
+        let extended = PathBuf::from(r"\\?\C:\already");
+        assert_eq!(to_extended_length(&extended), extended);
+    }
+
+    #[test]
+    fn strips_nothing_from_plain_paths() {
+        for plain in [r"C:\plain", r"\\server\share", r"relative\path"] {
+            assert_eq!(
+                strip_extended_prefix(Path::new(plain)),
+                PathBuf::from(plain)
+            );
+        }
+    }
+}
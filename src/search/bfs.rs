@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+use super::{Entry, KeepEntry, WalkError};
+
+/// Walks a tree level by level, shallower entries first.
+///
+/// Every directory of the level being walked is queued before the next level
+/// starts, so wide trees hold many more pending paths than a depth-first walk.
+pub(super) struct BreadthFirst {
+    queue: VecDeque<(PathBuf, usize)>,
+    current: Option<(walkdir::IntoIter, usize)>,
+    max_depth: Option<usize>,
+    follow_links: bool,
+    keep: Option<KeepEntry>,
+}
+
+impl BreadthFirst {
+    pub fn new(
+        root: PathBuf,
+        max_depth: Option<usize>,
+        follow_links: bool,
+        keep: Option<KeepEntry>,
+    ) -> Self {
+        // The root itself comes from a zero-depth walk, like a depth-first walk's first entry
+        let root = WalkDir::new(root).max_depth(0).follow_links(follow_links);
+        BreadthFirst {
+            queue: VecDeque::new(),
+            current: Some((root.into_iter(), 0)),
+            max_depth,
+            follow_links,
+            keep,
+        }
+    }
+}
+
+/// The directory above `entry` that a followed symlink leads back to, if any.
+///
+/// Each level is its own walk, so walkdir can't see loops spanning levels.
+fn loop_ancestor(entry: &DirEntry, depth: usize) -> Option<PathBuf> {
+    if !entry.path_is_symlink() || !entry.file_type().is_dir() {
+        return None;
+    }
+    let target = entry.path().canonicalize().ok()?;
+    entry
+        .path()
+        .ancestors()
+        .skip(1)
+        .take(depth)
+        .find(|ancestor| ancestor.canonicalize().is_ok_and(|dir| dir == target))
+        .map(Path::to_path_buf)
+}
+
+impl Iterator for BreadthFirst {
+    type Item = Result<Entry, WalkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((entries, depth)) = &mut self.current {
+                let depth = *depth;
+                match entries.next() {
+                    Some(Ok(entry)) => {
+                        if self.follow_links
+                            && let Some(ancestor) = loop_ancestor(&entry, depth)
+                        {
+                            return Some(Err(WalkError::filesystem_loop(
+                                entry.path(),
+                                depth,
+                                ancestor,
+                            )));
+                        }
+                        // Each level is its own walk, so its depths are relative
+                        let entry = Entry::at_depth(entry, depth);
+                        // Like `filter_entry`, a dropped directory isn't descended into
+                        if let Some(keep) = &mut self.keep
+                            && !keep(&entry)
+                        {
+                            continue;
+                        }
+                        if entry.is_dir() && self.max_depth.is_none_or(|max| depth < max) {
+                            self.queue
+                                .push_back((entry.path().to_path_buf(), depth + 1));
+                        }
+                        return Some(Ok(entry));
+                    }
+                    Some(Err(err)) => return Some(Err(WalkError::at_depth(err, depth))),
+                    None => self.current = None,
+                }
+            }
+
+            let (dir, depth) = self.queue.pop_front()?;
+            let entries = WalkDir::new(dir)
+                .min_depth(1)
+                .max_depth(1)
+                .follow_links(self.follow_links)
+                .into_iter();
+            self.current = Some((entries, depth));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// a/b/c.txt, a/d.txt, e.txt, and f/
+    fn tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::create_dir(dir.path().join("f")).unwrap();
+        for file in ["a/b/c.txt", "a/d.txt", "e.txt"] {
+            fs::write(dir.path().join(file), "").unwrap();
+        }
+        dir
+    }
+
+    /// Each walked path relative to `root` with its depth, sorted within each level
+    fn walked(
+        root: &Path,
+        max_depth: Option<usize>,
+        keep: Option<KeepEntry>,
+    ) -> Vec<(String, usize)> {
+        let mut walked: Vec<(String, usize)> =
+            BreadthFirst::new(root.to_path_buf(), max_depth, false, keep)
+                .map(|entry| {
+                    let entry = entry.unwrap();
+                    let relative = entry.path().strip_prefix(root).unwrap();
+                    (relative.display().to_string(), entry.depth())
+                })
+                .collect();
+        assert!(walked.is_sorted_by_key(|(_, depth)| *depth), "{walked:?}");
+        walked.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        walked
+    }
+
+    #[test]
+    fn walks_each_level_before_the_next() {
+        let dir = tree();
+        let pairs = |items: &[(&str, usize)]| -> Vec<(String, usize)> {
+            items.iter().map(|(p, d)| (p.to_string(), *d)).collect()
+        };
+        assert_eq!(
+            walked(dir.path(), None, None),
+            pairs(&[
+                ("", 0),
+                ("a", 1),
+                ("e.txt", 1),
+                ("f", 1),
+                ("a/b", 2),
+                ("a/d.txt", 2),
+                ("a/b/c.txt", 3),
+            ])
+        );
+        assert_eq!(
+            walked(dir.path(), Some(1), None),
+            pairs(&[("", 0), ("a", 1), ("e.txt", 1), ("f", 1)])
+        );
+    }
+
+    #[test]
+    fn dropped_directories_are_not_descended() {
+        let dir = tree();
+        let keep: KeepEntry = Box::new(|entry: &Entry| entry.file_name() != "a");
+        let paths: Vec<String> = walked(dir.path(), None, Some(keep))
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(paths, ["", "e.txt", "f"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn followed_links_back_up_the_tree_are_loops() {
+        let dir = tree();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("a/up")).unwrap();
+        let walk = BreadthFirst::new(dir.path().to_path_buf(), None, true, None);
+        let errors: Vec<WalkError> = walk.filter_map(Result::err).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path(), Some(dir.path().join("a/up").as_path()));
+        assert_eq!(
+            errors[0]
+                .loop_ancestor()
+                .map(Path::canonicalize)
+                .transpose()
+                .unwrap(),
+            Some(dir.path().canonicalize().unwrap())
+        );
+    }
+}
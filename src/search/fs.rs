@@ -0,0 +1,305 @@
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs::Metadata;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use walkdir::{DirEntry, WalkDir};
+
+use super::bfs;
+use crate::matchers::EntryMatcher;
+
+/// Entries of one walk, in the order they were reached
+pub type Walk = Box<dyn Iterator<Item = Result<Entry, WalkError>> + Send>;
+
+/// Decides whether a walk keeps an entry
+pub type KeepEntry = Box<dyn FnMut(&Entry) -> bool + Send>;
+
+/// What a walked entry is, without following symlinks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+    /// Sockets, devices, and the like
+    Other,
+}
+
+/// One entry reached by a [`FileSystem`] walk
+#[derive(Debug, Clone)]
+pub struct Entry {
+    path: PathBuf,
+    depth: usize,
+    kind: EntryKind,
+    size: Option<u64>,
+    /// Set for entries of the real filesystem, which matchers take as they are
+    dir_entry: Option<DirEntry>,
+}
+
+impl Entry {
+    /// An entry at `depth` below the walk's root, the root being 0
+    pub fn new(path: impl Into<PathBuf>, depth: usize, kind: EntryKind) -> Self {
+        Entry {
+            path: path.into(),
+            depth,
+            kind,
+            size: None,
+            dir_entry: None,
+        }
+    }
+
+    /// Give the entry a size instead of reading it from its metadata
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// The last component of the path, or the whole path for a root like `/`
+    pub fn file_name(&self) -> &OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Dir
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == EntryKind::File
+    }
+
+    /// Metadata of the entry itself, not following symlinks
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        match &self.dir_entry {
+            Some(entry) => entry.metadata().map_err(io::Error::other),
+            None => self.path.symlink_metadata(),
+        }
+    }
+
+    /// Size in bytes, or 0 when it can't be read
+    pub fn size(&self) -> u64 {
+        self.size
+            .unwrap_or_else(|| self.metadata().map_or(0, |metadata| metadata.len()))
+    }
+
+    /// Whether `matcher` matches the entry; entries not from the real
+    /// filesystem are matched by path
+    pub fn is_match(&self, matcher: &dyn EntryMatcher, full_path: bool) -> bool {
+        match &self.dir_entry {
+            Some(entry) => matcher.is_match(entry, full_path),
+            None => matcher.is_path_match(&self.path, full_path),
+        }
+    }
+
+    /// Wrap a walkdir entry that came from a walk of a subdirectory
+    pub(super) fn at_depth(entry: DirEntry, depth: usize) -> Self {
+        let file_type = entry.file_type();
+        let kind = if file_type.is_dir() {
+            EntryKind::Dir
+        } else if file_type.is_file() {
+            EntryKind::File
+        } else if file_type.is_symlink() {
+            EntryKind::Symlink
+        } else {
+            EntryKind::Other
+        };
+        Entry {
+            path: entry.path().to_path_buf(),
+            depth,
+            kind,
+            size: None,
+            dir_entry: Some(entry),
+        }
+    }
+}
+
+impl From<DirEntry> for Entry {
+    fn from(entry: DirEntry) -> Self {
+        let depth = entry.depth();
+        Entry::at_depth(entry, depth)
+    }
+}
+
+/// Something a walk couldn't read, such as a directory without permission
+#[derive(Debug)]
+pub struct WalkError {
+    path: Option<PathBuf>,
+    depth: usize,
+    cause: WalkErrorCause,
+}
+
+#[derive(Debug)]
+enum WalkErrorCause {
+    Io(io::Error),
+    /// A symlink leading back to `ancestor`, which the walk is already inside
+    Loop {
+        ancestor: PathBuf,
+    },
+}
+
+impl WalkError {
+    /// An IO error reading `path` at `depth`
+    pub fn io(path: impl Into<PathBuf>, depth: usize, err: io::Error) -> Self {
+        WalkError {
+            path: Some(path.into()),
+            depth,
+            cause: WalkErrorCause::Io(err),
+        }
+    }
+
+    /// A symlink at `path` pointing back to `ancestor`
+    pub fn filesystem_loop(
+        path: impl Into<PathBuf>,
+        depth: usize,
+        ancestor: impl Into<PathBuf>,
+    ) -> Self {
+        WalkError {
+            path: Some(path.into()),
+            depth,
+            cause: WalkErrorCause::Loop {
+                ancestor: ancestor.into(),
+            },
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn io_error(&self) -> Option<&io::Error> {
+        match &self.cause {
+            WalkErrorCause::Io(err) => Some(err),
+            WalkErrorCause::Loop { .. } => None,
+        }
+    }
+
+    pub fn loop_ancestor(&self) -> Option<&Path> {
+        match &self.cause {
+            WalkErrorCause::Loop { ancestor } => Some(ancestor),
+            WalkErrorCause::Io(_) => None,
+        }
+    }
+
+    /// Wrap a walkdir error that came from a walk of a subdirectory
+    pub(super) fn at_depth(err: walkdir::Error, depth: usize) -> Self {
+        let path = err.path().map(Path::to_path_buf);
+        let cause = match err.loop_ancestor() {
+            Some(ancestor) => WalkErrorCause::Loop {
+                ancestor: ancestor.to_path_buf(),
+            },
+            None => {
+                let message = err.to_string();
+                WalkErrorCause::Io(
+                    err.into_io_error()
+                        .unwrap_or_else(|| io::Error::other(message)),
+                )
+            }
+        };
+        WalkError { path, depth, cause }
+    }
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.cause, &self.path) {
+            (WalkErrorCause::Io(err), Some(path)) => {
+                write!(f, "IO error for operation on {}: {err}", path.display())
+            }
+            (WalkErrorCause::Io(err), None) => write!(f, "IO error: {err}"),
+            (WalkErrorCause::Loop { ancestor }, path) => write!(
+                f,
+                "File system loop found: {} points to an ancestor {}",
+                path.as_deref().unwrap_or(Path::new("")).display(),
+                ancestor.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WalkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.cause {
+            WalkErrorCause::Io(err) => Some(err),
+            WalkErrorCause::Loop { .. } => None,
+        }
+    }
+}
+
+impl From<walkdir::Error> for WalkError {
+    fn from(err: walkdir::Error) -> Self {
+        let depth = err.depth();
+        WalkError::at_depth(err, depth)
+    }
+}
+
+/// How a [`FileSystem`] should walk one root
+pub struct WalkOpts {
+    /// Deepest level to descend to, counting the root as 0
+    pub max_depth: Option<usize>,
+    /// Yield shallower entries before deeper ones
+    pub breadth_first: bool,
+    /// Descend into directories that symlinks point to, reporting loops as errors
+    pub follow_links: bool,
+    /// Whether to keep each entry; a directory that isn't kept isn't
+    /// descended into either
+    pub keep: Option<KeepEntry>,
+}
+
+/// Where a [`Searcher`](super::Searcher) gets its entries from.
+///
+/// Only the walk goes through this. Entries from another source are matched
+/// by path, so matchers that read metadata or file contents won't match them.
+pub trait FileSystem: Send + Sync {
+    /// Walk `root`, yielding the root itself first
+    fn walk(&self, root: &Path, opts: WalkOpts) -> Walk;
+}
+
+/// The real filesystem, walked with `walkdir`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+    fn walk(&self, root: &Path, opts: WalkOpts) -> Walk {
+        if opts.breadth_first {
+            return Box::new(bfs::BreadthFirst::new(
+                root.to_path_buf(),
+                opts.max_depth,
+                opts.follow_links,
+                opts.keep,
+            ));
+        }
+        let mut walker = WalkDir::new(root).follow_links(opts.follow_links);
+        if let Some(depth) = opts.max_depth {
+            walker = walker.max_depth(depth);
+        }
+        let walker: Box<dyn Iterator<Item = walkdir::Result<DirEntry>> + Send> = match opts.keep {
+            // walkdir filters its own entries, so each is wrapped for the check
+            Some(mut keep) => Box::new(
+                walker
+                    .into_iter()
+                    .filter_entry(move |entry| keep(&Entry::from(entry.clone()))),
+            ),
+            None => Box::new(walker.into_iter()),
+        };
+        Box::new(walker.map(|result| result.map(Entry::from).map_err(WalkError::from)))
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::{Entry, SearchEvent, StatusMessage, Throttle};
+use crate::matchers::EntryMatcher;
+
+/// What the search should do after an entry has been tested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Flow {
+    Continue,
+    /// The match limit was reached and only matches that are shown are wanted
+    Stop,
+    /// The timeout passed while waiting for the display to take a match
+    TimedOut,
+    /// The consumer asked for the search to stop
+    Cancelled,
+}
+
+/// Tests entries against the pattern and reports matches, both for entries
+/// straight from the walk and for ones collected beforehand
+pub(super) struct MatchPhase {
+    matcher: Arc<dyn EntryMatcher>,
+    full_path: bool,
+    verbosity: u8,
+    max_matches: usize,
+    count_all_matches: bool,
+    deadline: Option<Instant>,
+    cancelled: Arc<AtomicBool>,
+    match_updates: Throttle,
+    sum_bytes: bool,
+    pub(super) match_count: usize,
+    pub(super) matches_sent: usize,
+    pub(super) match_limit_reached: bool,
+    /// Total size of the matched files, once any file has matched
+    pub(super) matched_bytes: Option<u64>,
+}
+
+impl MatchPhase {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        matcher: Arc<dyn EntryMatcher>,
+        full_path: bool,
+        verbosity: u8,
+        max_matches: usize,
+        count_all_matches: bool,
+        deadline: Option<Instant>,
+        cancelled: Arc<AtomicBool>,
+        status_interval: Duration,
+        sum_bytes: bool,
+    ) -> Self {
+        MatchPhase {
+            matcher,
+            full_path,
+            verbosity,
+            max_matches,
+            count_all_matches,
+            deadline,
+            cancelled,
+            match_updates: Throttle::new(status_interval),
+            sum_bytes,
+            match_count: 0,
+            matches_sent: 0,
+            match_limit_reached: false,
+            matched_bytes: None,
+        }
+    }
+
+    /// Test one entry, passing it to `send` if it matches
+    pub(super) fn process(&mut self, entry: &Entry, send: &mut dyn FnMut(SearchEvent)) -> Flow {
+        if self.test(entry, send) {
+            self.report(entry, send)
+        } else {
+            Flow::Continue
+        }
+    }
+
+    /// Whether an entry matches, tracing the decision when verbose
+    pub(super) fn test(&self, entry: &Entry, send: &mut dyn FnMut(SearchEvent)) -> bool {
+        let is_match = entry.is_match(&*self.matcher, self.full_path);
+        if self.verbosity >= 2 && is_match {
+            send(SearchEvent::Status(StatusMessage::Debug(format!(
+                "{}: matched pattern",
+                entry.path().display()
+            ))));
+        } else if self.verbosity >= 1 && !is_match {
+            send(SearchEvent::Status(StatusMessage::Debug(format!(
+                "{}: skipped, did not match pattern{}",
+                entry.path().display(),
+                if self.full_path {
+                    " (full path)"
+                } else {
+                    " (file name)"
+                }
+            ))));
+        }
+        is_match
+    }
+
+    /// Count and pass on an entry already known to match
+    pub(super) fn report(&mut self, entry: &Entry, send: &mut dyn FnMut(SearchEvent)) -> Flow {
+        self.match_count += 1;
+        if self.sum_bytes && entry.is_file() {
+            *self.matched_bytes.get_or_insert(0) += entry.size();
+        }
+        // Past the match limit only the total is still being counted
+        if self.match_limit_reached {
+            return Flow::Continue;
+        }
+
+        // Report the match right away; a consumer that stops the search once the
+        // deadline has passed gave up waiting to take it
+        send(SearchEvent::Match(entry.clone()));
+        let stopped = self.cancelled.load(Ordering::Relaxed);
+        if stopped && self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Flow::TimedOut;
+        }
+        self.matches_sent += 1;
+        if stopped {
+            return Flow::Cancelled;
+        }
+
+        if self.max_matches > 0 && self.matches_sent >= self.max_matches {
+            self.match_limit_reached = true;
+            if !self.count_all_matches {
+                return Flow::Stop;
+            }
+        }
+
+        // Update the counter periodically
+        if self.match_updates.ready() {
+            send(SearchEvent::Status(StatusMessage::CurrentPath(format!(
+                "Found {} matches so far...",
+                self.match_count
+            ))));
+        }
+        Flow::Continue
+    }
+}
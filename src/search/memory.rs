@@ -0,0 +1,273 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{Entry, EntryKind, FileSystem, Walk, WalkError, WalkOpts};
+
+/// A file tree held in memory, for exercising a [`Searcher`](super::Searcher)
+/// without touching the disk.
+///
+/// Entries are matched by path, so only name and path matchers apply to them.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileSystem {
+    nodes: BTreeMap<PathBuf, Node>,
+    delay: Duration,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    File {
+        size: u64,
+    },
+    Dir {
+        children: BTreeSet<OsString>,
+        /// Reading the directory's contents fails with this
+        error: Option<io::ErrorKind>,
+    },
+    Symlink,
+}
+
+impl Node {
+    fn dir() -> Self {
+        Node::Dir {
+            children: BTreeSet::new(),
+            error: None,
+        }
+    }
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file of `size` bytes, creating the directories above it
+    pub fn with_file(self, path: impl Into<PathBuf>, size: u64) -> Self {
+        self.with_node(path.into(), Node::File { size })
+    }
+
+    /// Add an empty directory, creating the directories above it
+    pub fn with_dir(self, path: impl Into<PathBuf>) -> Self {
+        self.with_node(path.into(), Node::dir())
+    }
+
+    /// Add a symlink, which a walk reports but doesn't follow, even with
+    /// [`WalkOpts::follow_links`]
+    pub fn with_symlink(self, path: impl Into<PathBuf>) -> Self {
+        self.with_node(path.into(), Node::Symlink)
+    }
+
+    /// Add a directory whose contents can't be read, failing with `kind`
+    pub fn with_unreadable_dir(self, path: impl Into<PathBuf>, kind: io::ErrorKind) -> Self {
+        let node = Node::Dir {
+            children: BTreeSet::new(),
+            error: Some(kind),
+        };
+        self.with_node(path.into(), node)
+    }
+
+    /// Wait this long before yielding each entry, as a slow disk would
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn with_node(mut self, path: PathBuf, node: Node) -> Self {
+        let mut child = path.clone();
+        while let Some(parent) = child.parent().filter(|p| !p.as_os_str().is_empty()) {
+            let name = child.file_name().map(OsString::from);
+            let parent_node = self
+                .nodes
+                .entry(parent.to_path_buf())
+                .or_insert_with(Node::dir);
+            if let (Node::Dir { children, .. }, Some(name)) = (parent_node, name) {
+                children.insert(name);
+            }
+            child = parent.to_path_buf();
+        }
+        // Re-adding a directory keeps what's already inside it
+        match (self.nodes.get_mut(&path), node) {
+            (Some(Node::Dir { error, .. }), Node::Dir { error: new, .. }) => *error = new,
+            (_, node) => {
+                self.nodes.insert(path, node);
+            }
+        }
+        self
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn walk(&self, root: &Path, opts: WalkOpts) -> Walk {
+        let WalkOpts {
+            max_depth,
+            breadth_first,
+            mut keep,
+            ..
+        } = opts;
+        // The tree is walked up front; only yielding the entries is paced
+        let mut walked = Vec::new();
+        let mut pending = VecDeque::from([(root.to_path_buf(), 0)]);
+        loop {
+            let next = if breadth_first {
+                pending.pop_front()
+            } else {
+                pending.pop_back()
+            };
+            let Some((path, depth)) = next else {
+                break;
+            };
+            let Some(node) = self.nodes.get(&path) else {
+                let err = io::Error::from(io::ErrorKind::NotFound);
+                walked.push(Err(WalkError::io(path, depth, err)));
+                continue;
+            };
+            let entry = match node {
+                Node::File { size } => Entry::new(&path, depth, EntryKind::File).with_size(*size),
+                Node::Dir { .. } => Entry::new(&path, depth, EntryKind::Dir),
+                Node::Symlink => Entry::new(&path, depth, EntryKind::Symlink),
+            };
+            if let Some(keep) = &mut keep
+                && !keep(&entry)
+            {
+                continue;
+            }
+            walked.push(Ok(entry));
+
+            let Node::Dir { children, error } = node else {
+                continue;
+            };
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            if let Some(kind) = error {
+                walked.push(Err(WalkError::io(&path, depth, io::Error::from(*kind))));
+                continue;
+            }
+            let children = children.iter().map(|name| (path.join(name), depth + 1));
+            if breadth_first {
+                pending.extend(children);
+            } else {
+                // Reversed onto the stack so they come off in name order
+                pending.extend(children.rev());
+            }
+        }
+
+        let delay = self.delay;
+        Box::new(walked.into_iter().inspect(move |_| {
+            if !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> MemoryFileSystem {
+        MemoryFileSystem::new()
+            .with_file("/r/b.txt", 2)
+            .with_file("/r/a/x.txt", 1)
+            .with_file("/r/a/deep/y.txt", 1)
+            .with_unreadable_dir("/r/c", io::ErrorKind::PermissionDenied)
+            .with_symlink("/r/link")
+    }
+
+    fn walk(file_system: &MemoryFileSystem, opts: WalkOpts) -> Vec<String> {
+        file_system
+            .walk(Path::new("/r"), opts)
+            .map(|result| match result {
+                Ok(entry) => format!("{} {}", entry.depth(), entry.path().display()),
+                Err(err) => format!("{} error {}", err.depth(), err.path().unwrap().display()),
+            })
+            .collect()
+    }
+
+    fn opts(max_depth: Option<usize>, breadth_first: bool) -> WalkOpts {
+        WalkOpts {
+            max_depth,
+            breadth_first,
+            follow_links: false,
+            keep: None,
+        }
+    }
+
+    #[test]
+    fn walks_depth_first_in_name_order() {
+        assert_eq!(
+            walk(&tree(), opts(None, false)),
+            [
+                "0 /r",
+                "1 /r/a",
+                "2 /r/a/deep",
+                "3 /r/a/deep/y.txt",
+                "2 /r/a/x.txt",
+                "1 /r/b.txt",
+                "1 /r/c",
+                "1 error /r/c",
+                "1 /r/link",
+            ]
+        );
+    }
+
+    #[test]
+    fn walks_breadth_first_within_max_depth() {
+        assert_eq!(
+            walk(&tree(), opts(Some(2), true)),
+            [
+                "0 /r",
+                "1 /r/a",
+                "1 /r/b.txt",
+                "1 /r/c",
+                "1 error /r/c",
+                "1 /r/link",
+                "2 /r/a/deep",
+                "2 /r/a/x.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn dropped_directories_are_not_descended_into() {
+        let keep = Box::new(|entry: &Entry| entry.file_name() != "a");
+        let opts = WalkOpts {
+            keep: Some(keep),
+            ..opts(Some(1), false)
+        };
+        assert_eq!(
+            walk(&tree(), opts),
+            ["0 /r", "1 /r/b.txt", "1 /r/c", "1 /r/link"]
+        );
+    }
+
+    #[test]
+    fn entries_carry_their_kind_and_size() {
+        let entries: Vec<Entry> = tree()
+            .walk(Path::new("/r"), opts(Some(1), false))
+            .filter_map(Result::ok)
+            .collect();
+        let kinds: Vec<_> = entries.iter().map(|entry| entry.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                EntryKind::Dir,
+                EntryKind::Dir,
+                EntryKind::File,
+                EntryKind::Dir,
+                EntryKind::Symlink
+            ]
+        );
+        assert_eq!(entries[2].size(), 2);
+    }
+
+    #[test]
+    fn missing_root_is_an_error() {
+        let mut walked = tree().walk(Path::new("/elsewhere"), opts(None, false));
+        let err = walked.next().unwrap().unwrap_err();
+        assert_eq!(err.io_error().unwrap().kind(), io::ErrorKind::NotFound);
+        assert!(walked.next().is_none());
+    }
+}
@@ -1,280 +1,1850 @@
 use crate::matchers::EntryMatcher;
-use colored::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::ControlFlow;
 use std::path::PathBuf;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::mpsc::{self, Receiver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
-use walkdir::{DirEntry, WalkDir};
 
-/// Message types for our channels
+mod bfs;
+mod fs;
+mod matching;
+mod memory;
+mod options;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "watch")]
+mod watch;
+mod watchdog;
+
+use matching::{Flow, MatchPhase};
+
+pub use fs::{Entry, EntryKind, FileSystem, KeepEntry, OsFileSystem, Walk, WalkError, WalkOpts};
+pub use memory::MemoryFileSystem;
+pub use options::SearchOptions;
+#[cfg(feature = "async")]
+pub use stream::MatchStream;
+#[cfg(feature = "watch")]
+pub use watch::WatchEvent;
+pub use watchdog::with_timeout;
+
+/// Iterator over matches from a background search, cancelling it when dropped
+struct SearchIter {
+    result_rx: Receiver<Entry>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Iterator for SearchIter {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<PathBuf> {
+        self.result_rx.recv().ok().map(Entry::into_path)
+    }
+}
+
+impl Drop for SearchIter {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Default bound on queued matches between the search thread and its consumer
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 4096;
+
+#[derive(Debug, PartialEq, Eq)]
+enum SendOutcome {
+    Sent,
+    TimedOut,
+    Closed,
+}
+
+/// Send on a bounded channel, polling so a full queue can't outlast the deadline or a cancel
+fn send_until<T>(
+    tx: &SyncSender<T>,
+    mut item: T,
+    deadline: Option<Instant>,
+    cancelled: &AtomicBool,
+) -> SendOutcome {
+    loop {
+        match tx.try_send(item) {
+            Ok(()) => return SendOutcome::Sent,
+            Err(TrySendError::Disconnected(_)) => return SendOutcome::Closed,
+            Err(TrySendError::Full(returned)) => {
+                if deadline.is_some_and(|d| Instant::now() >= d) {
+                    return SendOutcome::TimedOut;
+                }
+                if cancelled.load(Ordering::Relaxed) {
+                    return SendOutcome::Closed;
+                }
+                item = returned;
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+/// Default minimum time between periodic status updates
+pub const DEFAULT_STATUS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Limits a periodic status update to at most once per interval
+struct Throttle {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl Throttle {
+    fn new(interval: Duration) -> Self {
+        Throttle {
+            interval,
+            last: None,
+        }
+    }
+
+    /// Whether an update is due, starting the next interval if so
+    fn ready(&mut self) -> bool {
+        let now = Instant::now();
+        match self.last {
+            Some(last) if now.duration_since(last) < self.interval => false,
+            _ => {
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// Fraction of the timeout after which a warning is sent
+const TIMEOUT_WARNING_RATIO: f64 = 0.8;
+
+/// Message types for our channels, serialized for --status-json as
+/// `{"event": "current_path", "data": ...}`
+#[derive(Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
 pub enum StatusMessage {
     CurrentPath(String),
     FileCount(usize, usize), // current count, total limit
     PermissionErrors(usize),
     Timeout(u64),
+    TimeoutApproaching(u64), // seconds remaining before the timeout
     LimitReached(usize),
+    Metrics(SearchMetrics),
+    Debug(String), // per-entry trace when verbose logging is enabled
+    // Periodic totals, built by the display rather than sent by the search
+    Heartbeat {
+        scanned: usize,
+        found: usize,
+        elapsed_ms: u64,
+    },
     Done,
 }
 
+/// What a callback-driven search reports, in the order it happens
+pub enum SearchEvent {
+    /// A matching entry, carrying the kind the walk found it to be
+    Match(Entry),
+    /// Entries examined and permission errors so far, sent periodically
+    Progress { scanned: usize, errors: usize },
+    /// Everything else the display shows, such as the directory being walked
+    Status(StatusMessage),
+    /// Always the last event
+    Finished(SearchResult),
+}
+
 /// Result of a search operation
 pub struct SearchResult {
     pub matches: usize,
-    pub files_scanned: usize,
-    pub permission_errors: usize,
+    pub matches_sent: usize, // fewer than `matches` when matches past the limit were only counted
+    pub match_limit_reached: bool,
+    pub entries_examined: usize, // walked entries, excluding errors, as limited by max_files
+    pub permission_errors: usize, // final count; progress updates to the UI are throttled
+    pub other_errors: BTreeMap<String, usize>, // walk errors other than permission denied, by kind
     pub elapsed: Duration,
     pub limit_reached: bool,
     pub timed_out: bool,
+    pub metrics: Option<SearchMetrics>,
+    pub tree_stats: Option<TreeStats>, // only for stats-only searches
+    pub deepest: usize,                // greatest entry depth walked, the root being 0
+    pub depth_limit_reached: Option<usize>, // --max-depth, when it left directories unsearched
+    pub root_match_skipped: bool, // the search root matched but isn't a result without include_root
+    pub matched_bytes: Option<u64>, // total size of matched files, when summed and any file matched
+}
+
+impl SearchResult {
+    /// Number of walk errors that weren't permission errors
+    pub fn other_error_count(&self) -> usize {
+        self.other_errors.values().sum()
+    }
+
+    /// Files scanned per second, or `None` when the search was too quick to measure
+    pub fn files_per_sec(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+        (secs > 0.0).then(|| self.entries_examined as f64 / secs)
+    }
+}
+
+/// Size totals gathered by a walk that skips matching
+#[derive(Debug, Clone, Default)]
+pub struct TreeStats {
+    pub files: usize,
+    pub dirs: usize, // not counting the search root
+    pub total_bytes: u64,
+    pub largest: Option<(PathBuf, u64)>,
+}
+
+impl TreeStats {
+    fn record(&mut self, entry: &Entry) {
+        if entry.is_dir() {
+            if entry.depth() > 0 {
+                self.dirs += 1;
+            }
+            return;
+        }
+        self.files += 1;
+        if !entry.is_file() {
+            return;
+        }
+        let size = entry.size();
+        self.total_bytes += size;
+        if self
+            .largest
+            .as_ref()
+            .is_none_or(|(_, largest)| size > *largest)
+        {
+            self.largest = Some((entry.path().to_path_buf(), size));
+        }
+    }
+}
+
+/// Throughput counters gathered by the search thread when stats are requested
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchMetrics {
+    pub entries_walked: usize,
+    pub dirs_walked: usize,
+    pub entries_matched: usize,
+    pub collection_time: Duration,
+    pub matching_time: Duration,
+    pub peak_queued: usize,
+    pub threads: usize, // as requested with --threads; the walk itself is single-threaded so far
+}
+
+impl SearchMetrics {
+    /// Total time spent across both phases
+    pub fn total_time(&self) -> Duration {
+        self.collection_time + self.matching_time
+    }
+
+    /// Entries walked per second during the collection phase
+    pub fn entries_per_sec(&self) -> f64 {
+        per_sec(self.entries_walked, self.collection_time)
+    }
+
+    /// Directories walked per second during the collection phase
+    pub fn dirs_per_sec(&self) -> f64 {
+        per_sec(self.dirs_walked, self.collection_time)
+    }
+}
+
+fn per_sec(count: usize, duration: Duration) -> f64 {
+    let secs = duration.as_secs_f64();
+    if secs > 0.0 { count as f64 / secs } else { 0.0 }
 }
 
 /// Core search functionality
+#[derive(Clone)]
 pub struct Searcher {
     matcher: Arc<dyn EntryMatcher>,
     base_path: Arc<PathBuf>,
     max_depth: Option<usize>,
+    min_depth: Option<usize>,
     max_files: usize,
+    max_matches: usize,
+    count_all_matches: bool,
     timeout: Option<Duration>,
-    start_time: Instant,
+    follow_links: bool,
     files_only: bool,
     dirs_only: bool,
+    hidden: bool,
     show_permission_errors: bool,
+    collect_metrics: bool,
+    verbosity: u8,
+    channel_capacity: usize,
+    status_interval: Duration,
+    breadth_first: bool,
+    stats_only: bool,
+    threads: usize,
+    depth_below_match: Option<usize>,
+    two_pass: bool, // collect the whole walk before matching anything
+    include_root: bool,
+    sum_matched_bytes: bool,
+    file_system: Arc<dyn FileSystem>,
+    prune: Option<Arc<dyn EntryMatcher>>, // directories skipped along with their contents
+    /// Cancel flags of the searches started from this searcher and its clones
+    running: Arc<Mutex<Vec<Weak<AtomicBool>>>>,
+}
+
+/// [`crate::matchers::type_filter_reason`] for a walked entry
+fn type_filter_reason(entry: &Entry, files_only: bool, dirs_only: bool) -> Option<&'static str> {
+    crate::matchers::kind_filter_reason(entry.is_file(), entry.is_dir(), files_only, dirs_only)
+}
+
+/// Walk filter keeping entries within `limit` levels below the shallowest
+/// matching directory on their path; elsewhere the walk is unrestricted
+fn within_match_depth(
+    matcher: Arc<dyn EntryMatcher>,
+    full_path: bool,
+    limit: usize,
+) -> impl FnMut(&Entry) -> bool + Send {
+    // Any walk order yields a directory before its contents, so a matched
+    // directory is always known by the time its entries are checked. Only the
+    // shallowest match on each path is kept, since deeper ones add no limit.
+    let mut matched: HashSet<PathBuf> = HashSet::new();
+    move |entry| {
+        let path = entry.path();
+        if let Some(dir) = path.ancestors().skip(1).find(|dir| matched.contains(*dir)) {
+            let below = path
+                .strip_prefix(dir)
+                .map_or(0, |rel| rel.components().count());
+            return below <= limit;
+        }
+        if entry.is_dir() && entry.is_match(&*matcher, full_path) {
+            matched.insert(path.to_path_buf());
+        }
+        true
+    }
 }
 
 impl Searcher {
+    /// Create a searcher from positional settings; `with_options` is the
+    /// same with named ones, including any added since, like `include_root`
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         matcher: Box<dyn EntryMatcher>,
         base_path: PathBuf,
         max_depth: Option<usize>,
         max_files: usize,
+        max_matches: usize,
+        count_all_matches: bool,
         timeout_seconds: u64,
         files_only: bool,
         dirs_only: bool,
         show_permission_errors: bool,
+        collect_metrics: bool,
+        verbosity: u8,
+        channel_capacity: usize,
+        status_interval: Duration,
+        breadth_first: bool,
+        stats_only: bool,
+        threads: usize,
+        depth_below_match: Option<usize>,
+        two_pass: bool,
     ) -> Self {
-        let timeout = if timeout_seconds > 0 {
-            Some(Duration::from_secs(timeout_seconds))
-        } else {
-            None
+        let options = SearchOptions {
+            max_depth,
+            max_files,
+            max_matches,
+            count_all_matches,
+            timeout: (timeout_seconds > 0).then(|| Duration::from_secs(timeout_seconds)),
+            files_only,
+            dirs_only,
+            show_permission_errors,
+            collect_metrics,
+            verbosity,
+            channel_capacity,
+            status_interval,
+            breadth_first,
+            stats_only,
+            threads,
+            depth_below_match,
+            two_pass,
+            ..SearchOptions::default()
         };
+        Self::with_options(matcher, base_path, options)
+    }
+
+    /// Create a searcher for `base_path` with the given settings
+    pub fn with_options(
+        matcher: Box<dyn EntryMatcher>,
+        base_path: PathBuf,
+        options: SearchOptions,
+    ) -> Self {
+        let SearchOptions {
+            max_depth,
+            min_depth,
+            max_files,
+            max_matches,
+            count_all_matches,
+            timeout,
+            follow_links,
+            files_only,
+            dirs_only,
+            hidden,
+            show_permission_errors,
+            collect_metrics,
+            verbosity,
+            channel_capacity,
+            status_interval,
+            breadth_first,
+            stats_only,
+            threads,
+            depth_below_match,
+            two_pass,
+            include_root,
+            sum_matched_bytes,
+        } = options;
 
         Searcher {
             matcher: Arc::from(matcher),
             base_path: Arc::new(base_path),
             max_depth,
+            min_depth,
             max_files,
+            max_matches,
+            count_all_matches,
             timeout,
-            start_time: Instant::now(), // Record start time when searcher is created
+            follow_links,
             files_only,
             dirs_only,
+            hidden,
             show_permission_errors,
+            collect_metrics,
+            verbosity,
+            channel_capacity,
+            status_interval,
+            breadth_first,
+            stats_only,
+            threads: threads.max(1),
+            depth_below_match,
+            two_pass,
+            include_root,
+            sum_matched_bytes,
+            file_system: Arc::new(OsFileSystem),
+            prune: None,
+            running: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
-    /// Performs the search operation
+    /// Walk `file_system` instead of the real filesystem
+    pub fn with_file_system(mut self, file_system: Arc<dyn FileSystem>) -> Self {
+        self.file_system = file_system;
+        self
+    }
+
+    /// Skip directories whose names `prune` matches, along with everything
+    /// inside them; the search root itself is always searched
+    pub fn with_prune(mut self, prune: Box<dyn EntryMatcher>) -> Self {
+        self.prune = Some(Arc::from(prune));
+        self
+    }
+
+    /// Stop every running search started from this searcher or its clones
+    /// at the next entry; searches started afterwards are unaffected
+    pub fn cancel(&self) {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        for cancelled in running.iter().filter_map(Weak::upgrade) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// A fresh cancel flag for one search, which `cancel` can reach while
+    /// the search holds it
+    fn start_search(&self) -> Arc<AtomicBool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.retain(|flag| flag.strong_count() > 0);
+        running.push(Arc::downgrade(&cancelled));
+        cancelled
+    }
+
+    /// The matcher entries are tested against
+    pub fn matcher(&self) -> Arc<dyn EntryMatcher> {
+        Arc::clone(&self.matcher)
+    }
+
+    /// Iterate over matching paths as they're found.
+    ///
+    /// The walk runs on a background thread and stops early if the iterator
+    /// is dropped before it's exhausted.
+    pub fn search_iter(&self, full_path: bool) -> impl Iterator<Item = PathBuf> + use<> {
+        let cancelled = self.start_search();
+        let (result_rx, _status_rx, _handle) = self.spawn_search(full_path, Arc::clone(&cancelled));
+        SearchIter {
+            result_rx,
+            cancelled,
+        }
+    }
+
+    /// Run the search to completion, returning every match and the final result
+    pub fn search_collect(&self, full_path: bool) -> (Vec<PathBuf>, SearchResult) {
+        let (result_rx, _status_rx, handle) = self.search(full_path);
+        let matches = result_rx.into_iter().map(Entry::into_path).collect();
+        let result = handle.join().expect("search thread panicked");
+        (matches, result)
+    }
+
+    /// Performs the search operation on a background thread.
+    ///
+    /// Matches and progress updates stream through the returned receivers as
+    /// they're found, which suits interactive display. Join the returned
+    /// handle for the final [`SearchResult`] once the walk completes. For
+    /// programmatic use, [`Searcher::search_iter`] and
+    /// [`Searcher::search_collect`] hide the channels.
     pub fn search(
         &self,
         full_path: bool,
-    ) -> (Receiver<PathBuf>, Receiver<StatusMessage>, SearchResult) {
-        let (result_tx, result_rx) = mpsc::channel::<PathBuf>();
+    ) -> (
+        Receiver<Entry>,
+        Receiver<StatusMessage>,
+        JoinHandle<SearchResult>,
+    ) {
+        self.spawn_search(full_path, self.start_search())
+    }
+
+    fn spawn_search(
+        &self,
+        full_path: bool,
+        cancelled: Arc<AtomicBool>,
+    ) -> (
+        Receiver<Entry>,
+        Receiver<StatusMessage>,
+        JoinHandle<SearchResult>,
+    ) {
+        // Timeouts and the reported elapsed time both count from this call
+        let start_time = Instant::now();
+        // Bounded so a slow consumer applies backpressure instead of queueing every match
+        let (result_tx, result_rx) = mpsc::sync_channel::<Entry>(self.channel_capacity);
         let (status_tx, status_rx) = mpsc::channel::<StatusMessage>();
+        let searcher = self.clone();
 
-        // Send initial status message
-        let _ = status_tx.send(StatusMessage::CurrentPath("Starting search...".to_string()));
+        let search_thread = std::thread::spawn(move || {
+            let deadline = searcher.timeout.map(|t| start_time + t);
+            let max_files = searcher.max_files;
+            let result = searcher.walk(full_path, start_time, &cancelled, &mut |event| {
+                match event {
+                    // Giving up on a full queue at the deadline ends the search as timed out
+                    SearchEvent::Match(entry) => {
+                        if send_until(&result_tx, entry, deadline, &cancelled)
+                            == SendOutcome::TimedOut
+                        {
+                            return ControlFlow::Break(());
+                        }
+                    }
+                    SearchEvent::Progress { scanned, errors } => {
+                        let _ = status_tx.send(StatusMessage::FileCount(scanned, max_files));
+                        let _ = status_tx.send(StatusMessage::PermissionErrors(errors));
+                    }
+                    SearchEvent::Status(msg) => {
+                        let _ = status_tx.send(msg);
+                    }
+                    SearchEvent::Finished(_) => {}
+                }
+                ControlFlow::Continue(())
+            });
+            let _ = status_tx.send(StatusMessage::Done);
+            result
+        });
 
+        (result_rx, status_rx, search_thread)
+    }
+
+    /// Run the search on a background thread, passing each event to `callback`.
+    ///
+    /// The callback is invoked on the search thread, in order, ending with
+    /// [`SearchEvent::Finished`]. Returning `ControlFlow::Break` cancels the
+    /// search; `Finished` still follows.
+    pub fn run<F>(&self, full_path: bool, callback: F) -> JoinHandle<()>
+    where
+        F: FnMut(SearchEvent) -> ControlFlow<()> + Send + 'static,
+    {
+        let cancelled = self.start_search();
+        let start_time = Instant::now();
+        let searcher = self.clone();
+        std::thread::spawn(move || searcher.finish_run(full_path, start_time, &cancelled, callback))
+    }
+
+    /// Like [`Searcher::run`], but on the calling thread, returning once the
+    /// search is finished
+    pub fn run_blocking<F>(&self, full_path: bool, callback: F)
+    where
+        F: FnMut(SearchEvent) -> ControlFlow<()>,
+    {
+        self.finish_run(full_path, Instant::now(), &self.start_search(), callback);
+    }
+
+    fn finish_run<F>(
+        &self,
+        full_path: bool,
+        start_time: Instant,
+        cancelled: &Arc<AtomicBool>,
+        mut callback: F,
+    ) where
+        F: FnMut(SearchEvent) -> ControlFlow<()>,
+    {
+        let result = self.walk(full_path, start_time, cancelled, &mut callback);
+        let _ = callback(SearchEvent::Finished(result));
+    }
+
+    /// Walk and match on the current thread, reporting everything but the
+    /// final result through `callback`, until done or `cancelled` is set
+    fn walk(
+        &self,
+        full_path: bool,
+        start_time: Instant,
+        cancelled: &Arc<AtomicBool>,
+        callback: &mut dyn FnMut(SearchEvent) -> ControlFlow<()>,
+    ) -> SearchResult {
         let matcher = Arc::clone(&self.matcher);
         let base_path = Arc::clone(&self.base_path);
         let max_depth = self.max_depth;
+        let min_depth = self.min_depth;
         let max_files = self.max_files;
+        let max_matches = self.max_matches;
+        let count_all_matches = self.count_all_matches;
         let timeout = self.timeout;
-        let start_time = self.start_time;
+        let follow_links = self.follow_links;
         let files_only = self.files_only;
         let dirs_only = self.dirs_only;
+        let hidden = self.hidden;
         let show_permission_errors = self.show_permission_errors;
+        let collect_metrics = self.collect_metrics;
+        let verbosity = self.verbosity;
+        let status_interval = self.status_interval;
+        let breadth_first = self.breadth_first;
+        let stats_only = self.stats_only;
+        let threads = self.threads;
+        let depth_below_match = self.depth_below_match;
+        let walk_matcher = Arc::clone(&self.matcher);
+        let file_system = Arc::clone(&self.file_system);
+        let prune = self.prune.clone();
+        let two_pass = self.two_pass;
+        let include_root = self.include_root;
+        let sum_matched_bytes = self.sum_matched_bytes;
+
+        // The callback breaking cancels the search, which every loop below checks
+        let mut send = |event: SearchEvent| {
+            if callback(event).is_break() {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+        };
+        send(SearchEvent::Status(StatusMessage::CurrentPath(
+            "Starting search...".to_string(),
+        )));
+
+        // Setup walkers over each root with optional depth limit, yielding each entry's depth
+        let walker = crate::paths::search_roots(base_path.as_path())
+            .into_iter()
+            .flat_map(move |root| {
+                let mut below_match = depth_below_match
+                    .map(|limit| within_match_depth(Arc::clone(&walk_matcher), full_path, limit));
+                let prune = prune.clone();
+                let wanted = below_match.is_some() || prune.is_some() || !hidden;
+                let keep = wanted.then(|| {
+                    Box::new(move |entry: &Entry| {
+                        let pruned = entry.depth() > 0
+                            && entry.is_dir()
+                            && prune
+                                .as_ref()
+                                .is_some_and(|prune| entry.is_match(&**prune, false));
+                        // The root is searched even when its own name is hidden
+                        let skipped_hidden = !hidden
+                            && entry.depth() > 0
+                            && entry.file_name().as_encoded_bytes().starts_with(b".");
+                        !pruned
+                            && !skipped_hidden
+                            && below_match.as_mut().is_none_or(|keep| keep(entry))
+                    }) as KeepEntry
+                });
+                let opts = WalkOpts {
+                    max_depth,
+                    breadth_first,
+                    follow_links,
+                    keep,
+                };
+                file_system.walk(&root, opts)
+            });
+        // With a timeout the walk runs on its own thread, so a stat that hangs
+        // on an unresponsive mount can't hold the search past its deadline
+        let walker: Box<dyn Iterator<Item = _>> = match timeout {
+            Some(limit) => Box::new(watchdog::until_deadline(
+                walker,
+                start_time + limit,
+                Arc::clone(cancelled),
+            )),
+            None => Box::new(walker),
+        };
+
+        // Variables to track progress
+        let mut file_count = 0;
+        let mut permission_errors = 0;
+        let mut other_errors: BTreeMap<String, usize> = BTreeMap::new();
+        let mut limit_reached = false;
+        let mut timed_out = false;
+        let mut timeout_warned = false;
+        let mut deepest = 0;
+        let mut depth_limit_reached = None;
+        let mut root_match_skipped = false;
+        let mut metrics = SearchMetrics {
+            threads,
+            ..SearchMetrics::default()
+        };
+        let mut tree_stats = stats_only.then(TreeStats::default);
+        let mut count_updates = Throttle::new(status_interval);
+        let mut path_updates = Throttle::new(status_interval);
+        let mut permission_updates = Throttle::new(status_interval);
+        let collection_start = Instant::now();
+        let mut phase = MatchPhase::new(
+            Arc::clone(&matcher),
+            full_path,
+            verbosity,
+            max_matches,
+            count_all_matches,
+            timeout.map(|t| start_time + t),
+            Arc::clone(cancelled),
+            status_interval,
+            sum_matched_bytes,
+        );
 
-        let counter = Arc::new(AtomicUsize::new(0));
-        let counter_clone = Arc::clone(&counter);
+        // Entries are matched as they're walked unless asked to collect them first
+        let streaming = !two_pass && !stats_only;
+        let mut entries: Vec<Entry> = Vec::new();
+        let match_during_walk = max_matches > 0 && !count_all_matches && !stats_only;
 
-        // Store start time for elapsed time calculation
-        let search_start_time = self.start_time;
+        // Iterate over files with early termination
+        for result in walker {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
 
-        // Spawn a thread to collect and process files
-        let _search_thread = std::thread::spawn(move || {
-            // Setup walker with optional depth limit
-            let mut walker = WalkDir::new(base_path.as_path());
-            if let Some(depth) = max_depth {
-                walker = walker.max_depth(depth);
+            // Check for timeout, warning once when most of the budget is spent
+            if let Some(timeout_duration) = timeout {
+                let elapsed = start_time.elapsed();
+                if elapsed > timeout_duration {
+                    timed_out = true;
+                    send(SearchEvent::Status(StatusMessage::Timeout(
+                        timeout_duration.as_secs(),
+                    )));
+                    break;
+                }
+                if !timeout_warned && elapsed > timeout_duration.mul_f64(TIMEOUT_WARNING_RATIO) {
+                    timeout_warned = true;
+                    let remaining = (timeout_duration - elapsed).as_secs();
+                    send(SearchEvent::Status(StatusMessage::TimeoutApproaching(
+                        remaining,
+                    )));
+                }
             }
 
-            // Variables to track progress
-            let mut file_count = 0;
-            let mut permission_errors = 0;
-            let mut limit_reached = false;
-            let mut timed_out = false;
-            let mut match_count = 0;
-
-            // First pass: collect entries
-            let mut entries: Vec<DirEntry> = Vec::new();
-
-            // Iterate over files with early termination
-            for result in walker.into_iter() {
-                // Check for timeout
-                if let Some(timeout_duration) = timeout {
-                    if start_time.elapsed() > timeout_duration {
-                        timed_out = true;
-                        let _ = status_tx.send(StatusMessage::Timeout(timeout_duration.as_secs()));
+            // Process the entry
+            match result {
+                Ok(entry) => {
+                    let depth = entry.depth();
+                    // The file limit counts entries examined, not walk errors
+                    if max_files > 0 && file_count >= max_files {
+                        limit_reached = true;
+                        send(SearchEvent::Status(StatusMessage::LimitReached(max_files)));
                         break;
                     }
-                }
+                    file_count += 1;
+                    metrics.entries_walked += 1;
+                    deepest = deepest.max(depth);
+                    if max_depth == Some(depth) && entry.is_dir() {
+                        depth_limit_reached = max_depth;
+                    }
 
-                // Check file count limit
-                file_count += 1;
-                if max_files > 0 && file_count >= max_files {
-                    limit_reached = true;
-                    let _ = status_tx.send(StatusMessage::LimitReached(max_files));
-                    break;
-                }
+                    // Update counts periodically
+                    if count_updates.ready() {
+                        send(SearchEvent::Progress {
+                            scanned: file_count,
+                            errors: permission_errors,
+                        });
+                    }
 
-                // Update counts periodically
-                if file_count % 1000 == 0 || (max_files > 0 && max_files - file_count < 1000) {
-                    let _ = status_tx.send(StatusMessage::FileCount(file_count, max_files));
-                }
+                    // Summarizing the tree needs no matching phase
+                    if let Some(stats) = &mut tree_stats {
+                        stats.record(&entry);
+                        continue;
+                    }
 
-                // Process the entry
-                match result {
-                    Ok(entry) => {
-                        // Update current directory for spinner
-                        if entry.file_type().is_dir() {
-                            let rel_path = entry
-                                .path()
+                    // Update current directory for spinner
+                    if entry.is_dir() {
+                        metrics.dirs_walked += 1;
+                        if path_updates.ready() {
+                            let display_path = crate::paths::strip_extended_prefix(entry.path());
+                            let rel_path = display_path
                                 .strip_prefix(base_path.as_path())
-                                .unwrap_or(entry.path());
+                                .unwrap_or(&display_path);
                             let path_str = rel_path.to_string_lossy().to_string();
-                            if !path_str.is_empty() && file_count % 100 == 0 {
-                                let _ = status_tx.send(StatusMessage::CurrentPath(path_str));
+                            if !path_str.is_empty() {
+                                send(SearchEvent::Status(StatusMessage::CurrentPath(path_str)));
                             }
                         }
+                    }
+
+                    if verbosity >= 2 && entry.is_dir() && max_depth == Some(depth) {
+                        send(SearchEvent::Status(StatusMessage::Debug(format!(
+                            "{}: at max depth {depth}, not descending",
+                            entry.path().display(),
+                        ))));
+                    }
 
-                        // Apply file type filters
-                        if crate::matchers::should_process_entry(&entry, files_only, dirs_only) {
-                            entries.push(entry);
+                    if min_depth.is_some_and(|min| depth < min) {
+                        if verbosity >= 2 {
+                            send(SearchEvent::Status(StatusMessage::Debug(format!(
+                                "{}: above min depth, not matched (depth {depth})",
+                                entry.path().display(),
+                            ))));
                         }
+                        continue;
                     }
-                    Err(err) => {
-                        // Handle permission errors
-                        if let Some(path) = err.path() {
-                            if let Some(io_err) = err.io_error() {
-                                if io_err.kind() == std::io::ErrorKind::PermissionDenied {
-                                    permission_errors += 1;
-                                    if show_permission_errors {
-                                        let _ =
-                                            status_tx.send(StatusMessage::CurrentPath(format!(
-                                                "Permission denied: {}",
-                                                path.to_string_lossy()
-                                            )));
-                                    }
 
-                                    if permission_errors % 10 == 0 {
-                                        let _ = status_tx.send(StatusMessage::PermissionErrors(
-                                            permission_errors,
-                                        ));
+                    // A directory isn't a result of its own search unless asked for
+                    if depth == 0 && !include_root && entry.is_dir() {
+                        if verbosity >= 1 {
+                            send(SearchEvent::Status(StatusMessage::Debug(format!(
+                                "{}: skipped, the search root (--include-root to match it)",
+                                entry.path().display()
+                            ))));
+                        }
+                        root_match_skipped |= type_filter_reason(&entry, files_only, dirs_only)
+                            .is_none()
+                            && entry.is_match(&*matcher, full_path);
+                        continue;
+                    }
+
+                    // Apply file type filters
+                    match type_filter_reason(&entry, files_only, dirs_only) {
+                        None => {
+                            if verbosity >= 2 {
+                                send(SearchEvent::Status(StatusMessage::Debug(format!(
+                                    "{}: passed type filter (depth {depth})",
+                                    entry.path().display(),
+                                ))));
+                            }
+                            if streaming {
+                                let matching_start = collect_metrics.then(Instant::now);
+                                let flow = phase.process(&entry, &mut send);
+                                if let Some(start) = matching_start {
+                                    metrics.matching_time += start.elapsed();
+                                }
+                                match flow {
+                                    Flow::Continue => {}
+                                    Flow::Stop | Flow::Cancelled => break,
+                                    Flow::TimedOut => {
+                                        timed_out = true;
+                                        let timeout_secs = timeout.map_or(0, |t| t.as_secs());
+                                        send(SearchEvent::Status(StatusMessage::Timeout(
+                                            timeout_secs,
+                                        )));
+                                        break;
                                     }
                                 }
+                            // With a match limit there's no need to keep walking once
+                            // enough matches are in hand, so test entries right away
+                            } else if match_during_walk {
+                                if !phase.test(&entry, &mut send) {
+                                    continue;
+                                }
+                                entries.push(entry);
+                                if entries.len() >= max_matches {
+                                    break;
+                                }
+                            } else {
+                                entries.push(entry);
+                            }
+                        }
+                        Some(reason) => {
+                            if verbosity >= 1 {
+                                send(SearchEvent::Status(StatusMessage::Debug(format!(
+                                    "{}: skipped, {reason}",
+                                    entry.path().display()
+                                ))));
                             }
                         }
                     }
                 }
+                Err(err) => {
+                    // Handle permission errors
+                    if let Some(io_err) = err.io_error()
+                        && io_err.kind() == std::io::ErrorKind::PermissionDenied
+                    {
+                        permission_errors += 1;
+                        if show_permission_errors && let Some(path) = err.path() {
+                            send(SearchEvent::Status(StatusMessage::CurrentPath(format!(
+                                "Permission denied: {}",
+                                path.to_string_lossy()
+                            ))));
+                        }
+
+                        if permission_updates.ready() {
+                            send(SearchEvent::Progress {
+                                scanned: file_count,
+                                errors: permission_errors,
+                            });
+                        }
+                    } else {
+                        // Anything else also leaves part of the tree unsearched
+                        let kind = match err.io_error() {
+                            Some(io_err) => io_err.kind().to_string(),
+                            None if err.loop_ancestor().is_some() => "filesystem loop".to_string(),
+                            None => "other error".to_string(),
+                        };
+                        *other_errors.entry(kind).or_insert(0) += 1;
+                        if show_permission_errors {
+                            send(SearchEvent::Status(StatusMessage::CurrentPath(format!(
+                                "Error: {err}"
+                            ))));
+                        }
+                    }
+                }
             }
+        }
 
-            // Send final collection stats
-            let _ = status_tx.send(StatusMessage::FileCount(file_count, max_files));
-            let _ = status_tx.send(StatusMessage::PermissionErrors(permission_errors));
+        // The watchdog ends the walk silently when the deadline passes while blocked
+        if let Some(timeout_duration) = timeout
+            && !timed_out
+            && start_time.elapsed() > timeout_duration
+        {
+            timed_out = true;
+            send(SearchEvent::Status(StatusMessage::Timeout(
+                timeout_duration.as_secs(),
+            )));
+        }
+
+        // Send final collection stats
+        send(SearchEvent::Progress {
+            scanned: file_count,
+            errors: permission_errors,
+        });
 
-            // Second phase: Process entries
-            let _ = status_tx.send(StatusMessage::CurrentPath(format!(
+        // Time spent matching during a streaming walk isn't collection
+        metrics.collection_time = collection_start.elapsed() - metrics.matching_time;
+        metrics.peak_queued = entries.len();
+        let matching_start = Instant::now();
+
+        // Second phase: match what the walk collected
+        if !streaming {
+            send(SearchEvent::Status(StatusMessage::CurrentPath(format!(
                 "Searching {} files...",
                 entries.len()
-            )));
+            ))));
+        }
+
+        // Process entries; hitting the file limit only stops collection, so
+        // everything gathered up to it is still matched
+        for entry in &entries {
+            if timed_out || cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+
+            // Check for timeout
+            if let Some(timeout_duration) = timeout
+                && start_time.elapsed() > timeout_duration
+            {
+                timed_out = true;
+                send(SearchEvent::Status(StatusMessage::Timeout(
+                    timeout_duration.as_secs(),
+                )));
+                break;
+            }
 
-            // Process entries
-            for entry in &entries {
-                if limit_reached || timed_out {
+            // Entries collected with a match limit were tested as they were walked
+            let flow = if match_during_walk {
+                phase.report(entry, &mut send)
+            } else {
+                phase.process(entry, &mut send)
+            };
+            match flow {
+                Flow::Continue => {}
+                Flow::Stop | Flow::Cancelled => break,
+                Flow::TimedOut => {
+                    timed_out = true;
+                    let timeout_secs = timeout.map_or(0, |t| t.as_secs());
+                    send(SearchEvent::Status(StatusMessage::Timeout(timeout_secs)));
                     break;
                 }
+            }
+        }
 
-                // Check for timeout
-                if let Some(timeout_duration) = timeout {
-                    if start_time.elapsed() > timeout_duration {
-                        timed_out = true;
-                        break;
-                    }
-                }
+        metrics.matching_time += matching_start.elapsed();
+        let MatchPhase {
+            match_count,
+            matches_sent,
+            match_limit_reached,
+            matched_bytes,
+            ..
+        } = phase;
+        metrics.entries_matched = match_count;
+        let metrics = collect_metrics.then_some(metrics);
+        if let Some(metrics) = &metrics {
+            send(SearchEvent::Status(StatusMessage::Metrics(metrics.clone())));
+        }
 
-                // Process entry
-                if matcher.is_match(entry, full_path) {
-                    match_count += 1;
-                    counter_clone.fetch_add(1, Ordering::Relaxed);
+        // Return search results with properly calculated elapsed time
+        SearchResult {
+            matches: match_count,
+            matches_sent,
+            match_limit_reached,
+            entries_examined: file_count,
+            permission_errors,
+            other_errors,
+            elapsed: start_time.elapsed(),
+            limit_reached,
+            timed_out,
+            metrics,
+            tree_stats,
+            deepest,
+            depth_limit_reached,
+            root_match_skipped,
+            matched_bytes,
+        }
+    }
+}
 
-                    // Send match immediately for display
-                    let _ = result_tx.send(entry.path().to_path_buf());
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::atomic::AtomicUsize;
 
-                    // Update the counter periodically
-                    if match_count % 10 == 0 {
-                        let _ = status_tx.send(StatusMessage::CurrentPath(format!(
-                            "Found {match_count} matches so far..."
-                        )));
-                    }
-                }
+    use walkdir::DirEntry;
+
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+
+    /// `root/{a.txt, b.log, sub/{c.txt, d.txt, deeper/e.txt}}`
+    pub(super) fn sample_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        for file in [
+            "a.txt",
+            "b.log",
+            "sub/c.txt",
+            "sub/d.txt",
+            "sub/deeper/e.txt",
+        ] {
+            fs::write(root.join(file), file).unwrap();
+        }
+        dir
+    }
+
+    pub(super) fn searcher(root: &Path, pattern: &str, options: SearchOptions) -> Searcher {
+        Searcher::with_options(
+            Box::new(GlobEntryMatcher::new(pattern).unwrap()),
+            root.to_path_buf(),
+            options,
+        )
+    }
+
+    fn metrics_options() -> SearchOptions {
+        SearchOptions {
+            collect_metrics: true,
+            ..SearchOptions::default()
+        }
+    }
+
+    #[test]
+    fn requested_threads_are_reported_without_changing_results() {
+        let dir = sample_tree();
+        let (expected, _) = searcher(dir.path(), "*.txt", metrics_options()).search_collect(false);
+        for (requested, reported) in [(0, 1), (1, 1), (8, 8)] {
+            let options = SearchOptions {
+                threads: requested,
+                ..metrics_options()
+            };
+            let (matches, result) = searcher(dir.path(), "*.txt", options).search_collect(false);
+            assert_eq!(matches, expected);
+            assert_eq!(result.metrics.unwrap().threads, reported, "{requested}");
+        }
+    }
+
+    #[test]
+    fn metrics_agree_with_result() {
+        let dir = sample_tree();
+        for two_pass in [false, true] {
+            let options = SearchOptions {
+                two_pass,
+                ..metrics_options()
+            };
+            let (matches, result) = searcher(dir.path(), "*.txt", options).search_collect(false);
+            let metrics = result.metrics.expect("metrics were requested");
+
+            assert_eq!(matches.len(), 4);
+            assert_eq!(metrics.entries_matched, result.matches);
+            assert_eq!(metrics.entries_walked, result.entries_examined);
+            // The root, sub, and sub/deeper
+            assert_eq!(metrics.dirs_walked, 3);
+            assert_eq!(metrics.entries_walked, 8);
+            assert_eq!(metrics.peak_queued, if two_pass { 7 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn metrics_event_matches_result() {
+        let dir = sample_tree();
+        let mut reported = None;
+        let mut finished = None;
+        searcher(dir.path(), "*.txt", metrics_options()).run_blocking(false, |event| {
+            match event {
+                SearchEvent::Status(StatusMessage::Metrics(metrics)) => reported = Some(metrics),
+                SearchEvent::Finished(result) => finished = Some(result),
+                _ => {}
             }
+            ControlFlow::Continue(())
+        });
 
-            // Signal completion
-            let _ = status_tx.send(StatusMessage::Done);
+        let reported = reported.expect("metrics event was sent");
+        let finished = finished.unwrap().metrics.unwrap();
+        assert_eq!(reported.entries_walked, finished.entries_walked);
+        assert_eq!(reported.dirs_walked, finished.dirs_walked);
+        assert_eq!(reported.entries_matched, finished.entries_matched);
+    }
+
+    /// Yields `root` and then made-up files without end, counting entries handed out
+    struct EndlessFileSystem {
+        walked: Arc<AtomicUsize>,
+    }
 
-            // Return search results with properly calculated elapsed time
-            SearchResult {
-                matches: match_count,
-                files_scanned: file_count,
-                permission_errors,
-                elapsed: search_start_time.elapsed(), // Use the search start time for elapsed calculation
-                limit_reached,
-                timed_out,
+    impl FileSystem for EndlessFileSystem {
+        fn walk(&self, root: &Path, _opts: WalkOpts) -> Walk {
+            let dir = root.to_path_buf();
+            let walked = Arc::clone(&self.walked);
+            let files =
+                (0..).map(move |i| Entry::new(dir.join(format!("f{i}.txt")), 1, EntryKind::File));
+            let entries = std::iter::once(Entry::new(root, 0, EntryKind::Dir))
+                .chain(files)
+                .map(move |entry| {
+                    walked.fetch_add(1, Ordering::Relaxed);
+                    Ok(entry)
+                });
+            Box::new(entries)
+        }
+    }
+
+    pub(super) fn endless_searcher(root: &Path) -> (Searcher, Arc<AtomicUsize>) {
+        let walked = Arc::new(AtomicUsize::new(0));
+        let options = SearchOptions {
+            channel_capacity: 1,
+            ..SearchOptions::default()
+        };
+        let file_system = EndlessFileSystem {
+            walked: Arc::clone(&walked),
+        };
+        let searcher = searcher(root, "*.txt", options).with_file_system(Arc::new(file_system));
+        (searcher, walked)
+    }
+
+    /// Whether the walk counted by `walked` has stopped advancing
+    pub(super) fn walk_stops(walked: &AtomicUsize) -> bool {
+        let mut last = walked.load(Ordering::Relaxed);
+        for _ in 0..50 {
+            std::thread::sleep(Duration::from_millis(20));
+            let now = walked.load(Ordering::Relaxed);
+            if now == last {
+                return true;
             }
+            last = now;
+        }
+        false
+    }
+
+    #[test]
+    fn dropping_iterator_stops_walk() {
+        let dir = sample_tree();
+        let (searcher, walked) = endless_searcher(dir.path());
+        let mut matches = searcher.search_iter(false);
+        assert!(matches.next().is_some());
+        drop(matches);
+        assert!(walk_stops(&walked));
+    }
+
+    #[test]
+    fn searches_from_clones_cancel_independently() {
+        let dir = sample_tree();
+        let (endless, walked) = endless_searcher(dir.path());
+        let finite = endless.clone().with_file_system(Arc::new(OsFileSystem));
+
+        let mut first = endless.search_iter(false);
+        assert!(first.next().is_some());
+        // Starting a second search mustn't revive the first, nor dropping
+        // the first stop the second
+        let second = finite.search_iter(false);
+        drop(first);
+        assert_eq!(second.count(), 4);
+        assert!(walk_stops(&walked));
+    }
+
+    /// `/data` holding `count` files of 10 bytes each, `f0.txt` and so on
+    fn memory_tree(count: usize) -> MemoryFileSystem {
+        (0..count).fold(MemoryFileSystem::new(), |fs, i| {
+            fs.with_file(format!("/data/f{i}.txt"), 10)
+        })
+    }
+
+    fn memory_search(
+        file_system: MemoryFileSystem,
+        options: SearchOptions,
+    ) -> (Vec<PathBuf>, SearchResult) {
+        searcher(Path::new("/data"), "*.txt", options)
+            .with_file_system(Arc::new(file_system))
+            .search_collect(false)
+    }
+
+    #[test]
+    fn file_limit_stops_the_walk() {
+        let options = SearchOptions {
+            max_files: 5,
+            ..SearchOptions::default()
+        };
+        let (matches, result) = memory_search(memory_tree(20), options);
+        assert!(result.limit_reached);
+        assert_eq!(result.entries_examined, 5);
+        // The root is one of the five entries examined
+        assert_eq!(matches.len(), 4);
+        assert_eq!(result.matches, 4);
+    }
+
+    #[test]
+    fn file_limit_counts_examined_entries_but_not_errors() {
+        // The root and 19 files make 20 entries
+        let options = SearchOptions {
+            max_files: 10,
+            ..SearchOptions::default()
+        };
+        let (_, result) = memory_search(memory_tree(19), options.clone());
+        assert!(result.limit_reached);
+        assert_eq!(result.entries_examined, 10);
+
+        // The five unreadable directories are entries, but failing to read them isn't
+        let file_system = (0..5).fold(memory_tree(4), |fs, i| {
+            fs.with_unreadable_dir(
+                format!("/data/private{i}"),
+                std::io::ErrorKind::PermissionDenied,
+            )
         });
+        let (matches, result) = memory_search(file_system, options);
+        assert!(!result.limit_reached);
+        assert_eq!(result.entries_examined, 10);
+        assert_eq!(result.permission_errors, 5);
+        assert_eq!(matches.len(), 4);
+    }
 
-        // Don't wait for the search thread to complete - we want results to stream immediately
-        // But create an initial result with the correct start time for elapsed calculation
-        let empty_result = SearchResult {
-            matches: 0,
-            files_scanned: 0,
-            permission_errors: 0,
-            elapsed: self.start_time.elapsed(), // Use actual elapsed time
-            limit_reached: false,
-            timed_out: false,
+    #[test]
+    fn entries_collected_before_the_file_limit_are_all_matched() {
+        // Every other file matches, so the root and the next 99 entries hold 50 matches
+        let file_system = (0..200).fold(MemoryFileSystem::new(), |fs, i| {
+            let ext = if i % 2 == 0 { "txt" } else { "rs" };
+            fs.with_file(format!("/data/f{i:03}.{ext}"), 10)
+        });
+        for two_pass in [false, true] {
+            let options = SearchOptions {
+                max_files: 100,
+                two_pass,
+                ..SearchOptions::default()
+            };
+            let (matches, result) = memory_search(file_system.clone(), options);
+            assert!(result.limit_reached);
+            assert_eq!(result.entries_examined, 100);
+            assert_eq!(matches.len(), 50, "two_pass: {two_pass}");
+            assert_eq!(result.matches, 50);
+        }
+    }
+
+    #[test]
+    fn search_root_matches_only_when_included() {
+        let search = |pattern: &str, include_root| {
+            let options = SearchOptions {
+                include_root,
+                ..SearchOptions::default()
+            };
+            searcher(Path::new("/data"), pattern, options)
+                .with_file_system(Arc::new(memory_tree(2)))
+                .search_collect(false)
         };
 
-        (result_rx, status_rx, empty_result)
+        let (matches, result) = search("*", false);
+        assert_eq!(matches.len(), 2);
+        assert!(result.root_match_skipped);
+        // The root is still walked and counted, just never a result
+        assert_eq!(result.entries_examined, 3);
+
+        let (matches, result) = search("*", true);
+        assert_eq!(matches.len(), 3);
+        assert!(matches.contains(&PathBuf::from("/data")));
+        assert!(!result.root_match_skipped);
+
+        // A root that wouldn't match anyway isn't noted
+        let (matches, result) = search("*.txt", false);
+        assert_eq!(matches.len(), 2);
+        assert!(!result.root_match_skipped);
     }
 
-    /// Helper function to print sudo suggestion if needed
-    pub fn print_permission_hint(permission_errors: usize, path: &str, pattern: &str) {
-        if permission_errors > 5 {
-            println!(
-                "\n{}",
-                "Hint: Many permission errors encountered. Try running with sudo:".yellow()
-            );
-            println!(
-                "      {}",
-                format!("sudo seek \"{path}\" \"{pattern}\"").yellow()
+    #[test]
+    fn match_limit_stops_or_keeps_counting() {
+        let options = SearchOptions {
+            max_matches: 3,
+            ..SearchOptions::default()
+        };
+        let (matches, result) = memory_search(memory_tree(20), options.clone());
+        assert_eq!(matches.len(), 3);
+        assert!(result.match_limit_reached);
+        assert_eq!((result.matches, result.matches_sent), (3, 3));
+        assert!(result.entries_examined < 21);
+
+        let options = SearchOptions {
+            count_all_matches: true,
+            ..options
+        };
+        let (matches, result) = memory_search(memory_tree(20), options);
+        assert_eq!(matches.len(), 3);
+        assert_eq!((result.matches, result.matches_sent), (20, 3));
+        assert_eq!(result.entries_examined, 21);
+    }
+
+    #[test]
+    fn slow_walk_times_out() {
+        let options = SearchOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..SearchOptions::default()
+        };
+        let slow = memory_tree(1000).with_delay(Duration::from_millis(10));
+        let started = Instant::now();
+        let (matches, result) = memory_search(slow, options);
+        assert!(result.timed_out);
+        assert!(!result.limit_reached);
+        assert!(result.entries_examined < 1000);
+        assert_eq!(matches.len(), result.matches_sent);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Takes `delay` over every entry it's asked about
+    struct SlowMatcher {
+        inner: GlobEntryMatcher,
+        delay: Duration,
+    }
+
+    impl EntryMatcher for SlowMatcher {
+        fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+            std::thread::sleep(self.delay);
+            self.inner.is_match(entry, full_path)
+        }
+
+        fn is_path_match(&self, path: &Path, full_path: bool) -> bool {
+            std::thread::sleep(self.delay);
+            self.inner.is_path_match(path, full_path)
+        }
+    }
+
+    #[test]
+    fn slow_matching_times_out_after_the_walk() {
+        let matcher = SlowMatcher {
+            inner: GlobEntryMatcher::new("*.txt").unwrap(),
+            delay: Duration::from_millis(10),
+        };
+        let options = SearchOptions {
+            two_pass: true,
+            timeout: Some(Duration::from_millis(200)),
+            ..SearchOptions::default()
+        };
+        let mut timeouts = 0;
+        let mut found = 0;
+        let mut finished = None;
+        Searcher::with_options(Box::new(matcher), PathBuf::from("/data"), options)
+            .with_file_system(Arc::new(memory_tree(1000)))
+            .run_blocking(false, |event| {
+                match event {
+                    SearchEvent::Status(StatusMessage::Timeout(_)) => timeouts += 1,
+                    SearchEvent::Match(_) => found += 1,
+                    SearchEvent::Finished(result) => finished = Some(result),
+                    _ => {}
+                }
+                ControlFlow::Continue(())
+            });
+
+        let result = finished.expect("the search didn't finish");
+        // The walk itself is quick, so the deadline passed while matching
+        assert_eq!(result.entries_examined, 1001);
+        assert!(result.timed_out);
+        assert_eq!(timeouts, 1);
+        assert!(found < 1000);
+        assert_eq!(found, result.matches_sent);
+    }
+
+    #[test]
+    fn walk_errors_are_counted_by_kind() {
+        let file_system = memory_tree(2)
+            .with_unreadable_dir("/data/private", std::io::ErrorKind::PermissionDenied)
+            .with_unreadable_dir("/data/locked", std::io::ErrorKind::PermissionDenied)
+            .with_unreadable_dir("/data/gone", std::io::ErrorKind::NotFound)
+            .with_file("/data/open/f.txt", 1);
+        let (matches, result) = memory_search(file_system, SearchOptions::default());
+        assert_eq!(matches.len(), 3);
+        assert_eq!(result.permission_errors, 2);
+        assert_eq!(
+            result.other_errors,
+            BTreeMap::from([("entity not found".to_string(), 1)])
+        );
+        // Errors aren't entries: the root, 4 directories, and 3 files
+        assert_eq!(result.entries_examined, 8);
+    }
+
+    #[test]
+    fn final_permission_error_count_arrives_before_done() {
+        let file_system = (0..12).fold(memory_tree(2), |fs, i| {
+            fs.with_unreadable_dir(
+                format!("/data/private{i}"),
+                std::io::ErrorKind::PermissionDenied,
+            )
+        });
+        let (matches, status, handle) =
+            searcher(Path::new("/data"), "*.txt", SearchOptions::default())
+                .with_file_system(Arc::new(file_system))
+                .search(false);
+        assert_eq!(matches.iter().count(), 2);
+        let result = handle.join().unwrap();
+        assert_eq!(result.permission_errors, 12);
+
+        let statuses: Vec<StatusMessage> = status.iter().collect();
+        assert!(matches!(statuses.last(), Some(StatusMessage::Done)));
+        let last_count = statuses.iter().rev().find_map(|msg| match msg {
+            StatusMessage::PermissionErrors(count) => Some(*count),
+            _ => None,
+        });
+        assert_eq!(last_count, Some(result.permission_errors));
+    }
+
+    /// Yields the root and then a symlink back to it
+    struct LoopingFileSystem;
+
+    impl FileSystem for LoopingFileSystem {
+        fn walk(&self, root: &Path, _opts: WalkOpts) -> Walk {
+            let entries = [
+                Ok(Entry::new(root, 0, EntryKind::Dir)),
+                Err(WalkError::filesystem_loop(root.join("back"), 1, root)),
+            ];
+            Box::new(entries.into_iter())
+        }
+    }
+
+    #[test]
+    fn filesystem_loops_are_counted_apart() {
+        let (_, result) = searcher(Path::new("/data"), "*", SearchOptions::default())
+            .with_file_system(Arc::new(LoopingFileSystem))
+            .search_collect(false);
+        assert_eq!(result.permission_errors, 0);
+        assert_eq!(
+            result.other_errors,
+            BTreeMap::from([("filesystem loop".to_string(), 1)])
+        );
+    }
+
+    #[test]
+    fn depth_limit_is_noted_only_when_it_cut_the_walk() {
+        let file_system = MemoryFileSystem::new()
+            .with_file("/data/a.txt", 1)
+            .with_file("/data/sub/b.txt", 1)
+            .with_file("/data/sub/deeper/c.txt", 1);
+        let at_depth = |max_depth| {
+            let options = SearchOptions {
+                max_depth: Some(max_depth),
+                ..SearchOptions::default()
+            };
+            memory_search(file_system.clone(), options)
+        };
+
+        let (matches, result) = at_depth(1);
+        assert_eq!(matches, [PathBuf::from("/data/a.txt")]);
+        assert_eq!(result.depth_limit_reached, Some(1));
+        let (matches, result) = at_depth(3);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(result.depth_limit_reached, None);
+        assert_eq!(result.deepest, 3);
+    }
+
+    #[test]
+    fn sizes_come_from_the_file_system() {
+        let file_system = MemoryFileSystem::new()
+            .with_file("/data/small.txt", 5)
+            .with_file("/data/sub/big.log", 500)
+            .with_file("/data/sub/mid.txt", 50);
+        let options = SearchOptions {
+            stats_only: true,
+            ..SearchOptions::default()
+        };
+        let (_, result) = memory_search(file_system.clone(), options);
+        let stats = result.tree_stats.unwrap();
+        assert_eq!((stats.files, stats.dirs, stats.total_bytes), (3, 1, 555));
+        assert_eq!(
+            stats.largest,
+            Some((PathBuf::from("/data/sub/big.log"), 500))
+        );
+
+        let options = SearchOptions {
+            sum_matched_bytes: true,
+            ..SearchOptions::default()
+        };
+        let (_, result) = memory_search(file_system, options);
+        assert_eq!(result.matched_bytes, Some(55));
+    }
+
+    #[test]
+    fn time_counts_from_each_search_not_the_searcher() {
+        let options = SearchOptions {
+            timeout: Some(Duration::from_millis(300)),
+            ..SearchOptions::default()
+        };
+        let searcher = searcher(Path::new("/data"), "*.txt", options)
+            .with_file_system(Arc::new(memory_tree(5)));
+        std::thread::sleep(Duration::from_millis(400));
+        for _ in 0..2 {
+            let (matches, result) = searcher.search_collect(false);
+            assert_eq!(matches.len(), 5);
+            assert!(!result.timed_out);
+            assert!(
+                result.elapsed < Duration::from_millis(300),
+                "{:?}",
+                result.elapsed
             );
+        }
+    }
+
+    #[test]
+    fn stats_only_counts_everything_without_matching() {
+        let file_system = MemoryFileSystem::new()
+            .with_file("/data/a.txt", 3)
+            .with_file("/data/b.log", 4)
+            .with_file("/data/sub/c.md", 5);
+        let options = SearchOptions {
+            stats_only: true,
+            ..SearchOptions::default()
+        };
+        let (matches, result) = searcher(Path::new("/data"), "nothing-matches-this", options)
+            .with_file_system(Arc::new(file_system))
+            .search_collect(false);
+        assert!(matches.is_empty());
+        assert_eq!(result.matches, 0);
+        let stats = result.tree_stats.unwrap();
+        assert_eq!((stats.files, stats.dirs, stats.total_bytes), (3, 1, 12));
+        assert_eq!(stats.largest, Some((PathBuf::from("/data/sub/c.md"), 5)));
+        assert!(result.metrics.is_none() && result.matched_bytes.is_none());
+    }
+
+    #[test]
+    fn break_stops_the_walk() {
+        let dir = sample_tree();
+        let (searcher, walked) = endless_searcher(dir.path());
+        let mut events = Vec::new();
+        // The walk never ends by itself, so returning at all means Break stopped it
+        searcher.run_blocking(false, |event| {
+            let stop = matches!(event, SearchEvent::Match(_));
+            events.push(event);
+            if stop {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert!(matches!(events.last(), Some(SearchEvent::Finished(_))));
+
+        let breaks = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&breaks);
+        let handle = searcher.run(false, move |event| match event {
+            SearchEvent::Match(_) => {
+                counted.fetch_add(1, Ordering::Relaxed);
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        });
+        for _ in 0..250 {
+            if handle.is_finished() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(handle.is_finished());
+        handle.join().unwrap();
+        assert!(walk_stops(&walked));
+        // Matches already found may still be reported, but the walk goes no further
+        assert!(breaks.load(Ordering::Relaxed) >= 1);
+    }
+
+    #[test]
+    fn run_reports_events_in_order_and_finishes_last() {
+        let dir = sample_tree();
+        let options = SearchOptions {
+            status_interval: Duration::ZERO,
+            ..SearchOptions::default()
+        };
+        let mut events = Vec::new();
+        searcher(dir.path(), "*.txt", options).run_blocking(false, |event| {
+            events.push(event);
+            ControlFlow::Continue(())
+        });
+
+        let Some(SearchEvent::Finished(result)) = events.pop() else {
+            panic!("the last event isn't Finished");
+        };
+        assert!(!events.iter().any(|e| matches!(e, SearchEvent::Finished(_))));
+        let mut found: Vec<PathBuf> = events
+            .iter()
+            .filter_map(|event| match event {
+                SearchEvent::Match(entry) => {
+                    Some(entry.path().strip_prefix(dir.path()).unwrap().into())
+                }
+                _ => None,
+            })
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            ["a.txt", "sub/c.txt", "sub/d.txt", "sub/deeper/e.txt"].map(PathBuf::from)
+        );
+        assert_eq!(result.matches, 4);
 
-            if cfg!(target_os = "macos") {
-                println!("\n{}", "On macOS, some directories may still be restricted due to System Integrity Protection.".yellow());
-                println!("{}", "For searching user data directories, you may need to grant Terminal 'Full Disk Access'".yellow());
-                println!(
-                    "{}",
-                    "in System Preferences → Privacy & Security → Full Disk Access.".yellow()
-                );
+        // Progress counts only ever grow and never pass the final tally
+        let scanned: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                SearchEvent::Progress { scanned, .. } => Some(*scanned),
+                _ => None,
+            })
+            .collect();
+        assert!(!scanned.is_empty());
+        assert!(scanned.is_sorted());
+        assert!(
+            scanned
+                .iter()
+                .all(|&count| count <= result.entries_examined)
+        );
+    }
+
+    #[test]
+    fn cancel_reaches_running_searches_only() {
+        let dir = sample_tree();
+        let (searcher, walked) = endless_searcher(dir.path());
+        let (matches, _status, handle) = searcher.search(false);
+        assert!(matches.recv().is_ok());
+        searcher.cancel();
+        drop(matches);
+        handle.join().unwrap();
+        assert!(walk_stops(&walked));
+
+        let later = searcher.clone().with_file_system(Arc::new(OsFileSystem));
+        assert_eq!(later.search_iter(false).count(), 4);
+    }
+
+    #[test]
+    fn pruned_directories_are_not_walked() {
+        let dir = sample_tree();
+        let prune = GlobEntryMatcher::new("deeper").unwrap();
+        let (mut matches, result) = searcher(dir.path(), "*.txt", SearchOptions::default())
+            .with_prune(Box::new(prune))
+            .search_collect(false);
+        matches.sort();
+        let expected: Vec<_> = ["a.txt", "sub/c.txt", "sub/d.txt"]
+            .iter()
+            .map(|file| dir.path().join(file))
+            .collect();
+        assert_eq!(matches, expected);
+        // The root, a.txt, b.log, sub, and its two files
+        assert_eq!(result.entries_examined, 6);
+    }
+
+    /// Records every path it's asked about
+    struct RecordingMatcher {
+        inner: GlobEntryMatcher,
+        tested: Arc<Mutex<Vec<PathBuf>>>,
+    }
+
+    impl EntryMatcher for RecordingMatcher {
+        fn is_match(&self, entry: &DirEntry, full_path: bool) -> bool {
+            self.tested.lock().unwrap().push(entry.path().to_path_buf());
+            self.inner.is_match(entry, full_path)
+        }
+    }
+
+    #[test]
+    fn limited_search_tests_each_entry_once() {
+        let dir = sample_tree();
+        for two_pass in [false, true] {
+            let tested = Arc::new(Mutex::new(Vec::new()));
+            let matcher = RecordingMatcher {
+                inner: GlobEntryMatcher::new("*.txt").unwrap(),
+                tested: Arc::clone(&tested),
+            };
+            let options = SearchOptions {
+                two_pass,
+                max_matches: 2,
+                ..SearchOptions::default()
+            };
+            let (matches, result) =
+                Searcher::with_options(Box::new(matcher), dir.path().to_path_buf(), options)
+                    .search_collect(false);
+
+            assert_eq!(matches.len(), 2);
+            assert_eq!((result.matches, result.matches_sent), (2, 2));
+            let mut tested = tested.lock().unwrap().clone();
+            let count = tested.len();
+            tested.sort();
+            tested.dedup();
+            assert_eq!(tested.len(), count, "an entry was matched twice");
+        }
+    }
+
+    #[test]
+    fn matched_bytes_only_when_requested() {
+        let dir = sample_tree();
+        let (_, result) =
+            searcher(dir.path(), "*.txt", SearchOptions::default()).search_collect(false);
+        assert_eq!(result.matched_bytes, None);
+
+        let options = SearchOptions {
+            sum_matched_bytes: true,
+            ..SearchOptions::default()
+        };
+        let (_, result) = searcher(dir.path(), "*.txt", options).search_collect(false);
+        // Each file holds its own relative path
+        let expected = ["a.txt", "sub/c.txt", "sub/d.txt", "sub/deeper/e.txt"]
+            .iter()
+            .map(|file| file.len() as u64)
+            .sum();
+        assert_eq!(result.matched_bytes, Some(expected));
+    }
+
+    #[test]
+    fn depth_below_match_in_either_walk_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["match/a/b", "other/match"] {
+            fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        for file in [
+            "e.txt",
+            "match/x.txt",
+            "match/a/b/c.txt",
+            "other/match/d.txt",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        let expected: Vec<_> = [
+            "e.txt",
+            "match",
+            "match/x.txt",
+            "other/match",
+            "other/match/d.txt",
+        ]
+        .iter()
+        .map(|path| root.join(path))
+        .collect();
+
+        for breadth_first in [false, true] {
+            let options = SearchOptions {
+                depth_below_match: Some(1),
+                breadth_first,
+                ..SearchOptions::default()
+            };
+            let (mut matches, _) = searcher(root, "{match,*.txt}", options).search_collect(false);
+            if breadth_first {
+                let depths: Vec<_> = matches.iter().map(|m| m.components().count()).collect();
+                assert!(depths.is_sorted(), "{matches:?}");
             }
+            matches.sort();
+            assert_eq!(matches, expected, "breadth_first: {breadth_first}");
         }
     }
+
+    fn relative_matches(root: &Path, pattern: &str, options: SearchOptions) -> Vec<String> {
+        let (matches, _) = searcher(root, pattern, options).search_collect(false);
+        let mut matches: Vec<String> = matches
+            .iter()
+            .map(|m| m.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        matches.sort();
+        matches
+    }
+
+    #[test]
+    fn min_depth_skips_shallow_matches_but_walks_them() {
+        let dir = sample_tree();
+        for breadth_first in [false, true] {
+            let options = SearchOptions {
+                min_depth: Some(2),
+                breadth_first,
+                ..SearchOptions::default()
+            };
+            assert_eq!(
+                relative_matches(dir.path(), "*.txt", options),
+                ["sub/c.txt", "sub/d.txt", "sub/deeper/e.txt"]
+            );
+        }
+    }
+
+    #[test]
+    fn hidden_entries_can_be_skipped_with_their_contents() {
+        let dir = sample_tree();
+        let root = dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::write(root.join(".git/f.txt"), "").unwrap();
+        fs::write(root.join("sub/.g.txt"), "").unwrap();
+
+        let found = relative_matches(root, "*.txt", SearchOptions::default());
+        assert!(found.contains(&".git/f.txt".to_string()), "{found:?}");
+        assert!(found.contains(&"sub/.g.txt".to_string()), "{found:?}");
+
+        for breadth_first in [false, true] {
+            let options = SearchOptions {
+                hidden: false,
+                breadth_first,
+                ..SearchOptions::default()
+            };
+            assert_eq!(
+                relative_matches(root, "*.txt", options),
+                ["a.txt", "sub/c.txt", "sub/d.txt", "sub/deeper/e.txt"]
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn followed_links_are_descended_into_and_loops_reported() {
+        let dir = sample_tree();
+        let root = dir.path();
+        std::os::unix::fs::symlink(root.join("sub/deeper"), root.join("link")).unwrap();
+        std::os::unix::fs::symlink(root, root.join("sub/back")).unwrap();
+
+        let found = relative_matches(root, "e.txt", SearchOptions::default());
+        assert_eq!(found, ["sub/deeper/e.txt"]);
+
+        for breadth_first in [false, true] {
+            let options = SearchOptions {
+                follow_links: true,
+                breadth_first,
+                show_permission_errors: true,
+                ..SearchOptions::default()
+            };
+            let (matches, result) = searcher(root, "e.txt", options).search_collect(false);
+            let mut found: Vec<_> = matches
+                .iter()
+                .map(|m| m.strip_prefix(root).unwrap().display().to_string())
+                .collect();
+            found.sort();
+            assert_eq!(found, ["link/e.txt", "sub/deeper/e.txt"], "{breadth_first}");
+            assert_eq!(
+                result.other_errors.values().sum::<usize>(),
+                1,
+                "{:?}",
+                result.other_errors
+            );
+        }
+    }
+
+    #[test]
+    fn metrics_only_when_requested() {
+        let dir = sample_tree();
+        let (_, result) =
+            searcher(dir.path(), "*.txt", SearchOptions::default()).search_collect(false);
+        assert!(result.metrics.is_none());
+    }
 }
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use super::{DEFAULT_CHANNEL_CAPACITY, DEFAULT_STATUS_INTERVAL};
+
+/// Everything that shapes a search apart from the pattern and where it starts.
+///
+/// The defaults search the whole tree, hidden entries included, on one
+/// thread with no limits;
+/// `cli::Args::to_search_options` fills one in from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Deepest level to descend to, counting the base path as 0
+    pub max_depth: Option<usize>,
+    /// Don't match entries shallower than this, though they're still walked
+    pub min_depth: Option<usize>,
+    /// Stop after looking at this many entries; 0 for no limit
+    pub max_files: usize,
+    /// Stop after this many matches; 0 for no limit
+    pub max_matches: usize,
+    /// Keep counting matches past `max_matches` for the summary
+    pub count_all_matches: bool,
+    pub timeout: Option<Duration>,
+    /// Descend into directories that symlinks point to
+    pub follow_links: bool,
+    pub files_only: bool,
+    pub dirs_only: bool,
+    /// Search entries whose names start with `.`; without it hidden
+    /// directories aren't descended into either
+    pub hidden: bool,
+    pub show_permission_errors: bool,
+    /// Time the walk and the matching separately for `SearchResult::metrics`
+    pub collect_metrics: bool,
+    /// 1 reports skipped entries, 2 matched ones too
+    pub verbosity: u8,
+    /// Matches queued for display before the search waits
+    pub channel_capacity: usize,
+    /// Minimum time between progress updates
+    pub status_interval: Duration,
+    /// Report shallower matches before deeper ones
+    pub breadth_first: bool,
+    /// Summarize the tree without matching anything
+    pub stats_only: bool,
+    /// Threads for walking directories; 0 is treated as 1
+    pub threads: usize,
+    /// Only descend this many levels below a matching directory
+    pub depth_below_match: Option<usize>,
+    /// Walk the whole tree before matching anything
+    pub two_pass: bool,
+    /// Let a directory being searched match itself
+    pub include_root: bool,
+    /// Stat matched files for `SearchResult::matched_bytes`
+    pub sum_matched_bytes: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            max_depth: None,
+            min_depth: None,
+            max_files: 0,
+            max_matches: 0,
+            count_all_matches: false,
+            timeout: None,
+            follow_links: false,
+            files_only: false,
+            dirs_only: false,
+            hidden: true,
+            show_permission_errors: false,
+            collect_metrics: false,
+            verbosity: 0,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            status_interval: DEFAULT_STATUS_INTERVAL,
+            breadth_first: false,
+            stats_only: false,
+            threads: 1,
+            depth_below_match: None,
+            two_pass: false,
+            include_root: false,
+            sum_matched_bytes: false,
+        }
+    }
+}
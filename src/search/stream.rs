@@ -0,0 +1,136 @@
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+
+use super::{SearchEvent, SearchResult, Searcher};
+
+/// Matches from a search running on tokio's blocking pool.
+///
+/// The search waits while `capacity` matches are queued, and stops early if
+/// the stream is dropped. Call [`MatchStream::finish`] for the final result.
+pub struct MatchStream {
+    matches: mpsc::Receiver<PathBuf>,
+    search: Option<JoinHandle<SearchResult>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl MatchStream {
+    /// Wait for the search to end and return its result, stopping it first if
+    /// matches are still coming
+    pub async fn finish(mut self) -> SearchResult {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.matches.close();
+        let search = self.search.take().expect("search already finished");
+        search.await.expect("search thread panicked")
+    }
+}
+
+impl Stream for MatchStream {
+    type Item = PathBuf;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<PathBuf>> {
+        self.get_mut().matches.poll_recv(cx)
+    }
+}
+
+impl Drop for MatchStream {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Searcher {
+    /// Stream matching paths into async code. Must be called within a tokio runtime.
+    pub fn search_stream(&self, full_path: bool) -> MatchStream {
+        let cancelled = self.start_search();
+        let start_time = Instant::now();
+        let (tx, matches) = mpsc::channel(self.channel_capacity.max(1));
+        let searcher = self.clone();
+        let walk_cancelled = Arc::clone(&cancelled);
+        let search = tokio::task::spawn_blocking(move || {
+            let deadline = searcher.timeout.map(|t| start_time + t);
+            searcher.walk(full_path, start_time, &walk_cancelled, &mut |event| {
+                let SearchEvent::Match(entry) = event else {
+                    return ControlFlow::Continue(());
+                };
+                let mut path = entry.into_path();
+                // Poll like `send_until`, so a stream nobody reads can't outlast the timeout
+                loop {
+                    match tx.try_send(path) {
+                        Ok(()) => return ControlFlow::Continue(()),
+                        Err(TrySendError::Closed(_)) => return ControlFlow::Break(()),
+                        Err(TrySendError::Full(returned)) => {
+                            if deadline.is_some_and(|d| Instant::now() >= d)
+                                || walk_cancelled.load(Ordering::Relaxed)
+                            {
+                                return ControlFlow::Break(());
+                            }
+                            path = returned;
+                            std::thread::sleep(Duration::from_millis(1));
+                        }
+                    }
+                }
+            })
+        });
+        MatchStream {
+            matches,
+            search: Some(search),
+            cancelled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchOptions;
+    use crate::search::tests::{endless_searcher, sample_tree, searcher, walk_stops};
+
+    async fn next(stream: &mut MatchStream) -> Option<PathBuf> {
+        std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn streams_every_match_then_finishes() {
+        let dir = sample_tree();
+        let mut stream =
+            searcher(dir.path(), "*.txt", SearchOptions::default()).search_stream(false);
+        let mut found = Vec::new();
+        while let Some(path) = next(&mut stream).await {
+            found.push(path);
+        }
+        assert_eq!(found.len(), 4);
+        let result = stream.finish().await;
+        assert_eq!(result.matches, 4);
+        assert!(!result.timed_out);
+    }
+
+    #[tokio::test]
+    async fn dropping_the_stream_stops_the_walk() {
+        let dir = sample_tree();
+        let (searcher, walked) = endless_searcher(dir.path());
+        let mut stream = searcher.search_stream(false);
+        assert!(next(&mut stream).await.is_some());
+        drop(stream);
+        assert!(walk_stops(&walked));
+    }
+
+    #[tokio::test]
+    async fn finish_stops_an_unfinished_search() {
+        let dir = sample_tree();
+        let (searcher, walked) = endless_searcher(dir.path());
+        let mut stream = searcher.search_stream(false);
+        assert!(next(&mut stream).await.is_some());
+        let result = stream.finish().await;
+        assert!(result.matches >= 1);
+        assert!(walk_stops(&walked));
+    }
+}
@@ -0,0 +1,280 @@
+use notify::event::{ModifyKind, RemoveKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+use super::{Entry, Searcher};
+use crate::matchers::EntryMatcher;
+
+/// Quiet period after a path's last event before it's matched, so a burst of
+/// writes from one save is checked once
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Filesystem changes reported after the initial scan
+pub enum WatchEvent {
+    Created(Entry),
+    /// An existing entry that was modified and now matches, possibly for the first time
+    Changed(Entry),
+    /// Only the path is left to report, as the entry is gone
+    Removed(PathBuf),
+}
+
+/// What happened to a path while it waits out the debounce period
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    Created,
+    Changed,
+}
+
+/// Filters applied to paths reported by the watcher
+struct WatchFilter {
+    matcher: Arc<dyn EntryMatcher>,
+    base_path: Arc<PathBuf>,
+    max_depth: Option<usize>,
+    files_only: bool,
+    dirs_only: bool,
+    full_path: bool,
+}
+
+impl WatchFilter {
+    /// Levels below the base path, if `path` is inside it
+    fn depth(&self, path: &Path) -> Option<usize> {
+        path.strip_prefix(self.base_path.as_path())
+            .ok()
+            .map(|rel| rel.components().count())
+    }
+
+    fn within_depth(&self, path: &Path) -> bool {
+        match self.max_depth {
+            Some(max) => self.depth(path).is_some_and(|depth| depth <= max),
+            None => true,
+        }
+    }
+
+    /// The entry for a path that exists on disk, if it matches
+    fn matching_entry(&self, path: &Path) -> Option<Entry> {
+        if !self.within_depth(path) {
+            return None;
+        }
+        // Walking the path itself gives us a DirEntry without following symlinks
+        match WalkDir::new(path).max_depth(0).into_iter().next() {
+            Some(Ok(entry))
+                if crate::matchers::should_process_entry(
+                    &entry,
+                    self.files_only,
+                    self.dirs_only,
+                ) && self.matcher.is_match(&entry, self.full_path) =>
+            {
+                Some(Entry::at_depth(entry, self.depth(path).unwrap_or(0)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check a path that has been removed, using the event's kind hint for type filters
+    fn accepts_removed(&self, path: &Path, kind: Option<RemoveKind>) -> bool {
+        if !self.within_depth(path) {
+            return false;
+        }
+        match kind {
+            Some(RemoveKind::File) if self.dirs_only => return false,
+            Some(RemoveKind::Folder) if self.files_only => return false,
+            _ => {}
+        }
+        self.matcher.is_path_match(path, self.full_path)
+    }
+}
+
+/// Turns raw watcher events into `WatchEvent`s, holding created and modified
+/// paths until they've been quiet for `DEBOUNCE`
+struct Debouncer {
+    filter: WatchFilter,
+    event_tx: Sender<WatchEvent>,
+    report_deletes: bool,
+    pending: HashMap<PathBuf, (Pending, Instant)>,
+}
+
+impl Debouncer {
+    /// Record a raw event, sending removals straight away
+    fn handle(&mut self, event: Event) {
+        match event.kind {
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                for path in event.paths {
+                    self.touch(path, Pending::Created);
+                }
+            }
+            // Renames within the watched tree report both the old and new path
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                let mut paths = event.paths.into_iter();
+                if let Some(from) = paths.next() {
+                    self.remove(from, None);
+                }
+                if let Some(to) = paths.next() {
+                    self.touch(to, Pending::Created);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in event.paths {
+                    self.remove(path, None);
+                }
+            }
+            EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Metadata(_) | ModifyKind::Any) => {
+                for path in event.paths {
+                    self.touch(path, Pending::Changed);
+                }
+            }
+            EventKind::Remove(kind) => {
+                for path in event.paths {
+                    self.remove(path, Some(kind));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Restart a path's quiet period, remembering if it was created rather than changed
+    fn touch(&mut self, path: PathBuf, change: Pending) {
+        let entry = self.pending.entry(path).or_insert((change, Instant::now()));
+        if change == Pending::Created {
+            entry.0 = Pending::Created;
+        }
+        entry.1 = Instant::now();
+    }
+
+    fn remove(&mut self, path: PathBuf, kind: Option<RemoveKind>) {
+        self.pending.remove(&path);
+        if self.report_deletes && self.filter.accepts_removed(&path, kind) {
+            let _ = self.event_tx.send(WatchEvent::Removed(path));
+        }
+    }
+
+    /// Match every path quiet since `before`, sending those that match
+    fn flush(&mut self, before: Instant) {
+        let due: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, last))| *last <= before)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in due {
+            let Some((change, _)) = self.pending.remove(&path) else {
+                continue;
+            };
+            if let Some(entry) = self.filter.matching_entry(&path) {
+                let event = match change {
+                    Pending::Created => WatchEvent::Created(entry),
+                    Pending::Changed => WatchEvent::Changed(entry),
+                };
+                let _ = self.event_tx.send(event);
+            }
+        }
+    }
+
+    /// Process raw events until the watcher is dropped
+    fn run(mut self, raw_rx: Receiver<Event>) {
+        loop {
+            match raw_rx.recv_timeout(DEBOUNCE / 2) {
+                Ok(event) => self.handle(event),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            if let Some(before) = Instant::now().checked_sub(DEBOUNCE) {
+                self.flush(before);
+            }
+        }
+    }
+}
+
+impl Searcher {
+    /// Watch the base path for changes, sending matching paths as they appear
+    /// or are modified into matching.
+    ///
+    /// The returned watcher must be kept alive for events to keep arriving.
+    pub fn watch(
+        &self,
+        full_path: bool,
+        report_deletes: bool,
+    ) -> Result<(Receiver<WatchEvent>, RecommendedWatcher), String> {
+        let (event_tx, event_rx) = mpsc::channel::<WatchEvent>();
+
+        let filter = WatchFilter {
+            matcher: Arc::clone(&self.matcher),
+            base_path: Arc::clone(&self.base_path),
+            max_depth: self.max_depth,
+            files_only: self.files_only,
+            dirs_only: self.dirs_only,
+            full_path,
+        };
+
+        let (raw_tx, raw_rx) = mpsc::channel::<Event>();
+        let debouncer = Debouncer {
+            filter,
+            event_tx,
+            report_deletes,
+            pending: HashMap::new(),
+        };
+        thread::spawn(move || debouncer.run(raw_rx));
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                let _ = raw_tx.send(event);
+            }
+        })
+        .map_err(|e| format!("Failed to start watcher: {e}"))?;
+
+        watcher
+            .watch(self.base_path.as_path(), RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", self.base_path.display()))?;
+
+        Ok((event_rx, watcher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::matchers::GlobEntryMatcher;
+    use crate::search::SearchOptions;
+
+    #[test]
+    fn reports_file_created_after_initial_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("before.txt"), "").unwrap();
+        let searcher = Searcher::with_options(
+            Box::new(GlobEntryMatcher::new("*.txt").unwrap()),
+            dir.path().to_path_buf(),
+            SearchOptions::default(),
+        );
+
+        let (initial, _) = searcher.search_collect(false);
+        assert_eq!(initial, vec![dir.path().join("before.txt")]);
+
+        let (events, _watcher) = searcher.watch(false, false).unwrap();
+        let created = dir.path().join("after.txt");
+        fs::write(&created, "").unwrap();
+        fs::write(dir.path().join("ignored.log"), "").unwrap();
+
+        match events.recv_timeout(Duration::from_secs(5)).unwrap() {
+            WatchEvent::Created(entry) => {
+                assert_eq!(entry.path(), created);
+                assert_eq!(entry.depth(), 1);
+                assert!(entry.is_file());
+            }
+            WatchEvent::Changed(entry) => {
+                panic!(
+                    "expected {} to be reported as created",
+                    entry.path().display()
+                )
+            }
+            WatchEvent::Removed(path) => panic!("{} reported as removed", path.display()),
+        }
+        assert!(events.recv_timeout(DEBOUNCE * 3).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How often a blocked wait wakes to check for cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Walk entries buffered between the walking thread and the search
+const WALK_BUFFER: usize = 1024;
+
+/// Run `f` on its own thread, giving up on it if it hasn't finished within `limit`.
+///
+/// A call that never returns, such as a stat on an unresponsive network mount,
+/// is left blocked on its thread rather than holding up the caller.
+pub fn with_timeout<T, F>(limit: Option<Duration>, f: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let Some(limit) = limit else {
+        return Some(f());
+    };
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.recv_timeout(limit).ok()
+}
+
+/// Drive `walker` on its own thread, ending early once `deadline` passes or the
+/// search is cancelled, even if the walk is blocked on IO
+pub(super) fn until_deadline<I>(
+    walker: I,
+    deadline: Instant,
+    cancelled: Arc<AtomicBool>,
+) -> impl Iterator<Item = I::Item>
+where
+    I: Iterator + Send + 'static,
+    I::Item: Send + 'static,
+{
+    let (tx, rx) = mpsc::sync_channel(WALK_BUFFER);
+    std::thread::spawn(move || {
+        for item in walker {
+            if tx.send(item).is_err() {
+                break;
+            }
+        }
+    });
+    std::iter::from_fn(move || {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            match rx.recv_timeout(remaining.min(POLL_INTERVAL)) {
+                Ok(item) => return Some(item),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Yields `0..ready`, then blocks as if stuck on an unresponsive mount
+    fn stalls_after(ready: usize) -> impl Iterator<Item = usize> + Send {
+        (0..).inspect(move |&i| {
+            if i >= ready {
+                std::thread::sleep(Duration::from_secs(3600));
+            }
+        })
+    }
+
+    #[test]
+    fn quick_calls_finish_and_slow_ones_are_abandoned() {
+        assert_eq!(with_timeout(None, || 1), Some(1));
+        assert_eq!(with_timeout(Some(Duration::from_secs(5)), || 2), Some(2));
+        let started = Instant::now();
+        let stuck = with_timeout(Some(Duration::from_millis(50)), || {
+            std::thread::sleep(Duration::from_secs(3600));
+        });
+        assert_eq!(stuck, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn finished_walks_yield_everything() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let items: Vec<_> =
+            until_deadline(0..2000, deadline, Arc::new(AtomicBool::new(false))).collect();
+        assert_eq!(items, (0..2000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn blocked_walks_end_at_the_deadline() {
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(200);
+        let items: Vec<_> =
+            until_deadline(stalls_after(3), deadline, Arc::new(AtomicBool::new(false))).collect();
+        assert_eq!(items, [0, 1, 2]);
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(200), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(5), "{elapsed:?}");
+    }
+
+    #[test]
+    fn cancelling_ends_a_blocked_walk() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let deadline = Instant::now() + Duration::from_secs(3600);
+        let mut walk = until_deadline(stalls_after(1), deadline, Arc::clone(&cancelled));
+        assert_eq!(walk.next(), Some(0));
+        let canceller = {
+            let cancelled = Arc::clone(&cancelled);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                cancelled.store(true, Ordering::Relaxed);
+            })
+        };
+        let started = Instant::now();
+        assert_eq!(walk.next(), None);
+        assert!(started.elapsed() < POLL_INTERVAL * 10);
+        canceller.join().unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::search::StatusMessage;
+
+/// Writes status messages as JSON lines on stderr, with a heartbeat of running
+/// totals at most once per `interval`
+pub struct StatusEvents {
+    interval: Duration,
+    started: Instant,
+    last_heartbeat: Cell<Option<Instant>>,
+    out: RefCell<Box<dyn Write>>,
+}
+
+impl StatusEvents {
+    pub fn new(interval: Duration) -> Self {
+        Self::writing_to(interval, Box::new(std::io::stderr()))
+    }
+
+    /// Write events to `out` instead of stderr
+    pub fn writing_to(interval: Duration, out: Box<dyn Write>) -> Self {
+        StatusEvents {
+            interval,
+            started: Instant::now(),
+            last_heartbeat: Cell::new(None),
+            out: RefCell::new(out),
+        }
+    }
+
+    /// Write one message as a line of JSON
+    pub fn emit(&self, msg: &StatusMessage) {
+        let mut out = self.out.borrow_mut();
+        if serde_json::to_writer(&mut *out, msg).is_ok() {
+            let _ = writeln!(out);
+        }
+    }
+
+    /// Write a heartbeat if a full interval has passed since the last one
+    pub fn heartbeat(&self, scanned: usize, found: usize) {
+        let now = Instant::now();
+        let due = self
+            .last_heartbeat
+            .get()
+            .is_none_or(|last| now.duration_since(last) >= self.interval);
+        if due {
+            self.last_heartbeat.set(Some(now));
+            self.emit(&self.heartbeat_message(scanned, found));
+        }
+    }
+
+    /// Write the final totals followed by `Done`, which is always the last event
+    pub fn finish(&self, scanned: usize, found: usize) {
+        self.emit(&self.heartbeat_message(scanned, found));
+        self.emit(&StatusMessage::Done);
+    }
+
+    fn heartbeat_message(&self, scanned: usize, found: usize) -> StatusMessage {
+        StatusMessage::Heartbeat {
+            scanned,
+            found,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// Collects written bytes where the test can still read them
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Shared {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.borrow().clone())
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    fn recorded(interval: Duration) -> (StatusEvents, Shared) {
+        let out = Shared::default();
+        (
+            StatusEvents::writing_to(interval, Box::new(out.clone())),
+            out,
+        )
+    }
+
+    #[test]
+    fn messages_are_tagged_json_lines() {
+        let (events, out) = recorded(Duration::ZERO);
+        events.emit(&StatusMessage::CurrentPath("src/a b".to_string()));
+        events.emit(&StatusMessage::FileCount(3, 100));
+        events.emit(&StatusMessage::Timeout(5));
+        events.emit(&StatusMessage::Done);
+        assert_eq!(
+            out.lines(),
+            [
+                r#"{"event":"current_path","data":"src/a b"}"#,
+                r#"{"event":"file_count","data":[3,100]}"#,
+                r#"{"event":"timeout","data":5}"#,
+                r#"{"event":"done"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn heartbeats_are_throttled() {
+        let (events, out) = recorded(Duration::from_secs(3600));
+        events.heartbeat(1, 0);
+        events.heartbeat(2, 1);
+        events.heartbeat(3, 1);
+        let lines = out.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(
+            lines[0].starts_with(r#"{"event":"heartbeat","data":{"scanned":1,"found":0,"#),
+            "{lines:?}"
+        );
+
+        let (events, out) = recorded(Duration::ZERO);
+        events.heartbeat(1, 0);
+        events.heartbeat(2, 1);
+        assert_eq!(out.lines().len(), 2);
+    }
+
+    #[test]
+    fn finish_ends_with_totals_then_done() {
+        let (events, out) = recorded(Duration::from_secs(3600));
+        events.heartbeat(1, 0);
+        // The final totals aren't throttled
+        events.finish(10, 4);
+        let lines = out.lines();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains(r#""scanned":10,"found":4"#), "{lines:?}");
+        assert_eq!(lines[2], r#"{"event":"done"}"#);
+    }
+}
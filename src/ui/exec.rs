@@ -0,0 +1,556 @@
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+use super::writer::ResultWriter;
+
+/// Runs a command for each result, substituting path placeholders into its arguments
+pub struct ExecWriter {
+    template: Vec<String>,
+    shell: bool,
+    dry_run: bool,
+    jobs: usize,
+    running: VecDeque<Child>,
+    failed: bool,
+}
+
+impl ExecWriter {
+    /// `template` is the command and its arguments; `{}` placeholders are
+    /// replaced per result, and the path is appended when there are none
+    pub fn new(template: Vec<String>, jobs: usize, shell: bool, dry_run: bool) -> Self {
+        ExecWriter {
+            template,
+            shell,
+            dry_run,
+            jobs: jobs.max(1),
+            running: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    /// Arguments for one result with every placeholder substituted
+    fn arguments(&self, path: &Path) -> Vec<OsString> {
+        let has_placeholder = self.template.iter().any(|arg| has_placeholder(arg));
+        let mut args: Vec<OsString> = self
+            .template
+            .iter()
+            .map(|arg| substitute(arg, path, self.shell))
+            .collect();
+        if !has_placeholder {
+            args.push(quote_if(self.shell, path.as_os_str().to_os_string()));
+        }
+        args
+    }
+
+    /// Wait for the oldest running command, recording whether it failed
+    fn wait_oldest(&mut self) {
+        if let Some(mut child) = self.running.pop_front() {
+            match child.wait() {
+                Ok(status) if status.success() => {}
+                Ok(_) => self.failed = true,
+                Err(e) => {
+                    eprintln!("Error: Failed to wait for command: {e}");
+                    self.failed = true;
+                }
+            }
+        }
+    }
+}
+
+impl ResultWriter for ExecWriter {
+    fn write_result(&mut self, path: &Path, _shown: &str, _marker: Option<&str>) {
+        let args = self.arguments(path);
+        if args.is_empty() {
+            return;
+        }
+
+        if self.dry_run {
+            println!("{}", command_line(&args));
+            return;
+        }
+
+        while self.running.len() >= self.jobs {
+            self.wait_oldest();
+        }
+        match build_command(&args, self.shell).spawn() {
+            Ok(child) => self.running.push_back(child),
+            Err(e) => {
+                eprintln!("Error: Failed to run `{}`: {e}", args[0].to_string_lossy());
+                self.failed = true;
+            }
+        }
+    }
+
+    fn write_note(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        while !self.running.is_empty() {
+            self.wait_oldest();
+        }
+        !self.failed
+    }
+}
+
+/// Default bound on the bytes of arguments passed to one batched command,
+/// kept well under common OS limits to leave room for the environment
+#[cfg(windows)]
+pub const DEFAULT_ARG_LIMIT: usize = 30_000;
+#[cfg(not(windows))]
+pub const DEFAULT_ARG_LIMIT: usize = 128 * 1024;
+
+/// Collects every result and runs a command once over all of them, split into
+/// several invocations when the arguments would exceed `arg_limit` bytes
+pub struct ExecBatchWriter {
+    template: Vec<String>,
+    shell: bool,
+    dry_run: bool,
+    arg_limit: usize,
+    paths: Vec<PathBuf>,
+}
+
+impl ExecBatchWriter {
+    /// Arguments containing placeholders are repeated once per path, in result
+    /// order; the paths are appended when there are no placeholders
+    pub fn new(template: Vec<String>, shell: bool, dry_run: bool, arg_limit: usize) -> Self {
+        ExecBatchWriter {
+            template,
+            shell,
+            dry_run,
+            arg_limit,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Split the collected paths into runs whose arguments fit within the limit
+    fn chunks(&self) -> Vec<&[PathBuf]> {
+        let fixed: usize = self
+            .template
+            .iter()
+            .filter(|arg| !has_placeholder(arg))
+            .map(|arg| arg_cost(arg.len()))
+            .sum();
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut size = fixed;
+        for (i, path) in self.paths.iter().enumerate() {
+            let cost = self.path_cost(path);
+            // A path too long to share an invocation still runs on its own
+            if i > start && size + cost > self.arg_limit {
+                chunks.push(&self.paths[start..i]);
+                start = i;
+                size = fixed;
+            }
+            size += cost;
+        }
+        if start < self.paths.len() {
+            chunks.push(&self.paths[start..]);
+        }
+        chunks
+    }
+
+    /// Bytes one path adds to an invocation across all of its substitutions
+    fn path_cost(&self, path: &Path) -> usize {
+        let substituted: Vec<usize> = self
+            .template
+            .iter()
+            .filter(|arg| has_placeholder(arg))
+            .map(|arg| substitute(arg, path, self.shell).len())
+            .collect();
+        if substituted.is_empty() {
+            arg_cost(quote_if(self.shell, path.as_os_str().to_os_string()).len())
+        } else {
+            substituted.into_iter().map(arg_cost).sum()
+        }
+    }
+
+    /// Arguments for one invocation over `paths`
+    fn arguments(&self, paths: &[PathBuf]) -> Vec<OsString> {
+        let mut args = Vec::new();
+        let mut expanded = false;
+        for arg in &self.template {
+            if has_placeholder(arg) {
+                expanded = true;
+                args.extend(paths.iter().map(|path| substitute(arg, path, self.shell)));
+            } else {
+                args.push(OsString::from(arg));
+            }
+        }
+        if !expanded {
+            args.extend(
+                paths
+                    .iter()
+                    .map(|path| quote_if(self.shell, path.as_os_str().to_os_string())),
+            );
+        }
+        args
+    }
+}
+
+impl ResultWriter for ExecBatchWriter {
+    fn write_result(&mut self, path: &Path, _shown: &str, _marker: Option<&str>) {
+        self.paths.push(path.to_path_buf());
+    }
+
+    fn write_note(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        if self.paths.is_empty() || self.template.is_empty() {
+            return true;
+        }
+
+        let mut failures = Vec::new();
+        let chunks = self.chunks();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let args = self.arguments(chunk);
+            if self.dry_run {
+                println!("{}", command_line(&args));
+                continue;
+            }
+            match build_command(&args, self.shell).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => failures.push(format!("run {} exited with {status}", i + 1)),
+                Err(e) => failures.push(format!(
+                    "run {} failed to start `{}`: {e}",
+                    i + 1,
+                    args[0].to_string_lossy()
+                )),
+            }
+        }
+
+        if !self.dry_run {
+            let runs = chunks.len();
+            let plural = if runs == 1 { "" } else { "s" };
+            if failures.is_empty() {
+                eprintln!(
+                    "Ran {runs} batch command{plural} over {} paths",
+                    self.paths.len()
+                );
+            } else {
+                eprintln!(
+                    "Ran {runs} batch command{plural} over {} paths, {} failed:",
+                    self.paths.len(),
+                    failures.len()
+                );
+                for failure in &failures {
+                    eprintln!("  {failure}");
+                }
+            }
+        }
+        self.paths.clear();
+        failures.is_empty()
+    }
+}
+
+/// Size an argument takes in the OS argument block, counting its terminator and pointer
+fn arg_cost(len: usize) -> usize {
+    len + 1 + std::mem::size_of::<usize>()
+}
+
+/// Build the process for substituted arguments, through the shell when requested
+fn build_command(args: &[OsString], shell: bool) -> Command {
+    if shell {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        command.arg(command_line(args));
+        command
+    } else {
+        let mut command = Command::new(&args[0]);
+        command.args(&args[1..]);
+        command
+    }
+}
+
+/// Arguments joined with spaces, as shown by `--dry-run` and passed to the shell
+fn command_line(args: &[OsString]) -> String {
+    args.iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const PLACEHOLDERS: [&str; 4] = ["{}", "{.}", "{/}", "{//}"];
+
+fn has_placeholder(arg: &str) -> bool {
+    PLACEHOLDERS.iter().any(|p| arg.contains(p))
+}
+
+/// Replace `{}`, `{.}`, `{/}`, and `{//}` in one argument
+fn substitute(arg: &str, path: &Path, shell: bool) -> OsString {
+    if !has_placeholder(arg) {
+        return OsString::from(arg);
+    }
+
+    let mut out = OsString::new();
+    let mut rest = arg;
+    while !rest.is_empty() {
+        // Longest placeholder first so `{//}` isn't read as `{/}`
+        let found = ["{//}", "{/}", "{.}", "{}"]
+            .iter()
+            .filter_map(|p| rest.find(p).map(|i| (i, *p)))
+            .min_by_key(|(i, p)| (*i, std::cmp::Reverse(p.len())));
+        let Some((index, placeholder)) = found else {
+            out.push(rest);
+            break;
+        };
+        out.push(&rest[..index]);
+        let value = match placeholder {
+            "{}" => path.as_os_str().to_os_string(),
+            "{.}" => path.with_extension("").into_os_string(),
+            "{/}" => path.file_name().unwrap_or_default().to_os_string(),
+            _ => match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.as_os_str().to_os_string(),
+                _ => OsString::from("."),
+            },
+        };
+        out.push(quote_if(shell, value));
+        rest = &rest[index + placeholder.len()..];
+    }
+    out
+}
+
+/// Quote a substituted path for the shell so spaces and metacharacters stay literal
+fn quote_if(shell: bool, value: OsString) -> OsString {
+    if !shell {
+        return value;
+    }
+    let value = value.to_string_lossy();
+    if cfg!(windows) {
+        OsString::from(format!("\"{value}\""))
+    } else {
+        OsString::from(format!("'{}'", value.replace('\'', r"'\''")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: Vec<OsString>) -> Vec<String> {
+        args.into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn placeholders_substitute_parts_of_the_path() {
+        let path = Path::new("dir/sub/a b.tar.gz");
+        for (arg, expected) in [
+            ("{}", "dir/sub/a b.tar.gz"),
+            ("{/}", "a b.tar.gz"),
+            ("{//}", "dir/sub"),
+            ("{.}", "dir/sub/a b.tar"),
+            ("--in={//}/{/}", "--in=dir/sub/a b.tar.gz"),
+            ("plain", "plain"),
+        ] {
+            assert_eq!(
+                substitute(arg, path, false),
+                OsString::from(expected),
+                "{arg}"
+            );
+        }
+        assert_eq!(substitute("{//}", Path::new("bare"), false), ".");
+    }
+
+    #[test]
+    fn path_is_appended_without_placeholders() {
+        let writer = ExecWriter::new(vec!["echo".into(), "-n".into()], 1, false, false);
+        assert_eq!(
+            strings(writer.arguments(Path::new("a b"))),
+            ["echo", "-n", "a b"]
+        );
+
+        let writer = ExecWriter::new(
+            vec!["mv".into(), "{}".into(), "{}.bak".into()],
+            1,
+            false,
+            false,
+        );
+        assert_eq!(
+            strings(writer.arguments(Path::new("x"))),
+            ["mv", "x", "x.bak"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_quoting_keeps_metacharacters_literal() {
+        assert_eq!(quote_if(false, "it's".into()), "it's");
+        assert_eq!(quote_if(true, "it's $HOME".into()), r"'it'\''s $HOME'");
+    }
+
+    fn batch(limit: usize, paths: &[&str]) -> ExecBatchWriter {
+        let mut writer = ExecBatchWriter::new(vec!["echo".into()], false, false, limit);
+        for path in paths {
+            writer.write_result(Path::new(path), "", None);
+        }
+        writer
+    }
+
+    fn chunk_names(writer: &ExecBatchWriter) -> Vec<Vec<String>> {
+        writer
+            .chunks()
+            .iter()
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batches_split_at_exactly_the_limit() {
+        // `echo` plus two one-byte paths
+        let limit = arg_cost(4) + 2 * arg_cost(1);
+        assert_eq!(
+            chunk_names(&batch(limit, &["a", "b", "c"])),
+            [vec!["a", "b"], vec!["c"]]
+        );
+        assert_eq!(
+            chunk_names(&batch(limit - 1, &["a", "b", "c"])),
+            [vec!["a"], vec!["b"], vec!["c"]]
+        );
+    }
+
+    #[test]
+    fn oversize_path_runs_on_its_own() {
+        let limit = arg_cost(4) + 2 * arg_cost(1);
+        let long = "x".repeat(100);
+        assert_eq!(
+            chunk_names(&batch(limit, &["a", &long, "b", "c"])),
+            [
+                vec!["a".to_string()],
+                vec![long],
+                vec!["b".into(), "c".into()]
+            ]
+        );
+    }
+
+    #[test]
+    fn batches_keep_result_order() {
+        let names: Vec<String> = (0..50).map(|i| format!("file{i:02}")).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let writer = batch(arg_cost(4) + 7 * arg_cost(6), &names);
+        let chunks = chunk_names(&writer);
+        assert_eq!(chunks.len(), 8);
+        assert_eq!(chunks.concat(), names);
+    }
+
+    #[cfg(unix)]
+    mod run {
+        use super::*;
+        use std::fs;
+
+        /// A command that appends each of its arguments as a line of `out`
+        fn recorder(out: &Path) -> Vec<String> {
+            vec![
+                "sh".into(),
+                "-c".into(),
+                r#"printf '%s\n' "$@" >> "$0""#.into(),
+                out.to_string_lossy().into_owned(),
+            ]
+        }
+
+        fn recorded(out: &Path) -> Vec<String> {
+            fs::read_to_string(out)
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+
+        #[test]
+        fn each_placeholder_reaches_the_command() {
+            let dir = tempfile::tempdir().unwrap();
+            let out = dir.path().join("out");
+            let mut template = recorder(&out);
+            template.extend(["{}", "{/}", "{//}", "{.}"].map(String::from));
+            let mut writer = ExecWriter::new(template, 1, false, false);
+            writer.write_result(Path::new("some dir/a file.txt"), "", None);
+            assert!(writer.finish());
+            assert_eq!(
+                recorded(&out),
+                [
+                    "some dir/a file.txt",
+                    "a file.txt",
+                    "some dir",
+                    "some dir/a file"
+                ]
+            );
+        }
+
+        #[test]
+        fn path_is_the_trailing_argument_without_placeholders() {
+            let dir = tempfile::tempdir().unwrap();
+            let out = dir.path().join("out");
+            let mut writer = ExecWriter::new(recorder(&out), 2, false, false);
+            writer.write_result(Path::new("one two"), "", None);
+            writer.write_result(Path::new("three"), "", None);
+            assert!(writer.finish());
+            let mut lines = recorded(&out);
+            lines.sort();
+            assert_eq!(lines, ["one two", "three"]);
+        }
+
+        #[test]
+        fn shell_commands_get_quoted_paths() {
+            let dir = tempfile::tempdir().unwrap();
+            let out = dir.path().join("out");
+            let template = ["printf", r"'%s\n'", "{/}", ">>"]
+                .map(String::from)
+                .into_iter()
+                .chain([out.to_string_lossy().into_owned()])
+                .collect();
+            let mut writer = ExecWriter::new(template, 1, true, false);
+            writer.write_result(Path::new("d/it's $HOME; x"), "", None);
+            assert!(writer.finish());
+            assert_eq!(recorded(&out), ["it's $HOME; x"]);
+        }
+
+        #[test]
+        fn batch_runs_pass_every_path_in_order() {
+            let dir = tempfile::tempdir().unwrap();
+            let out = dir.path().join("out");
+            let template = recorder(&out);
+            let fixed: usize = template.iter().map(|arg| arg_cost(arg.len())).sum();
+            let mut writer = ExecBatchWriter::new(template, false, false, fixed + 3 * arg_cost(1));
+            let names = ["e", "d", "c", "b", "a"];
+            for name in names {
+                writer.write_result(Path::new(name), "", None);
+            }
+            assert_eq!(writer.chunks().len(), 2);
+            assert!(writer.finish());
+            assert_eq!(recorded(&out), names);
+        }
+
+        #[test]
+        fn failing_command_fails_the_writer() {
+            let mut writer = ExecWriter::new(vec!["true".into()], 1, false, false);
+            writer.write_result(Path::new("a"), "", None);
+            assert!(writer.finish());
+
+            let mut writer = ExecWriter::new(vec!["false".into()], 1, false, false);
+            writer.write_result(Path::new("a"), "", None);
+            writer.write_result(Path::new("b"), "", None);
+            assert!(!writer.finish());
+
+            let mut writer = ExecWriter::new(vec!["/nonexistent/cmd".into()], 1, false, false);
+            writer.write_result(Path::new("a"), "", None);
+            assert!(!writer.finish());
+        }
+    }
+}
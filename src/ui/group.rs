@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::cli::GroupSort;
+use crate::search::EntryKind;
+
+/// Matches bucketed by parent directory
+#[derive(Default)]
+pub struct DirGroups {
+    groups: HashMap<PathBuf, Vec<(PathBuf, EntryKind)>>,
+}
+
+impl DirGroups {
+    /// Add a match under its parent directory
+    pub fn push(&mut self, path: &Path, kind: EntryKind) {
+        let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        self.groups
+            .entry(parent)
+            .or_default()
+            .push((path.to_path_buf(), kind));
+    }
+
+    /// Groups in the requested header order, with members sorted by name
+    pub fn into_sorted(self, sort: GroupSort) -> Vec<(PathBuf, Vec<(PathBuf, EntryKind)>)> {
+        let mut groups: Vec<_> = self.groups.into_iter().collect();
+        for (_, members) in &mut groups {
+            members.sort();
+        }
+        match sort {
+            GroupSort::Name => groups.sort_by(|a, b| a.0.cmp(&b.0)),
+            GroupSort::Count => {
+                groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)))
+            }
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(sort: GroupSort) -> Vec<(String, Vec<String>)> {
+        let mut groups = DirGroups::default();
+        for path in ["b/z.rs", "a/y.rs", "b/x.rs", "c.rs", "b/w.rs", "a/v.rs"] {
+            groups.push(Path::new(path), EntryKind::File);
+        }
+        groups
+            .into_sorted(sort)
+            .into_iter()
+            .map(|(dir, members)| {
+                let names = members
+                    .into_iter()
+                    .map(|(path, _)| path.display().to_string())
+                    .collect();
+                (dir.display().to_string(), names)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_sort_by_name_with_members_in_order() {
+        assert_eq!(
+            groups(GroupSort::Name),
+            [
+                ("".to_string(), vec!["c.rs".to_string()]),
+                (
+                    "a".to_string(),
+                    vec!["a/v.rs".to_string(), "a/y.rs".to_string()]
+                ),
+                (
+                    "b".to_string(),
+                    vec![
+                        "b/w.rs".to_string(),
+                        "b/x.rs".to_string(),
+                        "b/z.rs".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn largest_groups_come_first_by_count() {
+        let order: Vec<String> = groups(GroupSort::Count)
+            .into_iter()
+            .map(|(dir, _)| dir)
+            .collect();
+        assert_eq!(order, ["b", "a", ""]);
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`, which terminals without support ignore
+pub fn wrap(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// A `file://host/path` URL for an absolute path, percent-encoding anything
+/// outside the unreserved set
+pub fn file_url(path: &Path, host: &str) -> String {
+    let path = crate::paths::strip_extended_prefix(path);
+    let mut url = format!("file://{host}");
+    let text = path.to_string_lossy();
+    // Windows drive paths like C:\dir become /C:/dir
+    if !text.starts_with(['/', '\\']) {
+        url.push('/');
+    }
+    for &byte in path_bytes(&path, &text) {
+        match byte {
+            b'\\' if cfg!(windows) => url.push('/'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            b':' if cfg!(windows) => url.push(':'),
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    url
+}
+
+/// The raw bytes of a path on Unix, where names needn't be UTF-8
+#[cfg(unix)]
+fn path_bytes<'a>(path: &'a Path, _text: &'a str) -> &'a [u8] {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes()
+}
+
+/// The UTF-8 bytes of a path elsewhere
+#[cfg(not(unix))]
+fn path_bytes<'a>(_path: &'a Path, text: &'a str) -> &'a [u8] {
+    text.as_bytes()
+}
+
+/// This machine's host name for file URLs, empty if it can't be read
+#[cfg(unix)]
+pub fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    // SAFETY: the buffer is valid for its full length and gethostname NUL-terminates within it
+    let status = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if status != 0 {
+        return String::new();
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// This machine's host name for file URLs, empty if it can't be read
+#[cfg(not(unix))]
+pub fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_text_in_osc_8() {
+        assert_eq!(
+            wrap("a.txt", "file://host/tmp/a.txt"),
+            "\x1b]8;;file://host/tmp/a.txt\x1b\\a.txt\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_urls_name_the_host_and_absolute_path() {
+        assert_eq!(
+            file_url(Path::new("/tmp/a.txt"), "box"),
+            "file://box/tmp/a.txt"
+        );
+        assert_eq!(file_url(Path::new("/tmp/a.txt"), ""), "file:///tmp/a.txt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_urls_percent_encode_everything_unreserved_leaves_out() {
+        assert_eq!(
+            file_url(Path::new("/tmp/my file #1%.txt"), "box"),
+            "file://box/tmp/my%20file%20%231%25.txt"
+        );
+        assert_eq!(
+            file_url(Path::new("/tmp/über/日.rs"), "box"),
+            "file://box/tmp/%C3%BCber/%E6%97%A5.rs"
+        );
+        assert_eq!(file_url(Path::new("/a-b_c.d~"), ""), "file:///a-b_c.d~");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_urls_keep_non_utf8_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(OsStr::from_bytes(b"/tmp/\xff.txt"));
+        assert_eq!(file_url(path, ""), "file:///tmp/%FF.txt");
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use crate::cli::Preset;
+
+/// Shown for directories
+pub const DIR_ICON: char = '\u{f07b}';
+/// Shown for symlinks
+pub const SYMLINK_ICON: char = '\u{f0c1}';
+/// Shown for files no other entry covers
+pub const FILE_ICON: char = '\u{f15b}';
+
+/// Nerd Font glyphs for specific extensions, checked before the broader categories
+const EXTENSION_ICONS: &[(&str, char)] = &[
+    ("rs", '\u{e7a8}'),
+    ("py", '\u{e606}'),
+    ("js", '\u{e74e}'),
+    ("jsx", '\u{e7ba}'),
+    ("ts", '\u{e628}'),
+    ("tsx", '\u{e7ba}'),
+    ("go", '\u{e626}'),
+    ("c", '\u{e61e}'),
+    ("h", '\u{e61e}'),
+    ("cpp", '\u{e61d}'),
+    ("hpp", '\u{e61d}'),
+    ("java", '\u{e738}'),
+    ("rb", '\u{e739}'),
+    ("sh", '\u{f489}'),
+    ("html", '\u{e736}'),
+    ("css", '\u{e749}'),
+    ("md", '\u{e609}'),
+    ("json", '\u{e60b}'),
+    ("toml", '\u{e615}'),
+    ("yaml", '\u{e615}'),
+    ("yml", '\u{e615}'),
+    ("lock", '\u{f023}'),
+    ("pdf", '\u{f1c1}'),
+];
+
+/// Glyphs for whole categories of files, using the `--preset` extension lists
+const CATEGORY_ICONS: &[(Preset, char)] = &[
+    (Preset::Images, '\u{f1c5}'),
+    (Preset::Videos, '\u{f03d}'),
+    (Preset::Audio, '\u{f001}'),
+    (Preset::Archives, '\u{f410}'),
+    (Preset::Documents, '\u{f15c}'),
+    (Preset::Code, '\u{f121}'),
+];
+
+/// The glyph for an entry, chosen by type and then by extension
+pub fn icon_for(path: &Path, is_dir: bool, is_symlink: bool) -> char {
+    if is_symlink {
+        return SYMLINK_ICON;
+    }
+    if is_dir {
+        return DIR_ICON;
+    }
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return FILE_ICON;
+    };
+    let specific = EXTENSION_ICONS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+        .map(|&(_, icon)| icon);
+    let category = || {
+        CATEGORY_ICONS
+            .iter()
+            .find(|(preset, _)| {
+                preset
+                    .extensions()
+                    .iter()
+                    .any(|e| e.eq_ignore_ascii_case(ext))
+            })
+            .map(|&(_, icon)| icon)
+    };
+    specific.or_else(category).unwrap_or(FILE_ICON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_icon(name: &str) -> char {
+        icon_for(Path::new(name), false, false)
+    }
+
+    #[test]
+    fn kinds_come_before_extensions() {
+        assert_eq!(icon_for(Path::new("src.rs"), true, false), DIR_ICON);
+        assert_eq!(icon_for(Path::new("main.rs"), false, true), SYMLINK_ICON);
+        assert_eq!(icon_for(Path::new("dir"), true, true), SYMLINK_ICON);
+    }
+
+    #[test]
+    fn specific_extensions_beat_categories() {
+        assert!(Preset::Code.extensions().contains(&"rs"));
+        assert_eq!(file_icon("main.rs"), '\u{e7a8}');
+        assert_eq!(file_icon("MAIN.RS"), '\u{e7a8}');
+        assert_eq!(file_icon("Cargo.lock"), '\u{f023}');
+    }
+
+    #[test]
+    fn categories_cover_preset_extensions() {
+        assert_eq!(file_icon("photo.PNG"), '\u{f1c5}');
+        assert_eq!(file_icon("clip.mp4"), '\u{f03d}');
+        assert_eq!(file_icon("song.mp3"), '\u{f001}');
+        assert_eq!(file_icon("backup.zip"), '\u{f410}');
+    }
+
+    #[test]
+    fn unknown_files_get_the_plain_icon() {
+        assert_eq!(file_icon("notes.xyz"), FILE_ICON);
+        assert_eq!(file_icon("Makefile"), FILE_ICON);
+        assert_eq!(file_icon(".bashrc"), FILE_ICON);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::io::Write;
+use std::path::Path;
+
+use super::writer::{Output, ResultWriter};
+
+/// Writes `ls -l` style rows: permissions, owner, group, size, mtime, then the path
+pub struct LongWriter {
+    human_sizes: bool,
+    names: OwnerNames,
+    out: Output,
+}
+
+impl LongWriter {
+    pub fn new(human_sizes: bool, out: Output) -> Self {
+        LongWriter {
+            human_sizes,
+            names: OwnerNames::default(),
+            out,
+        }
+    }
+
+    /// Format the metadata columns for a path
+    fn columns(&mut self, metadata: &Metadata) -> String {
+        let size = if self.human_sizes {
+            format!("{:>6}", human_size(metadata.len()))
+        } else {
+            format!("{:>10}", metadata.len())
+        };
+        let mtime = metadata
+            .modified()
+            .ok()
+            .map(|t| {
+                // "YYYY-MM-DDTHH:MM:SSZ" trimmed to minutes
+                let stamp = humantime::format_rfc3339_seconds(t).to_string();
+                stamp[..16].replacen('T', " ", 1)
+            })
+            .unwrap_or_else(|| "-".repeat(16));
+        let (owner, group) = self.names.owner_and_group(metadata);
+        format!(
+            "{} {:<8} {:<8} {} {}",
+            permissions(metadata),
+            owner,
+            group,
+            size,
+            mtime
+        )
+    }
+}
+
+impl ResultWriter for LongWriter {
+    fn write_result(&mut self, path: &Path, shown: &str, marker: Option<&str>) {
+        let columns = match fs::symlink_metadata(path) {
+            Ok(metadata) => self.columns(&metadata),
+            Err(_) => "?".repeat(10),
+        };
+        let _ = match marker {
+            Some(marker) => writeln!(self.out, "{columns} {marker} {shown}"),
+            None => writeln!(self.out, "{columns} {shown}"),
+        };
+        let _ = self.out.flush();
+    }
+
+    fn write_note(&mut self, line: &str) {
+        let _ = writeln!(self.out, "{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        self.out.finish()
+    }
+
+    fn failed(&self) -> bool {
+        self.out.failed()
+    }
+}
+
+/// Format a byte count with a binary unit suffix, like `ls -lh`
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "K", "M", "G", "T", "P"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes}{}", UNITS[0])
+    } else if size < 10.0 {
+        format!("{size:.1}{}", UNITS[unit])
+    } else {
+        format!("{size:.0}{}", UNITS[unit])
+    }
+}
+
+/// Permission string such as `drwxr-xr-x`
+#[cfg(unix)]
+fn permissions(metadata: &Metadata) -> String {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_dir() {
+        'd'
+    } else if file_type.is_symlink() {
+        'l'
+    } else if file_type.is_fifo() {
+        'p'
+    } else if file_type.is_socket() {
+        's'
+    } else if file_type.is_char_device() {
+        'c'
+    } else if file_type.is_block_device() {
+        'b'
+    } else {
+        '-'
+    };
+
+    let mode = metadata.permissions().mode();
+    let mut text = String::with_capacity(10);
+    text.push(kind);
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        text.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
+
+/// Permission string approximated from the read-only flag
+#[cfg(not(unix))]
+fn permissions(metadata: &Metadata) -> String {
+    let kind = if metadata.is_dir() { 'd' } else { '-' };
+    let write = if metadata.permissions().readonly() {
+        '-'
+    } else {
+        'w'
+    };
+    format!("{kind}r{write}-r{write}-r{write}-")
+}
+
+/// Cached uid/gid to name lookups, since results often share owners
+#[derive(Default)]
+struct OwnerNames {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl OwnerNames {
+    #[cfg(unix)]
+    fn owner_and_group(&mut self, metadata: &Metadata) -> (String, String) {
+        use std::os::unix::fs::MetadataExt;
+
+        let (uid, gid) = (metadata.uid(), metadata.gid());
+        let owner = self
+            .users
+            .entry(uid)
+            .or_insert_with(|| match uzers::get_user_by_uid(uid) {
+                Some(user) => user.name().to_string_lossy().into_owned(),
+                None => uid.to_string(),
+            })
+            .clone();
+        let group = self
+            .groups
+            .entry(gid)
+            .or_insert_with(|| match uzers::get_group_by_gid(gid) {
+                Some(group) => group.name().to_string_lossy().into_owned(),
+                None => gid.to_string(),
+            })
+            .clone();
+        (owner, group)
+    }
+
+    #[cfg(not(unix))]
+    fn owner_and_group(&mut self, _metadata: &Metadata) -> (String, String) {
+        ("-".to_string(), "-".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_sizes_use_binary_units() {
+        assert_eq!(human_size(0), "0B");
+        assert_eq!(human_size(1023), "1023B");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(10 * 1024), "10K");
+        assert_eq!(human_size(5 * 1024 * 1024 * 1024), "5.0G");
+        assert_eq!(human_size(u64::MAX), "16384P");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permissions_read_like_ls() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o751)).unwrap();
+        std::os::unix::fs::symlink(&file, dir.path().join("link")).unwrap();
+
+        let mode = |path: &Path| permissions(&fs::symlink_metadata(path).unwrap());
+        assert_eq!(mode(&file), "-rw-r-----");
+        assert_eq!(mode(dir.path()), "drwxr-x--x");
+        assert!(mode(&dir.path().join("link")).starts_with('l'));
+    }
+
+    #[test]
+    fn rows_end_with_size_mtime_and_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.bin");
+        fs::write(&file, [0u8; 2048]).unwrap();
+        let out_path = dir.path().join("out.txt");
+
+        for (human_sizes, size) in [(false, "2048"), (true, "2.0K")] {
+            let mut writer = LongWriter::new(human_sizes, Output::create(&out_path).unwrap());
+            writer.write_result(&file, "data.bin", None);
+            writer.write_result(&file, "data.bin", Some("*"));
+            writer.write_result(&dir.path().join("gone"), "gone", None);
+            assert!(writer.finish());
+
+            let text = fs::read_to_string(&out_path).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines.len(), 3);
+            // permissions, owner, group, size, date, time, then the path
+            let columns: Vec<&str> = lines[0].split_whitespace().collect();
+            assert_eq!(columns.len(), 7, "{}", lines[0]);
+            assert!(columns[0].starts_with('-'));
+            assert_eq!(columns[3], size);
+            assert_eq!(columns[4].len(), "2026-01-01".len());
+            assert_eq!(columns[5].len(), "12:00".len());
+            assert_eq!(columns[6], "data.bin");
+            assert!(lines[1].ends_with(" * data.bin"));
+            // A result that's gone since it matched has no columns to show
+            assert_eq!(lines[2], "?????????? gone");
+        }
+    }
+}
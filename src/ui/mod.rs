@@ -1,280 +1,2078 @@
 use colored::*;
-use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{Receiver, TryRecvError};
+use std::rc::Rc;
+use std::sync::Arc;
+#[cfg(feature = "watch")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError};
 use std::time::Duration;
 
-use crate::search::{SearchResult, StatusMessage};
+mod events;
+mod exec;
+mod group;
+mod hyperlink;
+mod icons;
+mod long;
+#[cfg(feature = "picker")]
+mod picker;
+mod recent;
+mod render;
+mod template;
+mod tree;
+mod writer;
+
+pub use events::StatusEvents;
+pub use exec::{DEFAULT_ARG_LIMIT, ExecBatchWriter, ExecWriter};
+pub use group::DirGroups;
+pub use icons::icon_for;
+pub use long::{LongWriter, human_size};
+#[cfg(feature = "picker")]
+pub use picker::{PickOutcome, PickerState, pick};
+pub use recent::RecentMatches;
+pub use render::{MemoryRenderer, ProgressRenderer, Renderer, SilentRenderer};
+pub use template::{Template, TemplateWriter};
+pub use tree::PathTree;
+pub use writer::{
+    DelimitedWriter, JsonLinesWriter, LineWriter, Match, NulWriter, Output, ResultWriter,
+};
+
+use crate::cli::{GroupBy, GroupSort, OutputFormat, ReplaceScope};
+use crate::last_results;
+use crate::matchers::EntryMatcher;
+#[cfg(feature = "watch")]
+use crate::search::WatchEvent;
+use crate::search::{Entry, EntryKind, SearchMetrics, SearchResult, StatusMessage, TreeStats};
+
+/// Output settings for the display manager
+#[derive(Debug, Clone)]
+pub struct DisplayConfig {
+    /// Animate a progress spinner on stderr
+    pub show_progress: bool,
+    /// Show a bar filling toward this many scanned files instead of a spinner
+    pub progress_total: Option<usize>,
+    /// Print paths exactly as walked, keeping any extended-length prefix
+    pub raw_paths: bool,
+    /// Terminate results with NUL instead of newline
+    pub null_terminated: bool,
+    /// How each result is written
+    pub format: OutputFormat,
+    /// Root of the search, used to compute result depth
+    pub base_path: PathBuf,
+    /// Print `ls -l` style metadata columns before each path
+    pub long: bool,
+    /// Show sizes in the long listing with K/M/G suffixes
+    pub human_sizes: bool,
+    /// Relative or absolute result paths
+    pub path_style: PathStyle,
+    /// Summarize match counts by this key after the search
+    pub group_by: Option<GroupBy>,
+    /// Only list this many of the largest groups
+    pub group_top: Option<usize>,
+    /// Buffer matches and print them as a tree once the search finishes
+    pub tree: bool,
+    /// Keep only this many of the most recently modified matches, printed newest first
+    pub recent: Option<usize>,
+    /// Buffer matches and print them under their parent directory, in this header order
+    pub group_dirs: Option<GroupSort>,
+    /// Run this command for each result instead of printing it
+    pub exec: Option<ExecConfig>,
+    /// Substitute text in each displayed path
+    pub replace: Option<Replacement>,
+    /// Follow each matched directory with its number of immediate children
+    pub count_entries: bool,
+    /// Print the lines that matched `--grep` under each result
+    pub grep: Option<GrepLines>,
+    /// Print only this many results, counting the rest
+    pub head: Option<usize>,
+    /// Stop printing once the printed paths would exceed this many bytes
+    pub max_output_bytes: Option<usize>,
+    /// Make each printed path a clickable OSC 8 link to the file
+    pub hyperlinks: bool,
+    /// Remember matches so watch mode reports a modified file only once
+    pub watch: bool,
+    /// Mark directories with a trailing separator
+    pub classify: bool,
+    /// Color for directory results; files are green and symlinks cyan
+    pub dir_color: Color,
+    /// Follow symlink results with ` -> target`
+    pub show_link_targets: bool,
+    /// Put a Nerd Font glyph for the entry's type in front of each result
+    pub icons: bool,
+    /// Stick to ASCII for the spinner and tree connectors
+    pub ascii: bool,
+    /// Put a running index before each result, for `--get`
+    pub numbers: bool,
+    /// Longest current path shown in the spinner, instead of fitting the terminal width
+    pub progress_path_width: Option<usize>,
+    /// Report progress as JSON lines on stderr instead of a spinner, with a
+    /// heartbeat at this interval
+    pub status_json: Option<Duration>,
+}
+
+/// Matching lines shown under each result by `--grep`
+#[derive(Debug, Clone)]
+pub struct GrepLines {
+    /// Expression each line is tested against
+    pub regex: Regex,
+    /// Most lines shown per file
+    pub max_lines: usize,
+}
+
+/// Text substitution applied to displayed paths by `--replace`
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    /// Text to look for
+    pub from: String,
+    /// Text to put in its place
+    pub to: String,
+    /// Replace every occurrence instead of only the first
+    pub all: bool,
+    /// Whether the whole path or only the file name is rewritten
+    pub scope: ReplaceScope,
+}
+
+impl Replacement {
+    /// Apply the substitution to a displayed path
+    pub fn apply(&self, shown: &str) -> String {
+        let (dir, name) = match self.scope {
+            ReplaceScope::Path => ("", shown),
+            ReplaceScope::Name => shown.split_at(
+                shown
+                    .rfind(std::path::is_separator)
+                    .map_or(0, |sep| sep + 1),
+            ),
+        };
+        let name = if self.all {
+            name.replace(&self.from, &self.to)
+        } else {
+            name.replacen(&self.from, &self.to, 1)
+        };
+        format!("{dir}{name}")
+    }
+}
+
+/// Command run per result by `--exec`
+#[derive(Debug, Clone)]
+pub struct ExecConfig {
+    /// Program and arguments, with `{}` style placeholders
+    pub command: Vec<String>,
+    /// Maximum number of commands running at once
+    pub jobs: usize,
+    /// Run the substituted command line through the system shell
+    pub shell: bool,
+    /// Print the commands instead of running them
+    pub dry_run: bool,
+    /// Run the command once over all results, split to stay under this many argument bytes
+    pub batch: Option<usize>,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            show_progress: true,
+            progress_total: None,
+            raw_paths: false,
+            null_terminated: false,
+            format: OutputFormat::Text,
+            base_path: PathBuf::from("."),
+            long: false,
+            human_sizes: false,
+            path_style: PathStyle::AsWalked,
+            group_by: None,
+            group_top: None,
+            tree: false,
+            recent: None,
+            group_dirs: None,
+            exec: None,
+            replace: None,
+            count_entries: false,
+            grep: None,
+            head: None,
+            max_output_bytes: None,
+            hyperlinks: false,
+            watch: false,
+            classify: false,
+            dir_color: Color::Blue,
+            show_link_targets: false,
+            icons: false,
+            ascii: false,
+            numbers: false,
+            progress_path_width: None,
+            status_json: None,
+        }
+    }
+}
+
+impl DisplayConfig {
+    /// Show progress only when requested and stderr is a terminal
+    pub fn detect(no_progress: bool) -> Self {
+        DisplayConfig {
+            show_progress: !no_progress && std::io::stderr().is_terminal(),
+            ascii: !utf8_locale(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Whether the locale promises UTF-8 output, judged by the first of `LC_ALL`,
+/// `LC_CTYPE`, and `LANG` that is set. Windows consoles are assumed capable.
+pub fn utf8_locale() -> bool {
+    cfg!(windows) || utf8_locale_in(|name| std::env::var(name).ok())
+}
+
+/// `utf8_locale` with the environment looked up through `var`
+fn utf8_locale_in(var: impl Fn(&str) -> Option<String>) -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(var)
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_ascii_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+/// How result paths are printed relative to the search root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    /// Prefixed with the search root exactly as given
+    #[default]
+    AsWalked,
+    /// Relative to the search root, which itself prints as `.`
+    Relative,
+    /// Joined onto the canonicalized search root
+    Absolute,
+}
+
+/// Buffered tree matches beyond which a reminder about delayed output is shown
+const TREE_BUFFER_WARNING: usize = 100_000;
+
+/// How often progress is refreshed while no matches arrive
+const PROGRESS_REFRESH: Duration = Duration::from_millis(100);
+
+/// Queued matches printed in one go before status messages are checked again
+const RESULT_BATCH: usize = 256;
+
+/// Progress observed while processing a search's status messages; the outcome
+/// itself is reported on the joined `SearchResult`
+#[derive(Default)]
+struct LoopState {
+    received_initial_message: bool,
+}
 
 /// Display manager for search progress and results
 pub struct DisplayManager {
-    spinner: ProgressBar,
+    renderer: Box<dyn Renderer>,
     current_path: String,
     file_count: usize,
     found_count: usize,
     permission_errors: usize,
     max_files: usize,
+    config: DisplayConfig,
+    writer: Box<dyn ResultWriter>,
+    canonical_base: Option<PathBuf>, // resolved once for absolute paths and hyperlinks
+    link_host: Option<String>,       // host name for hyperlinks, when enabled
+    group_counts: HashMap<String, usize>,
+    highlighter: Option<(Arc<dyn EntryMatcher>, bool)>, // matcher and full-path mode
+    tree: Option<PathTree>,
+    recent: Option<RecentMatches>,
+    dir_groups: Option<DirGroups>,
+    written: usize,      // results written so far, for --head
+    output_bytes: usize, // path bytes written so far, for --max-output-bytes
+    truncated: bool,
+    output_file: Option<(PathBuf, Rc<Cell<usize>>)>, // --output file and its line count
+    seen: Option<HashSet<PathBuf>>,                  // matches reported so far, for --watch
+    events: Option<StatusEvents>,                    // --status-json output
+    numbered: Option<Vec<PathBuf>>,                  // absolute paths by --numbers index
 }
 
 impl Default for DisplayManager {
     fn default() -> Self {
-        Self::new()
+        Self::new(DisplayConfig::default())
     }
 }
 
 impl DisplayManager {
-    /// Create a new display manager with a spinner
-    pub fn new() -> Self {
-        let spinner = ProgressBar::new_spinner();
-        spinner.set_style(
-            ProgressStyle::default_spinner()
-                .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
-                .template("{spinner:.green} {msg}")
-                .unwrap()
-                .tick_strings(&["⠋ ", "⠙ ", "⠹ ", "⠸ ", "⠼ ", "⠴ ", "⠦ ", "⠧ ", "⠇ ", "⠏ "]),
-        );
-        spinner.enable_steady_tick(Duration::from_millis(80));
-        spinner.set_message("Starting search...");
+    /// Create a new display manager, with a spinner if progress is enabled
+    pub fn new(config: DisplayConfig) -> Self {
+        Self::writing_to(config, Output::stdout())
+    }
+
+    /// Create a display manager that writes results to `out` in the configured format
+    pub fn writing_to(config: DisplayConfig, out: Output) -> Self {
+        let renderer: Box<dyn Renderer> = if config.status_json.is_some() {
+            Box::new(SilentRenderer)
+        } else {
+            Box::new(ProgressRenderer::new(
+                config.show_progress,
+                config.progress_total,
+                config.ascii,
+            ))
+        };
+        let output_file = out
+            .path()
+            .map(|path| (path.to_path_buf(), out.line_count()));
+        let writer = Self::default_writer(&config, out);
+        let mut display = Self::with_output(config, renderer, writer);
+        display.output_file = output_file;
+        display
+    }
+
+    /// Create a display manager that renders progress and results through the given outputs
+    pub fn with_output(
+        config: DisplayConfig,
+        renderer: Box<dyn Renderer>,
+        writer: Box<dyn ResultWriter>,
+    ) -> Self {
+        renderer.status("Starting search...");
+
+        let canonical_base = match config.path_style {
+            _ if config.hyperlinks => config.base_path.canonicalize().ok(),
+            PathStyle::Absolute => config.base_path.canonicalize().ok(),
+            _ => None,
+        };
+        let link_host = config.hyperlinks.then(hyperlink::hostname);
+        let seen = config.watch.then(HashSet::new);
+        let events = config.status_json.map(StatusEvents::new);
+        let numbered = config.numbers.then(Vec::new);
+        let tree = config.tree.then(PathTree::default);
+        let recent = config.recent.map(RecentMatches::new);
+        let dir_groups = config.group_dirs.map(|_| DirGroups::default());
 
         DisplayManager {
-            spinner,
+            tree,
+            recent,
+            dir_groups,
+            renderer,
+            writer,
+            canonical_base,
+            link_host,
+            group_counts: HashMap::new(),
             current_path: String::from("..."),
             file_count: 0,
             found_count: 0,
             permission_errors: 0,
             max_files: 0,
+            config,
+            highlighter: None,
+            written: 0,
+            output_bytes: 0,
+            truncated: false,
+            output_file: None,
+            seen,
+            events,
+            numbered,
+        }
+    }
+
+    /// The writer matching the configured output format
+    fn default_writer(config: &DisplayConfig, out: Output) -> Box<dyn ResultWriter> {
+        match &config.format {
+            _ if let Some(exec) = &config.exec => match exec.batch {
+                Some(arg_limit) => Box::new(ExecBatchWriter::new(
+                    exec.command.clone(),
+                    exec.shell,
+                    exec.dry_run,
+                    arg_limit,
+                )),
+                None => Box::new(ExecWriter::new(
+                    exec.command.clone(),
+                    exec.jobs,
+                    exec.shell,
+                    exec.dry_run,
+                )),
+            },
+            OutputFormat::Csv => {
+                Box::new(DelimitedWriter::new(b',', config.base_path.clone(), out))
+            }
+            OutputFormat::Tsv => {
+                Box::new(DelimitedWriter::new(b'\t', config.base_path.clone(), out))
+            }
+            OutputFormat::JsonLines => {
+                Box::new(JsonLinesWriter::new(config.base_path.clone(), out))
+            }
+            OutputFormat::Text if config.null_terminated => Box::new(NulWriter::new(out)),
+            OutputFormat::Text if config.long => Box::new(LongWriter::new(config.human_sizes, out)),
+            OutputFormat::Text => Box::new(LineWriter::new(out)),
+            OutputFormat::Template(template) => Box::new(TemplateWriter::new(
+                template.clone(),
+                config.base_path.clone(),
+                out,
+            )),
+        }
+    }
+
+    /// Format a result path for display, applying any `--replace` substitution
+    fn display_path(&self, path: &Path) -> String {
+        let shown = self.walked_path(path);
+        match &self.config.replace {
+            Some(replacement) => replacement.apply(&shown),
+            None => shown,
+        }
+    }
+
+    /// Format a result path as walked, relative, or absolute
+    fn walked_path(&self, path: &Path) -> String {
+        if self.config.raw_paths {
+            return path.display().to_string();
+        }
+        let path = crate::paths::strip_extended_prefix(path);
+        let relative = || path.strip_prefix(&self.config.base_path).ok();
+        match (self.config.path_style, relative()) {
+            (PathStyle::Relative, Some(rel)) if rel.as_os_str().is_empty() => ".".to_string(),
+            (PathStyle::Relative, Some(rel)) => rel.display().to_string(),
+            (PathStyle::Absolute, Some(rel)) => match &self.canonical_base {
+                Some(base) if rel.as_os_str().is_empty() => base.display().to_string(),
+                Some(base) => base.join(rel).display().to_string(),
+                None => path.display().to_string(),
+            },
+            _ => path.display().to_string(),
+        }
+    }
+
+    /// Link a displayed result to its file when hyperlinks are enabled
+    fn link(&self, path: &Path, shown: String) -> String {
+        let Some(host) = &self.link_host else {
+            return shown;
+        };
+        let path = crate::paths::strip_extended_prefix(path);
+        let absolute = match (
+            &self.canonical_base,
+            path.strip_prefix(&self.config.base_path),
+        ) {
+            (Some(base), Ok(rel)) => base.join(rel),
+            _ => path.canonicalize().unwrap_or(path),
+        };
+        hyperlink::wrap(&shown, &hyperlink::file_url(&absolute, host))
+    }
+
+    /// Highlight the portion of each result matched by `matcher`, where it can be located
+    pub fn set_highlighter(&mut self, matcher: Arc<dyn EntryMatcher>, full_path: bool) {
+        self.highlighter = Some((matcher, full_path));
+    }
+
+    /// Color a result by its kind, mark directories, link it, and add any icon or symlink target
+    fn decorate(&self, path: &Path, kind: EntryKind, text: &str) -> String {
+        let is_dir = kind == EntryKind::Dir;
+        let is_symlink = kind == EntryKind::Symlink;
+        let color = if is_dir {
+            self.config.dir_color
+        } else if is_symlink {
+            Color::Cyan
+        } else {
+            Color::Green
+        };
+
+        let mut shown = self.colorize(text, color);
+        if is_dir && self.config.classify && !text.ends_with(std::path::is_separator) {
+            let separator = std::path::MAIN_SEPARATOR.to_string();
+            shown.push_str(&separator.color(color).to_string());
+        }
+        let mut shown = self.link(path, shown);
+        if self.config.icons {
+            let icon = icons::icon_for(path, is_dir, is_symlink).to_string();
+            shown = format!("{} {shown}", icon.color(color));
+        }
+        if is_symlink
+            && self.config.show_link_targets
+            && let Ok(target) = fs::read_link(path)
+        {
+            shown.push_str(&format!(" -> {}", target.display()));
+        }
+        shown
+    }
+
+    /// Color a displayed result, highlighting the matched portions when known.
+    ///
+    /// When matching full paths the directories are dimmed so the name stands out.
+    fn colorize(&self, shown: &str, color: Color) -> String {
+        let Some((matcher, full_path)) = &self.highlighter else {
+            return shown.color(color).to_string();
+        };
+        let name_start = if *full_path {
+            shown
+                .trim_end_matches(std::path::is_separator)
+                .rfind(std::path::is_separator)
+                .map_or(0, |sep| sep + 1)
+        } else {
+            0
+        };
+        let paint = |start: usize, end: usize| {
+            let split = name_start.clamp(start, end);
+            let dir = &shown[start..split];
+            let name = &shown[split..end];
+            match (dir.is_empty(), name.is_empty()) {
+                (true, _) => name.color(color).to_string(),
+                (false, true) => dir.dimmed().to_string(),
+                (false, false) => format!("{}{}", dir.dimmed(), name.color(color)),
+            }
+        };
+
+        // In name mode the matched text is the final component of the path
+        let offset = if *full_path {
+            0
+        } else {
+            let name = Path::new(shown)
+                .file_name()
+                .map(|n| n.to_string_lossy())
+                .unwrap_or_default();
+            match shown.strip_suffix(name.as_ref()) {
+                Some(parent) => parent.len(),
+                None => return shown.color(color).to_string(),
+            }
+        };
+
+        let Some(ranges) = matcher.match_ranges(&shown[offset..]) else {
+            return paint(0, shown.len());
+        };
+
+        let mut colored = String::new();
+        let mut pos = 0;
+        for range in ranges {
+            let (start, end) = (offset + range.start, offset + range.end);
+            // Skip anything out of order or that would split a character
+            if start < pos
+                || end > shown.len()
+                || !shown.is_char_boundary(start)
+                || !shown.is_char_boundary(end)
+            {
+                continue;
+            }
+            if start > pos {
+                colored.push_str(&paint(pos, start));
+            }
+            colored.push_str(&shown[start..end].red().bold().to_string());
+            pos = end;
+        }
+        if pos < shown.len() {
+            colored.push_str(&paint(pos, shown.len()));
         }
+        colored
     }
 
     /// Process and display search results in real-time
     pub fn process_results(
         &mut self,
-        result_rx: Receiver<PathBuf>,
+        result_rx: Receiver<Entry>,
         status_rx: Receiver<StatusMessage>,
-    ) -> (usize, usize, usize, bool, bool, u64) {
-        let start_time = std::time::Instant::now();
-        let mut limit_reached = false;
-        let mut timed_out = false;
-        let mut _timeout_seconds = 0;
-        let mut last_update = std::time::Instant::now();
-        let mut received_initial_message = false;
-        let update_interval = Duration::from_millis(100);
+    ) {
+        let mut state = LoopState::default();
+        let mut results_open = true;
 
         loop {
-            let now = std::time::Instant::now();
-            let mut updated = false;
-
-            // Process status messages first to get context
-            match status_rx.try_recv() {
-                Ok(msg) => {
-                    received_initial_message = true;
-                    updated = true;
-                    match msg {
-                        StatusMessage::CurrentPath(path) => {
-                            self.current_path = path;
-                            self.update_spinner_message();
+            if results_open {
+                // Wait for the next match, waking periodically to refresh progress
+                match result_rx.recv_timeout(PROGRESS_REFRESH) {
+                    Ok(entry) => {
+                        self.print_result(&entry);
+                        for entry in result_rx.try_iter().take(RESULT_BATCH) {
+                            self.print_result(&entry);
                         }
-                        StatusMessage::FileCount(count, max) => {
-                            self.file_count = count;
-                            self.max_files = max;
+                        // Nothing more can be shown, so leave the caller to stop the search
+                        if self.output_failed() {
+                            self.renderer.clear();
+                            return;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        if state.received_initial_message {
                             self.update_spinner_message();
+                        } else if self.current_path == "..." {
+                            self.renderer.status("Preparing search...");
                         }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => results_open = false,
+                }
+                if self.drain_status(&status_rx, false, &mut state) {
+                    break;
+                }
+            } else if self.drain_status(&status_rx, true, &mut state) {
+                // Every match is in; block until the search reports completion
+                break;
+            }
+        }
+
+        // Matches still queued when the search finished haven't been printed yet
+        for entry in result_rx.iter() {
+            self.print_result(&entry);
+        }
+        self.print_tree();
+        self.print_recent();
+        self.print_dir_groups();
+        self.print_cutoff();
+        if let Some(events) = &self.events {
+            events.finish(self.file_count, self.found_count);
+        }
+    }
+
+    /// Apply pending status messages, waiting for the first one when `block` is set.
+    ///
+    /// Only the latest path and file count are shown, so a backlog doesn't
+    /// replay stale progress. Returns true once the search has finished or
+    /// its status channel has closed.
+    fn drain_status(
+        &mut self,
+        status_rx: &Receiver<StatusMessage>,
+        block: bool,
+        state: &mut LoopState,
+    ) -> bool {
+        let mut latest_path = None;
+        let mut latest_count = None;
+        let mut progress_changed = false;
+        let mut notice = None;
+        let mut done = false;
+
+        let mut next = if block {
+            status_rx.recv().map_err(|_| TryRecvError::Disconnected)
+        } else {
+            status_rx.try_recv()
+        };
+        loop {
+            match next {
+                Ok(msg) => {
+                    state.received_initial_message = true;
+                    // Done is written by process_results once every result is out
+                    if let Some(events) = &self.events
+                        && !matches!(msg, StatusMessage::Done)
+                    {
+                        events.emit(&msg);
+                    }
+                    match msg {
+                        StatusMessage::CurrentPath(path) => latest_path = Some(path),
+                        StatusMessage::FileCount(count, max) => latest_count = Some((count, max)),
                         StatusMessage::PermissionErrors(count) => {
                             self.permission_errors = count;
-                            self.update_spinner_message();
+                            progress_changed = true;
                         }
                         StatusMessage::Timeout(seconds) => {
-                            timed_out = true;
-                            _timeout_seconds = seconds;
-                            self.spinner.set_message(format!(
-                                "Search timed out after {} seconds! ({} scanned, {} found)",
-                                seconds, self.file_count, self.found_count
-                            ));
+                            notice = Some(StatusMessage::Timeout(seconds));
+                        }
+                        // Already on stderr as events with --status-json
+                        StatusMessage::TimeoutApproaching(_) | StatusMessage::Debug(_)
+                            if self.events.is_some() => {}
+                        StatusMessage::TimeoutApproaching(remaining) => {
+                            self.renderer.suspend(&mut || {
+                                eprintln!(
+                                    "{}",
+                                    format!("Warning: approaching timeout, {remaining}s remaining")
+                                        .yellow()
+                                );
+                            });
                         }
                         StatusMessage::LimitReached(limit) => {
-                            limit_reached = true;
-                            self.spinner.set_message(format!(
-                                "File limit reached ({limit})! Finishing search..."
-                            ));
+                            notice = Some(StatusMessage::LimitReached(limit));
+                        }
+                        StatusMessage::Debug(line) => {
+                            self.renderer.suspend(&mut || {
+                                eprintln!("{}", line.dimmed());
+                            });
                         }
+                        // The final metrics also arrive with the search result
+                        StatusMessage::Metrics(_) | StatusMessage::Heartbeat { .. } => {}
                         StatusMessage::Done => {
+                            done = true;
                             break;
                         }
                     }
                 }
-                Err(TryRecvError::Empty) => {
-                    // No status messages, continue to results
-                }
+                Err(TryRecvError::Empty) => break,
                 Err(TryRecvError::Disconnected) => {
-                    if received_initial_message {
-                        // Status channel closed, exit loop
-                        break;
-                    }
+                    done = true;
+                    break;
                 }
             }
+            next = status_rx.try_recv();
+        }
 
-            // Check for new results to print (non-blocking)
-            let mut result_count = 0;
-            while result_count < 10 {
-                // Process up to 10 results at once
-                match result_rx.try_recv() {
-                    Ok(path) => {
-                        self.found_count += 1;
-                        result_count += 1;
-                        updated = true;
-
-                        // Pause spinner to print result
-                        self.spinner.suspend(|| {
-                            println!("{}", path.display().to_string().green());
-                        });
-
-                        // Update spinner after the first match or periodically
-                        if self.found_count == 1 || self.found_count % 5 == 0 {
-                            self.update_spinner_message();
-                        }
-                    }
-                    Err(TryRecvError::Empty) => {
-                        // No more results right now
-                        break;
-                    }
-                    Err(TryRecvError::Disconnected) => {
-                        // Result channel closed - will be handled below
-                        if received_initial_message && self.found_count > 0 {
-                            break;
-                        }
-                    }
-                }
+        if let Some(path) = latest_path {
+            self.current_path = path;
+            progress_changed = true;
+        }
+        if let Some((count, max)) = latest_count {
+            self.file_count = count;
+            self.max_files = max;
+            self.renderer.position(count as u64);
+            progress_changed = true;
+        }
+        if progress_changed {
+            self.update_spinner_message();
+        }
+        match notice {
+            Some(StatusMessage::Timeout(seconds)) => {
+                self.renderer.status(&format!(
+                    "Search timed out after {} seconds! ({} scanned, {} found)",
+                    seconds, self.file_count, self.found_count
+                ));
+            }
+            Some(StatusMessage::LimitReached(limit)) => {
+                self.renderer.position(limit as u64);
+                self.renderer.status(&format!(
+                    "File limit reached ({limit})! Finishing search..."
+                ));
+            }
+            _ => {}
+        }
+        done
+    }
+
+    /// Count and print a single result
+    fn print_result(&mut self, entry: &Entry) {
+        let (path, kind) = (entry.path(), entry.kind());
+        self.found_count += 1;
+        if let Some(seen) = &mut self.seen {
+            seen.insert(path.to_path_buf());
+        }
+        self.record_group(path);
+
+        if self.tree.is_some() {
+            self.buffer_tree_match(path, kind);
+        } else if let Some(recent) = &mut self.recent {
+            recent.push(path, kind);
+        } else if let Some(groups) = &mut self.dir_groups {
+            groups.push(path, kind);
+        } else {
+            self.write_result(path, kind);
+        }
+
+        // Update spinner after the first match or periodically
+        if self.found_count == 1 || self.found_count.is_multiple_of(5) {
+            self.update_spinner_message();
+        }
+    }
+
+    /// Write a single result, pausing the spinner
+    fn write_result(&mut self, path: &Path, kind: EntryKind) {
+        if self.truncated || self.config.head.is_some_and(|head| self.written >= head) {
+            return;
+        }
+        let displayed = self.display_path(path);
+        if let Some(budget) = self.config.max_output_bytes {
+            // Each path is followed by a one-byte separator
+            let bytes = displayed.len() + 1;
+            if self.output_bytes + bytes > budget {
+                self.truncated = true;
+                return;
+            }
+            self.output_bytes += bytes;
+        }
+        self.written += 1;
+        let mut shown = self.decorate(path, kind, &displayed);
+        if self.config.count_entries && kind == EntryKind::Dir {
+            let count =
+                fs::read_dir(path).map_or("?".to_string(), |entries| entries.count().to_string());
+            shown = format!("{shown} {}", format!("({count} entries)").dimmed());
+        }
+        let lines = match &self.config.grep {
+            Some(grep) => self.grep_lines(path, grep),
+            None => Vec::new(),
+        };
+        let number = self.numbered.as_mut().map(|numbered| {
+            if numbered.len() < last_results::MAX_SAVED {
+                numbered.push(std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()));
+            }
+            format!("{:>3} ", self.written).dimmed().to_string()
+        });
+        self.renderer.suspend(&mut || {
+            self.writer.write_result(path, &shown, number.as_deref());
+            for line in &lines {
+                self.writer.write_note(line);
+            }
+        });
+    }
+
+    /// Say how many results `--head` or `--max-output-bytes` held back
+    fn print_cutoff(&mut self) {
+        let hidden = self.found_count.saturating_sub(self.written);
+        if hidden == 0 {
+            return;
+        }
+        let plural = if hidden == 1 { "match" } else { "matches" };
+        let line = match self.config.max_output_bytes {
+            Some(budget) if self.truncated => {
+                format!("... output truncated at {budget} bytes, {hidden} more {plural} not shown")
+            }
+            _ if self.config.head.is_some() => {
+                format!("... and {hidden} more {plural} (use --head 0 to show all)")
             }
+            _ => return,
+        };
+        self.renderer.suspend(&mut || {
+            self.writer.write_note(&line.dimmed().to_string());
+        });
+    }
 
-            // If we haven't received any updates for a while, show a spinner update
-            if !updated && now.duration_since(last_update) > update_interval {
-                if received_initial_message {
-                    self.update_spinner_message();
-                } else if self.current_path == "..." {
-                    self.spinner.set_message("Preparing search...");
+    /// Lines of a result matching `--grep`, numbered and with the matches highlighted
+    fn grep_lines(&self, path: &Path, grep: &GrepLines) -> Vec<String> {
+        crate::matchers::matching_lines(path, &grep.regex, grep.max_lines)
+            .into_iter()
+            .map(|(number, line)| {
+                let mut highlighted = String::new();
+                let mut pos = 0;
+                for found in grep.regex.find_iter(&line).filter(|m| !m.is_empty()) {
+                    highlighted.push_str(&line[pos..found.start()]);
+                    highlighted.push_str(&found.as_str().red().bold().to_string());
+                    pos = found.end();
                 }
-                last_update = now;
+                highlighted.push_str(&line[pos..]);
+                format!("  {}: {highlighted}", number.to_string().yellow())
+            })
+            .collect()
+    }
+
+    /// Print the most recently modified matches kept for `--recent`
+    fn print_recent(&mut self) {
+        let Some(recent) = self.recent.take() else {
+            return;
+        };
+        for (path, kind) in recent.into_newest_first() {
+            self.write_result(&path, kind);
+        }
+    }
+
+    /// Print matches kept for `--group` under a header per parent directory
+    fn print_dir_groups(&mut self) {
+        let (Some(groups), Some(sort)) = (self.dir_groups.take(), self.config.group_dirs) else {
+            return;
+        };
+        for (i, (dir, members)) in groups.into_sorted(sort).into_iter().enumerate() {
+            let shown_dir = match self.display_path(&dir) {
+                shown if shown.is_empty() => ".".to_string(),
+                shown => shown,
+            };
+            let header = format!(
+                "{}{} ({})",
+                if i > 0 { "\n" } else { "" },
+                shown_dir.blue().bold(),
+                members.len()
+            );
+            self.renderer.suspend(&mut || {
+                self.writer.write_note(&header);
+            });
+            for (path, kind) in members {
+                let name = entry_display_name(&path);
+                let shown = format!("  {}", self.decorate(&path, kind, &name));
+                self.renderer.suspend(&mut || {
+                    self.writer.write_result(&path, &shown, None);
+                });
             }
+        }
+    }
 
-            // Brief sleep to avoid high CPU usage
-            std::thread::sleep(Duration::from_millis(10));
+    /// Hold a match for the tree printed when the search finishes
+    fn buffer_tree_match(&mut self, path: &Path, kind: EntryKind) {
+        let Some(tree) = &mut self.tree else {
+            return;
+        };
+        let stripped = crate::paths::strip_extended_prefix(path);
+        let relative = stripped
+            .strip_prefix(&self.config.base_path)
+            .unwrap_or(&stripped);
+        tree.insert(relative, kind == EntryKind::Dir);
+
+        if tree.len() == TREE_BUFFER_WARNING {
+            self.renderer.suspend(&mut || {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Warning: --tree holds all matches until the search finishes ({TREE_BUFFER_WARNING} so far)"
+                    )
+                    .yellow()
+                );
+            });
         }
+    }
 
-        (
-            self.found_count,
-            self.file_count,
-            self.permission_errors,
-            limit_reached,
-            timed_out,
-            start_time.elapsed().as_secs(),
-        )
+    /// Print the buffered tree of matches, if any
+    fn print_tree(&mut self) {
+        let Some(tree) = self.tree.take() else {
+            return;
+        };
+        if tree.is_empty() {
+            return;
+        }
+        let root = crate::paths::strip_extended_prefix(&self.config.base_path)
+            .display()
+            .to_string();
+        self.renderer.suspend(&mut || {
+            for line in tree.render(&root, self.config.ascii) {
+                self.writer.write_note(&line);
+            }
+        });
+    }
+
+    /// Absolute paths of the numbered results, in order, when `--numbers` is on
+    pub fn numbered(&self) -> &[PathBuf] {
+        self.numbered.as_deref().unwrap_or_default()
+    }
+
+    /// Wait for any work the output still has in flight, returning false if some of it failed
+    pub fn finish_output(&mut self) -> bool {
+        self.writer.finish()
+    }
+
+    /// Whether writing results has failed, as when stdout is a closed pipe or
+    /// the `--output` disk is full
+    pub fn output_failed(&self) -> bool {
+        self.writer.failed()
+    }
+
+    /// Count results without printing them, returning the total
+    pub fn count_results(
+        &mut self,
+        result_rx: Receiver<Entry>,
+        status_rx: Receiver<StatusMessage>,
+    ) -> usize {
+        self.renderer.status("Counting matches...");
+        for _ in result_rx.iter() {
+            self.found_count += 1;
+            if self.found_count.is_multiple_of(1000) {
+                self.renderer
+                    .status(&format!("Counted {} matches...", self.found_count));
+            }
+        }
+        drop(status_rx);
+        self.renderer.clear();
+        self.found_count
     }
 
-    /// Update the spinner message with current stats
+    /// Display watch-phase changes until interrupted, returning the number of new matches
+    #[cfg(feature = "watch")]
+    pub fn process_watch_events(
+        &mut self,
+        event_rx: Receiver<WatchEvent>,
+        interrupted: &AtomicBool,
+    ) -> usize {
+        let mut new_matches = 0;
+        self.renderer
+            .status("Watching for changes... (Ctrl-C to stop)");
+
+        while !interrupted.load(Ordering::SeqCst) {
+            match event_rx.recv_timeout(Duration::from_millis(100)) {
+                // A modified file is only news if it didn't already match
+                Ok(WatchEvent::Changed(entry))
+                    if self
+                        .seen
+                        .as_ref()
+                        .is_some_and(|seen| seen.contains(entry.path())) => {}
+                Ok(WatchEvent::Created(entry) | WatchEvent::Changed(entry)) => {
+                    let path = entry.path();
+                    if let Some(seen) = &mut self.seen {
+                        seen.insert(path.to_path_buf());
+                    }
+                    new_matches += 1;
+                    self.found_count += 1;
+                    self.record_group(path);
+                    let shown = self.decorate(path, entry.kind(), &self.display_path(path));
+                    let marker = "[new]".cyan().to_string();
+                    self.renderer.suspend(&mut || {
+                        self.writer.write_result(path, &shown, Some(&marker));
+                    });
+                    self.renderer.status(&format!(
+                        "Watching for changes... ({} new, Ctrl-C to stop)",
+                        new_matches
+                    ));
+                }
+                Ok(WatchEvent::Removed(path)) => {
+                    if let Some(seen) = &mut self.seen {
+                        seen.remove(&path);
+                    }
+                    let shown = self.display_path(&path);
+                    let line = format!("{} {}", "[deleted]".red(), shown);
+                    self.renderer.suspend(&mut || {
+                        self.writer.write_note(&line);
+                    });
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if self.output_failed() {
+                break;
+            }
+        }
+
+        new_matches
+    }
+
+    /// Update the spinner message with current stats, and send a heartbeat when one is due
     fn update_spinner_message(&self) {
-        // Special case for when we've found something to make it immediately visible
-        if self.found_count > 0 && self.found_count < 5 {
-            self.spinner.set_message(format!(
-                "Found {} match{}! Continuing search...",
+        if let Some(events) = &self.events {
+            events.heartbeat(self.file_count, self.found_count);
+        }
+        let message = |path: &str| {
+            progress_message(
+                path,
                 self.found_count,
-                if self.found_count == 1 { "" } else { "es" }
+                self.file_count,
+                self.permission_errors,
+                self.max_files,
+            )
+        };
+        let mut shown = message(&self.current_path);
+        // Keep the line from wrapping by trimming the path, its most variable part
+        let path_len = self.current_path.chars().count();
+        let limit = self.config.progress_path_width.or_else(|| {
+            let rest = shown.chars().count().saturating_sub(path_len);
+            Some(self.renderer.status_width()?.saturating_sub(rest))
+        });
+        if let Some(limit) = limit
+            && path_len > limit
+        {
+            shown = message(&shorten_path(&self.current_path, limit, self.config.ascii));
+        }
+        self.renderer.status(&shown);
+    }
+
+    /// Complete the search and show final results
+    pub fn finish(&self, result: &SearchResult, base_path: &Path) {
+        let mut summary = summary_message(result, base_path);
+        if self.truncated {
+            summary.push_str(" [output truncated]");
+        }
+        if let Some((path, lines)) = &self.output_file {
+            let plural = if lines.get() == 1 { "line" } else { "lines" };
+            summary.push_str(&format!(
+                "; wrote {} {plural} to {}",
+                lines.get(),
+                path.display()
             ));
-            return;
         }
+        self.renderer.finish(&summary);
 
-        let remaining = if self.max_files > 0 && self.max_files > self.file_count {
-            format!(", {} remaining", self.max_files - self.file_count)
-        } else {
-            String::new()
+        if self.config.group_by.is_some() && !self.group_counts.is_empty() {
+            self.print_groups();
+        }
+    }
+
+    /// Count a match toward its group when grouping is enabled
+    fn record_group(&mut self, path: &Path) {
+        let key = match self.config.group_by {
+            Some(GroupBy::Ext) => path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "(none)".to_string()),
+            Some(GroupBy::Dir) => match path.parent() {
+                Some(parent) => match self.display_path(parent) {
+                    shown if shown.is_empty() => ".".to_string(),
+                    shown => shown,
+                },
+                None => ".".to_string(),
+            },
+            None => return,
         };
+        *self.group_counts.entry(key).or_insert(0) += 1;
+    }
 
-        let permission_msg = if self.permission_errors > 0 {
-            format!(", {} permission errors", self.permission_errors)
-        } else {
-            String::new()
+    /// Print the per-group match counts, largest first
+    fn print_groups(&self) {
+        let heading = match self.config.group_by {
+            Some(GroupBy::Dir) => "Matches by directory:",
+            _ => "Matches by extension:",
         };
+        let (rows, hidden) = self.group_rows();
+        eprintln!("\n{}", heading.bold());
+        for row in rows {
+            eprintln!("  {row}");
+        }
+        if hidden > 0 {
+            eprintln!("  {}", format!("... {hidden} more").dimmed());
+        }
+    }
 
-        let count_msg = if self.file_count > 0 {
-            format!("{} scanned", self.file_count) // Keep as is - needs the space
+    /// Aligned `key  count` rows of the largest groups, and how many groups
+    /// `--top` left out
+    fn group_rows(&self) -> (Vec<String>, usize) {
+        let mut groups: Vec<(&String, &usize)> = self.group_counts.iter().collect();
+        groups.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let top = self.config.group_top.unwrap_or(groups.len());
+        let hidden = groups.len().saturating_sub(top);
+        groups.truncate(top);
+        let width = groups.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+        let rows = groups
+            .into_iter()
+            .map(|(key, count)| format!("{key:<width$}  {count}"))
+            .collect();
+        (rows, hidden)
+    }
+
+    /// Print how many walk errors of each kind left parts of the tree unsearched
+    pub fn print_errors(&self, result: &SearchResult) {
+        let rows = error_rows(result);
+        if rows.is_empty() {
+            return;
+        }
+        eprintln!("\n{}", "Errors by kind:".bold());
+        for row in rows {
+            eprintln!("  {row}");
+        }
+    }
+
+    /// Print the summary of a stats-only search in place of results
+    pub fn print_tree_stats(&self, stats: &TreeStats, result: &SearchResult) {
+        self.renderer.clear();
+        let size = |bytes: u64| format!("{} ({bytes} bytes)", human_size(bytes));
+        println!("Files:        {}", stats.files);
+        println!("Directories:  {}", stats.dirs);
+        println!("Total size:   {}", size(stats.total_bytes));
+        if let Some((path, bytes)) = &stats.largest {
+            println!(
+                "Largest file: {} ({})",
+                self.display_path(path),
+                human_size(*bytes)
+            );
+        }
+        if result.limit_reached || result.timed_out {
+            eprintln!(
+                "{}",
+                "Warning: the walk stopped early, so these totals are incomplete".yellow()
+            );
+        }
+    }
+
+    /// Print throughput metrics collected during the search
+    pub fn print_stats(&self, metrics: &SearchMetrics) {
+        eprintln!("\n{}", "Search statistics:".bold());
+        eprintln!(
+            "  Entries walked:   {} ({:.0}/s)",
+            metrics.entries_walked,
+            metrics.entries_per_sec()
+        );
+        eprintln!(
+            "  Directories:      {} ({:.0}/s)",
+            metrics.dirs_walked,
+            metrics.dirs_per_sec()
+        );
+        if metrics.threads > 1 {
+            eprintln!(
+                "  Threads:          1 ({} requested; the walk is single-threaded)",
+                metrics.threads
+            );
         } else {
-            "searching".to_string()
+            eprintln!("  Threads:          1");
+        }
+        eprintln!("  Peak queued:      {}", metrics.peak_queued);
+        eprintln!("  Matched:          {}", metrics.entries_matched);
+        eprintln!(
+            "  Collection phase: {:.3}s",
+            metrics.collection_time.as_secs_f64()
+        );
+        eprintln!(
+            "  Matching phase:   {:.3}s",
+            metrics.matching_time.as_secs_f64()
+        );
+    }
+}
+
+/// Final component of a path for display, or the whole path when it has none
+fn entry_display_name(path: &Path) -> String {
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.display().to_string(),
+    }
+}
+
+/// Shorten `path` to at most `max` characters, keeping its tail after `…/`
+fn shorten_path(path: &str, max: usize, ascii: bool) -> String {
+    if path.chars().count() <= max {
+        return path.to_string();
+    }
+    let ellipsis = if ascii { "..." } else { "…" };
+    let ellipsis_len = ellipsis.chars().count();
+    if max <= ellipsis_len {
+        return ellipsis.chars().take(max).collect();
+    }
+    let skip = path.chars().count().saturating_sub(max - ellipsis_len);
+    let start = path.char_indices().nth(skip).map_or(path.len(), |(i, _)| i);
+    let tail = &path[start..];
+    // Start at a component boundary when the tail has one
+    match tail.find(std::path::is_separator) {
+        Some(sep) if sep + 1 < tail.len() => format!("{ellipsis}{}", &tail[sep..]),
+        _ => format!("{ellipsis}{tail}"),
+    }
+}
+
+/// Status line shown while the search runs
+fn progress_message(
+    current_path: &str,
+    found: usize,
+    scanned: usize,
+    permission_errors: usize,
+    max_files: usize,
+) -> String {
+    // Special case for when we've found something to make it immediately visible
+    if found > 0 && found < 5 {
+        return format!(
+            "Found {} match{}! Continuing search...",
+            found,
+            if found == 1 { "" } else { "es" }
+        );
+    }
+
+    let remaining = if max_files > 0 && max_files > scanned {
+        format!(", {} remaining", max_files - scanned)
+    } else {
+        String::new()
+    };
+
+    let permission_msg = if permission_errors > 0 {
+        format!(", {permission_errors} permission errors")
+    } else {
+        String::new()
+    };
+
+    let count_msg = if scanned > 0 {
+        format!("{scanned} scanned")
+    } else {
+        "searching".to_string()
+    };
+
+    let found_msg = if found > 0 {
+        format!(", {found} found")
+    } else {
+        String::new()
+    };
+
+    format!("Searching in: {current_path} ({count_msg}{found_msg}{permission_msg}{remaining})")
+}
+
+/// Elapsed time in milliseconds below a second, tenths of a second above
+fn format_elapsed(elapsed: Duration) -> String {
+    if elapsed < Duration::from_millis(1) {
+        "<1ms".to_string()
+    } else if elapsed < Duration::from_secs(1) {
+        format!("{}ms", elapsed.as_millis())
+    } else {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    }
+}
+
+/// Aligned `kind  count` rows of the walk errors, most frequent first
+fn error_rows(result: &SearchResult) -> Vec<String> {
+    let mut kinds: Vec<(&str, usize)> = result
+        .other_errors
+        .iter()
+        .map(|(kind, count)| (kind.as_str(), *count))
+        .collect();
+    if result.permission_errors > 0 {
+        kinds.push(("permission denied", result.permission_errors));
+    }
+    kinds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let width = kinds.iter().map(|(kind, _)| kind.len()).max().unwrap_or(0);
+    kinds
+        .into_iter()
+        .map(|(kind, count)| format!("{kind:<width$}  {count}"))
+        .collect()
+}
+
+/// One-line summary shown when the search finishes
+fn summary_message(result: &SearchResult, base_path: &Path) -> String {
+    let elapsed = format_elapsed(result.elapsed);
+    let mut match_text = if result.matches == 0 {
+        "No matches found".to_string()
+    } else if result.matches == 1 {
+        "Found 1 match".to_string()
+    } else {
+        format!("Found {} matches", result.matches)
+    };
+    let mut notes = Vec::new();
+    if let Some(bytes) = result.matched_bytes {
+        notes.push(human_size(bytes));
+    }
+    if result.matches_sent < result.matches {
+        notes.push(format!("showing {}", result.matches_sent));
+    } else if result.match_limit_reached {
+        notes.push("match limit reached".to_string());
+    }
+    if !notes.is_empty() {
+        match_text.push_str(&format!(" ({})", notes.join(", ")));
+    }
+
+    let rate = match result.files_per_sec() {
+        Some(rate) => format!(", {rate:.0} files/s"),
+        None => String::new(),
+    };
+
+    let mut errors = String::new();
+    if result.permission_errors > 0 {
+        errors.push_str(&format!(", {} permission errors", result.permission_errors));
+    }
+    let other_errors = result.other_error_count();
+    if other_errors > 0 {
+        errors.push_str(&format!(", {other_errors} other errors"));
+    }
+
+    let mut summary = if result.timed_out {
+        format!(
+            "Search timed out after {}! {} in {} (scanned {} files{}{})",
+            elapsed,
+            match_text,
+            base_path.display(),
+            result.entries_examined,
+            rate,
+            errors
+        )
+    } else if result.limit_reached {
+        format!(
+            "Search stopped at file limit! {} in {} ({}{}{})",
+            match_text,
+            base_path.display(),
+            elapsed,
+            rate,
+            errors
+        )
+    } else {
+        format!(
+            "Search complete! {} in {} ({}, {} files{}{})",
+            match_text,
+            base_path.display(),
+            elapsed,
+            result.entries_examined,
+            rate,
+            errors
+        )
+    };
+    if let Some(limit) = result.depth_limit_reached {
+        summary.push_str(&format!(
+            "; depth limit {limit} reached, deeper entries not searched"
+        ));
+    }
+    if result.root_match_skipped {
+        summary.push_str(&format!(
+            "; {} itself also matches but isn't listed (--include-root lists it)",
+            base_path.display()
+        ));
+    }
+    summary
+}
+
+/// Point out that the search stopped at the default file limit rather than one the user chose
+pub fn print_default_limit_hint(result: &SearchResult) {
+    if result.limit_reached {
+        eprintln!(
+            "\n{}",
+            format!(
+                "Hint: Stopped at the default limit of {} files, so some matches may be missing.",
+                crate::cli::DEFAULT_MAX_FILES
+            )
+            .yellow()
+            .bold()
+        );
+        eprintln!(
+            "      {}",
+            "Pass --max-files 0 to search without a limit.".yellow()
+        );
+    }
+}
+
+/// Whether the finished search hit enough permission errors to suggest sudo
+fn wants_permission_hint(result: &SearchResult) -> bool {
+    result.permission_errors > 5
+}
+
+/// Helper function to print sudo suggestion if the finished search needs it
+pub fn print_permission_hint(result: &SearchResult, path: &str, pattern: &str) {
+    if wants_permission_hint(result) {
+        eprintln!(
+            "\n{}",
+            "Hint: Many permission errors encountered. Try running with sudo:".yellow()
+        );
+        eprintln!(
+            "      {}",
+            format!("sudo seek \"{path}\" \"{pattern}\"").yellow()
+        );
+
+        if cfg!(target_os = "macos") {
+            eprintln!("\n{}", "On macOS, some directories may still be restricted due to System Integrity Protection.".yellow());
+            eprintln!("{}", "For searching user data directories, you may need to grant Terminal 'Full Disk Access'".yellow());
+            eprintln!(
+                "{}",
+                "in System Preferences → Privacy & Security → Full Disk Access.".yellow()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn result(matches: usize, entries_examined: usize) -> SearchResult {
+        SearchResult {
+            matches,
+            matches_sent: matches,
+            match_limit_reached: false,
+            entries_examined,
+            permission_errors: 0,
+            other_errors: BTreeMap::new(),
+            elapsed: Duration::ZERO,
+            limit_reached: false,
+            timed_out: false,
+            metrics: None,
+            tree_stats: None,
+            deepest: 0,
+            depth_limit_reached: None,
+            root_match_skipped: false,
+            matched_bytes: None,
+        }
+    }
+
+    /// Keeps written results in memory
+    struct VecWriter(Rc<RefCell<Vec<String>>>);
+
+    impl ResultWriter for VecWriter {
+        fn write_result(&mut self, _path: &Path, shown: &str, _marker: Option<&str>) {
+            self.0.borrow_mut().push(shown.to_string());
+        }
+
+        fn write_note(&mut self, line: &str) {
+            self.0.borrow_mut().push(line.to_string());
+        }
+    }
+
+    fn memory_display(config: DisplayConfig) -> (DisplayManager, Arc<MemoryRenderer>) {
+        let renderer = Arc::new(MemoryRenderer::default());
+        let writer = VecWriter(Rc::default());
+        let display =
+            DisplayManager::with_output(config, Box::new(Arc::clone(&renderer)), Box::new(writer));
+        (display, renderer)
+    }
+
+    /// Lines written for `entries` passed through a whole search, without colors
+    fn written(config: DisplayConfig, entries: Vec<Entry>) -> Vec<String> {
+        colored::control::set_override(false);
+        let lines = Rc::default();
+        let mut display = DisplayManager::with_output(
+            config,
+            Box::new(Arc::new(MemoryRenderer::default())),
+            Box::new(VecWriter(Rc::clone(&lines))),
+        );
+        let (result_tx, result_rx) = mpsc::channel();
+        for entry in entries {
+            result_tx.send(entry).unwrap();
+        }
+        drop(result_tx);
+        let (status_tx, status_rx) = mpsc::channel();
+        status_tx.send(StatusMessage::Done).unwrap();
+        display.process_results(result_rx, status_rx);
+        lines.borrow().clone()
+    }
+
+    /// The `--group-by` rows after `paths` are found as files, and how many groups were left out
+    fn grouped(group_by: GroupBy, top: Option<usize>, paths: &[&str]) -> (Vec<String>, usize) {
+        let config = DisplayConfig {
+            group_by: Some(group_by),
+            group_top: top,
+            ..DisplayConfig::default()
         };
+        let (mut display, _) = memory_display(config);
+        let (result_tx, result_rx) = mpsc::channel();
+        for path in paths {
+            result_tx
+                .send(Entry::new(path, 1, EntryKind::File))
+                .unwrap();
+        }
+        drop(result_tx);
+        let (status_tx, status_rx) = mpsc::channel();
+        status_tx.send(StatusMessage::Done).unwrap();
+        display.process_results(result_rx, status_rx);
+        display.group_rows()
+    }
 
-        let found_msg = if self.found_count > 0 {
-            format!(", {} found", self.found_count)
-        } else {
-            "".to_string()
+    #[test]
+    fn extensions_are_counted_case_insensitively() {
+        let (rows, hidden) = grouped(
+            GroupBy::Ext,
+            None,
+            &[
+                "a.rs", "b.RS", "c.toml", "Makefile", "d.Rs", "e.toml", "f.md",
+            ],
+        );
+        assert_eq!(rows, ["rs      3", "toml    2", "(none)  1", "md      1"]);
+        assert_eq!(hidden, 0);
+    }
+
+    #[test]
+    fn top_keeps_the_largest_groups() {
+        let paths = ["a.rs", "b.rs", "c.md", "d.md", "e.md", "f.txt", "g.toml"];
+        let (rows, hidden) = grouped(GroupBy::Ext, Some(2), &paths);
+        assert_eq!(rows, ["md  3", "rs  2"]);
+        assert_eq!(hidden, 2);
+
+        let (rows, hidden) = grouped(GroupBy::Ext, Some(10), &paths);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(hidden, 0);
+    }
+
+    #[test]
+    fn directories_are_counted_by_parent() {
+        let (rows, hidden) = grouped(
+            GroupBy::Dir,
+            None,
+            &[
+                "src/a.rs",
+                "src/ui/b.rs",
+                "c.rs",
+                "src/d.rs",
+                "src/ui/e.rs",
+                "src/f.rs",
+            ],
+        );
+        assert_eq!(rows, ["src     3", "src/ui  2", ".       1"]);
+        assert_eq!(hidden, 0);
+    }
+
+    #[test]
+    fn directories_are_keyed_as_displayed() {
+        let config = DisplayConfig {
+            group_by: Some(GroupBy::Dir),
+            path_style: PathStyle::Relative,
+            base_path: PathBuf::from("/base"),
+            ..DisplayConfig::default()
+        };
+        let (mut display, _) = memory_display(config);
+        for path in ["/base/a.rs", "/base/sub/b.rs", "/base/sub/c.rs"] {
+            display.record_group(Path::new(path));
+        }
+        assert_eq!(
+            display.group_rows(),
+            (vec!["sub  2".into(), ".    1".into()], 0)
+        );
+    }
+
+    #[test]
+    fn nothing_is_grouped_without_matches() {
+        assert_eq!(grouped(GroupBy::Ext, Some(3), &[]), (vec![], 0));
+    }
+
+    #[test]
+    fn the_first_set_locale_variable_decides_utf8() {
+        for (vars, utf8) in [
+            (&[][..], false),
+            (&[("LANG", "en_US.UTF-8")], true),
+            (&[("LANG", "de_DE.utf8")], true),
+            (&[("LANG", "C")], false),
+            (&[("LANG", "en_US.UTF-8"), ("LC_ALL", "C")], false),
+            (&[("LANG", "C"), ("LC_CTYPE", "C.UTF-8")], true),
+            // Empty values are skipped
+            (&[("LC_ALL", ""), ("LANG", "en_US.UTF-8")], true),
+        ] {
+            let lookup = |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            };
+            assert_eq!(utf8_locale_in(lookup), utf8, "{vars:?}");
+        }
+    }
+
+    #[test]
+    fn shorten_path_keeps_the_tail() {
+        assert_eq!(shorten_path("src/ui/mod.rs", 20, false), "src/ui/mod.rs");
+        assert_eq!(shorten_path("src/ui/mod.rs", 13, false), "src/ui/mod.rs");
+        // Cut at a separator when one falls inside the kept tail
+        assert_eq!(shorten_path("src/ui/mod.rs", 10, false), "…/mod.rs");
+        assert_eq!(shorten_path("src/ui/mod.rs", 10, true), ".../mod.rs");
+        // Otherwise mid-name
+        assert_eq!(shorten_path("averyveryverylongname", 8, false), "…ongname");
+    }
+
+    #[test]
+    fn shorten_path_counts_characters() {
+        let shortened = shorten_path("données/été/résumé.txt", 12, false);
+        assert_eq!(shortened, "…/résumé.txt");
+        assert!(shortened.chars().count() <= 12);
+    }
+
+    #[test]
+    fn shorten_path_with_tiny_limits() {
+        assert_eq!(shorten_path("src/ui/mod.rs", 0, false), "");
+        assert_eq!(shorten_path("src/ui/mod.rs", 1, false), "…");
+        assert_eq!(shorten_path("src/ui/mod.rs", 2, true), "..");
+        assert_eq!(shorten_path("src/ui/mod.rs", 4, true), "...s");
+    }
+
+    #[test]
+    fn progress_announces_first_matches() {
+        assert_eq!(
+            progress_message("src", 1, 10, 0, 0),
+            "Found 1 match! Continuing search..."
+        );
+        assert_eq!(
+            progress_message("src", 3, 10, 0, 0),
+            "Found 3 matches! Continuing search..."
+        );
+    }
+
+    #[test]
+    fn progress_counts_errors_and_remaining_files() {
+        assert_eq!(
+            progress_message("src", 0, 0, 0, 0),
+            "Searching in: src (searching)"
+        );
+        assert_eq!(
+            progress_message("src", 7, 40, 2, 100),
+            "Searching in: src (40 scanned, 7 found, 2 permission errors, 60 remaining)"
+        );
+        // Past the file limit nothing is left to count down
+        assert_eq!(
+            progress_message("src", 0, 100, 0, 100),
+            "Searching in: src (100 scanned)"
+        );
+    }
+
+    #[test]
+    fn elapsed_time_is_shown_at_a_readable_precision() {
+        for (elapsed, shown) in [
+            (Duration::ZERO, "<1ms"),
+            (Duration::from_micros(999), "<1ms"),
+            (Duration::from_millis(1), "1ms"),
+            (Duration::from_millis(999), "999ms"),
+            (Duration::from_millis(1000), "1.0s"),
+            (Duration::from_millis(12_345), "12.3s"),
+        ] {
+            assert_eq!(format_elapsed(elapsed), shown);
+        }
+    }
+
+    #[test]
+    fn summary_without_matches() {
+        assert_eq!(
+            summary_message(&result(0, 12), Path::new("src")),
+            "Search complete! No matches found in src (<1ms, 12 files)"
+        );
+    }
+
+    #[test]
+    fn summary_lists_errors() {
+        let mut result = result(1, 12);
+        result.permission_errors = 3;
+        result.other_errors.insert("filesystem loop".to_string(), 2);
+        assert_eq!(
+            summary_message(&result, Path::new("src")),
+            "Search complete! Found 1 match in src \
+             (<1ms, 12 files, 3 permission errors, 2 other errors)"
+        );
+    }
+
+    #[test]
+    fn summary_notes_a_skipped_root_match() {
+        let mut result = result(2, 3);
+        result.root_match_skipped = true;
+        assert_eq!(
+            summary_message(&result, Path::new("src")),
+            "Search complete! Found 2 matches in src (<1ms, 3 files); \
+             src itself also matches but isn't listed (--include-root lists it)"
+        );
+    }
+
+    #[test]
+    fn permission_hint_follows_the_result_count() {
+        let mut result = result(1, 12);
+        result.permission_errors = 5;
+        assert!(!wants_permission_hint(&result));
+        result.permission_errors = 6;
+        assert!(wants_permission_hint(&result));
+        assert!(summary_message(&result, Path::new("src")).contains("6 permission errors"));
+    }
+
+    #[test]
+    fn summary_after_timeout_and_file_limit() {
+        let mut timed_out = result(2, 50);
+        timed_out.timed_out = true;
+        assert_eq!(
+            summary_message(&timed_out, Path::new("src")),
+            "Search timed out after <1ms! Found 2 matches in src (scanned 50 files)"
+        );
+
+        let mut limited = result(2, 50);
+        limited.limit_reached = true;
+        assert_eq!(
+            summary_message(&limited, Path::new("src")),
+            "Search stopped at file limit! Found 2 matches in src (<1ms)"
+        );
+    }
+
+    #[test]
+    fn summary_notes_matches_not_shown() {
+        let mut counted = result(9, 50);
+        counted.matches_sent = 4;
+        counted.match_limit_reached = true;
+        assert_eq!(
+            summary_message(&counted, Path::new("src")),
+            "Search complete! Found 9 matches (showing 4) in src (<1ms, 50 files)"
+        );
+
+        let mut limited = result(4, 50);
+        limited.match_limit_reached = true;
+        limited.depth_limit_reached = Some(2);
+        assert_eq!(
+            summary_message(&limited, Path::new("src")),
+            "Search complete! Found 4 matches (match limit reached) in src (<1ms, 50 files); \
+             depth limit 2 reached, deeper entries not searched"
+        );
+    }
+
+    #[test]
+    fn errors_are_listed_most_frequent_first() {
+        assert!(error_rows(&result(0, 1)).is_empty());
+
+        let mut result = result(0, 10);
+        result.permission_errors = 2;
+        result.other_errors.insert("filesystem loop".to_string(), 1);
+        result
+            .other_errors
+            .insert("entity not found".to_string(), 3);
+        result.other_errors.insert("other error".to_string(), 2);
+        assert_eq!(
+            error_rows(&result),
+            [
+                "entity not found   3",
+                "other error        2",
+                "permission denied  2",
+                "filesystem loop    1",
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_progress_and_truncated_summary() {
+        let config = DisplayConfig {
+            max_output_bytes: Some(6),
+            ..DisplayConfig::default()
         };
+        let (mut display, renderer) = memory_display(config);
+
+        let (result_tx, result_rx) = mpsc::channel();
+        let (status_tx, status_rx) = mpsc::channel();
+        for path in ["a.txt", "b.txt"] {
+            result_tx
+                .send(Entry::new(path, 1, EntryKind::File))
+                .unwrap();
+        }
+        drop(result_tx);
+        for msg in [
+            StatusMessage::CurrentPath("sub".to_string()),
+            StatusMessage::FileCount(8, 0),
+            StatusMessage::PermissionErrors(1),
+            StatusMessage::Done,
+        ] {
+            status_tx.send(msg).unwrap();
+        }
+        display.process_results(result_rx, status_rx);
+        display.finish(&result(2, 8), Path::new("."));
 
-        self.spinner.set_message(format!(
-            "Searching in: {} ({}{}{}{})",
-            self.current_path,
-            count_msg,
-            found_msg,
-            permission_msg,
-            remaining // Keep formatting
+        assert_eq!(
+            renderer.statuses(),
+            [
+                "Starting search...",
+                "Found 1 match! Continuing search...",
+                "Found 2 matches! Continuing search...",
+            ]
+        );
+        assert_eq!(
+            renderer.summary().as_deref(),
+            Some("Search complete! Found 2 matches in . (<1ms, 8 files) [output truncated]")
+        );
+    }
+
+    #[test]
+    fn a_status_backlog_is_shown_once_at_its_latest() {
+        let (mut display, renderer) = memory_display(DisplayConfig::default());
+        let (status_tx, status_rx) = mpsc::channel();
+        for i in 1..=500 {
+            status_tx
+                .send(StatusMessage::CurrentPath(format!("dir{i}")))
+                .unwrap();
+            status_tx.send(StatusMessage::FileCount(i, 0)).unwrap();
+        }
+        let mut state = LoopState::default();
+        assert!(!display.drain_status(&status_rx, false, &mut state));
+        assert!(state.received_initial_message);
+        assert_eq!(
+            renderer.statuses(),
+            ["Starting search...", "Searching in: dir500 (500 scanned)"]
+        );
+        assert_eq!(renderer.positions(), [500]);
+        assert!(status_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn draining_stops_at_done() {
+        let (mut display, _) = memory_display(DisplayConfig::default());
+        let (status_tx, status_rx) = mpsc::channel();
+        status_tx.send(StatusMessage::Done).unwrap();
+        status_tx.send(StatusMessage::FileCount(1, 0)).unwrap();
+        let mut state = LoopState::default();
+        assert!(display.drain_status(&status_rx, false, &mut state));
+        assert!(matches!(
+            status_rx.try_recv(),
+            Ok(StatusMessage::FileCount(1, 0))
         ));
+
+        // A closed channel also ends the search, and an empty one doesn't
+        assert!(!display.drain_status(&status_rx, false, &mut state));
+        drop(status_tx);
+        assert!(display.drain_status(&status_rx, false, &mut state));
     }
 
-    /// Complete the search and show final results
-    pub fn finish(&self, result: &SearchResult, base_path: &Path) {
-        // Calculate elapsed time (ensure it's never zero to avoid confusion)
-        let elapsed = if result.elapsed.as_secs_f64() < 0.1 {
-            std::time::Duration::from_millis(100) // Minimum display time of 0.1s
-        } else {
-            result.elapsed
+    #[test]
+    fn blocking_drain_waits_for_the_next_status() {
+        let (mut display, renderer) = memory_display(DisplayConfig::default());
+        let (status_tx, status_rx) = mpsc::channel();
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            status_tx.send(StatusMessage::FileCount(7, 0)).unwrap();
+            status_tx
+        });
+        let mut state = LoopState::default();
+        assert!(!display.drain_status(&status_rx, true, &mut state));
+        assert_eq!(renderer.positions(), [7]);
+        drop(sender.join().unwrap());
+    }
+
+    #[test]
+    fn results_finish_when_the_search_drops_its_channels() {
+        // A search thread that dies never sends Done, and must not hang the display
+        let (mut display, _) = memory_display(DisplayConfig::default());
+        let (result_tx, result_rx) = mpsc::channel();
+        let (status_tx, status_rx) = mpsc::channel::<StatusMessage>();
+        result_tx
+            .send(Entry::new("a.txt", 1, EntryKind::File))
+            .unwrap();
+        drop((result_tx, status_tx));
+        display.process_results(result_rx, status_rx);
+        assert_eq!(display.found_count, 1);
+
+        // Nor does one that drops its status channel while results stay open
+        let (mut display, _) = memory_display(DisplayConfig::default());
+        let (result_tx, result_rx) = mpsc::channel::<Entry>();
+        let (status_tx, status_rx) = mpsc::channel::<StatusMessage>();
+        drop(status_tx);
+        let started = std::time::Instant::now();
+        let late = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            drop(result_tx);
+        });
+        display.process_results(result_rx, status_rx);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        late.join().unwrap();
+    }
+
+    #[test]
+    fn results_queued_behind_done_are_still_printed() {
+        colored::control::set_override(false);
+        let (result_tx, result_rx) = mpsc::channel();
+        let (status_tx, status_rx) = mpsc::channel();
+        let lines = Rc::default();
+        let mut display = DisplayManager::with_output(
+            DisplayConfig::default(),
+            Box::new(MemoryRenderer::default()),
+            Box::new(VecWriter(Rc::clone(&lines))),
+        );
+        let sender = std::thread::spawn(move || {
+            status_tx.send(StatusMessage::Done).unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            for name in ["a", "b", "c"] {
+                result_tx
+                    .send(Entry::new(name, 1, EntryKind::File))
+                    .unwrap();
+            }
+        });
+        display.process_results(result_rx, status_rx);
+        sender.join().unwrap();
+        assert_eq!(*lines.borrow(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn progress_bar_follows_the_scanned_count_to_the_limit() {
+        let config = DisplayConfig {
+            progress_total: Some(100),
+            ..DisplayConfig::default()
         };
-        let match_text = if result.matches == 0 {
-            "No matches found".to_string()
-        } else if result.matches == 1 {
-            "Found 1 match".to_string()
-        } else {
-            format!("Found {} matches", result.matches)
-        };
-
-        if result.timed_out {
-            self.spinner.finish_with_message(format!(
-                "Search timed out after {:.1}s! {} in {} (scanned {} files{})",
-                elapsed.as_secs_f64(),
-                match_text,
-                base_path.display(),
-                result.files_scanned,
-                if result.permission_errors > 0 {
-                    format!(", {} permission errors", result.permission_errors)
-                } else {
-                    String::new()
-                }
-            ));
-        } else if result.limit_reached {
-            self.spinner.finish_with_message(format!(
-                "Search stopped at file limit! {} in {} ({:.1}s{})",
-                match_text,
-                base_path.display(),
-                elapsed.as_secs_f64(),
-                if result.permission_errors > 0 {
-                    format!(", {} permission errors", result.permission_errors)
-                } else {
-                    String::new()
-                }
-            ));
-        } else {
-            self.spinner.finish_with_message(format!(
-                "Search complete! {} in {} ({:.1}s, {} files{})",
-                match_text,
-                base_path.display(),
-                elapsed.as_secs_f64(),
-                result.files_scanned,
-                if result.permission_errors > 0 {
-                    format!(", {} permission errors", result.permission_errors)
-                } else {
-                    String::new()
-                }
-            ));
+        let (mut display, renderer) = memory_display(config);
+        let (result_tx, result_rx) = mpsc::channel::<Entry>();
+        drop(result_tx);
+        let (status_tx, status_rx) = mpsc::channel();
+        for msg in [
+            StatusMessage::FileCount(40, 100),
+            StatusMessage::FileCount(95, 100),
+            StatusMessage::LimitReached(100),
+            StatusMessage::Done,
+        ] {
+            status_tx.send(msg).unwrap();
         }
+        display.process_results(result_rx, status_rx);
+
+        // Counts queued together only move the bar once, to the latest
+        assert_eq!(renderer.positions(), [95, 100]);
+        assert_eq!(
+            renderer.statuses().last().map(String::as_str),
+            Some("File limit reached (100)! Finishing search...")
+        );
     }
 
-    /// Get the spinner for advanced operations
-    pub fn spinner(&self) -> &ProgressBar {
-        &self.spinner
+    #[test]
+    fn head_prints_the_first_results_and_counts_the_rest() {
+        let entries = || {
+            (1..=5)
+                .map(|i| Entry::new(format!("{i}.txt"), 1, EntryKind::File))
+                .collect()
+        };
+        let config = |head| DisplayConfig {
+            head: Some(head),
+            ..DisplayConfig::default()
+        };
+        assert_eq!(
+            written(config(2), entries()),
+            [
+                "1.txt",
+                "2.txt",
+                "... and 3 more matches (use --head 0 to show all)"
+            ]
+        );
+        assert_eq!(
+            written(config(4), entries()).last().unwrap(),
+            "... and 1 more match (use --head 0 to show all)"
+        );
+        // Nothing to summarize when every result fit
+        assert_eq!(written(config(5), entries()).len(), 5);
+    }
+
+    #[test]
+    fn results_are_decorated_by_the_walked_kind() {
+        // None of these exist, so their kinds can only come from the entries
+        let entries = || {
+            vec![
+                Entry::new("gone.txt", 1, EntryKind::File),
+                Entry::new("gone", 1, EntryKind::Dir),
+                Entry::new("gone.lnk", 1, EntryKind::Symlink),
+                Entry::new("gone.sock", 1, EntryKind::Other),
+            ]
+        };
+        let plain = written(DisplayConfig::default(), entries());
+        assert_eq!(plain, ["gone.txt", "gone", "gone.lnk", "gone.sock"]);
+
+        let config = DisplayConfig {
+            classify: true,
+            count_entries: true,
+            ..DisplayConfig::default()
+        };
+        let classified = written(config, entries());
+        assert_eq!(
+            classified,
+            [
+                "gone.txt",
+                &format!("gone{} (? entries)", std::path::MAIN_SEPARATOR),
+                "gone.lnk",
+                "gone.sock"
+            ]
+        );
+    }
+
+    #[test]
+    fn recent_prints_only_the_newest_after_the_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut entries = Vec::new();
+        for (name, age) in [("old", 1_000), ("new", 3_000), ("mid", 2_000)] {
+            let path = dir.path().join(name);
+            std::fs::File::create(&path)
+                .unwrap()
+                .set_modified(std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(age))
+                .unwrap();
+            entries.push(Entry::new(path, 1, EntryKind::File));
+        }
+        let config = DisplayConfig {
+            recent: Some(2),
+            path_style: PathStyle::Relative,
+            base_path: dir.path().to_path_buf(),
+            ..DisplayConfig::default()
+        };
+        assert_eq!(written(config, entries), ["new", "mid"]);
+    }
+
+    #[test]
+    fn grep_lines_follow_each_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "todo: one\ndone\nTODO two\ntodo: three\n").unwrap();
+        let config = DisplayConfig {
+            grep: Some(GrepLines {
+                regex: Regex::new("(?i)todo").unwrap(),
+                max_lines: 2,
+            }),
+            path_style: PathStyle::Relative,
+            base_path: dir.path().to_path_buf(),
+            ..DisplayConfig::default()
+        };
+        let lines = written(config, vec![Entry::new(&path, 1, EntryKind::File)]);
+        assert_eq!(lines, ["notes.txt", "  1: todo: one", "  3: TODO two"]);
+    }
+
+    #[test]
+    fn tree_is_printed_once_the_search_finishes() {
+        let base = PathBuf::from("base");
+        let config = DisplayConfig {
+            tree: true,
+            ascii: true,
+            base_path: base.clone(),
+            ..DisplayConfig::default()
+        };
+        let entries = vec![
+            Entry::new(base.join("sub").join("b.rs"), 2, EntryKind::File),
+            Entry::new(base.join("a.rs"), 1, EntryKind::File),
+        ];
+        assert_eq!(
+            written(config, entries),
+            ["base", "|-- a.rs", "`-- sub", "    `-- b.rs"]
+        );
+    }
+
+    #[test]
+    fn groups_list_members_under_their_directory() {
+        let base = PathBuf::from("base");
+        let config = DisplayConfig {
+            group_dirs: Some(GroupSort::Count),
+            path_style: PathStyle::Relative,
+            base_path: base.clone(),
+            ..DisplayConfig::default()
+        };
+        let entries = ["top.rs", "sub/b.rs", "sub/a.rs"]
+            .into_iter()
+            .map(|path| Entry::new(base.join(path), 1, EntryKind::File))
+            .collect();
+        assert_eq!(
+            written(config, entries),
+            ["sub (2)", "  a.rs", "  b.rs", "\n. (1)", "  top.rs"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_show_their_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink("target.txt", &link).unwrap();
+        let config = DisplayConfig {
+            show_link_targets: true,
+            ..DisplayConfig::default()
+        };
+        let lines = written(
+            config.clone(),
+            vec![
+                Entry::new(&link, 1, EntryKind::Symlink),
+                // Only entries walked as symlinks are looked up
+                Entry::new(&link, 1, EntryKind::File),
+            ],
+        );
+        let shown = link.display().to_string();
+        assert_eq!(lines, [format!("{shown} -> target.txt"), shown]);
     }
 }
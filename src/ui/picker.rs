@@ -0,0 +1,297 @@
+use console::{Key, Term};
+use std::collections::BTreeSet;
+use std::io;
+
+/// Most result rows shown at once, however tall the terminal
+const MAX_ROWS: usize = 20;
+
+/// What the picker should do after a key press
+#[derive(Debug, PartialEq, Eq)]
+pub enum PickOutcome {
+    /// Keep reading keys
+    Continue,
+    /// Print these entries and exit
+    Accept(Vec<String>),
+    /// Leave without choosing anything
+    Cancel,
+}
+
+/// Filter text, cursor, and marked entries of the picker, kept apart from the
+/// terminal so any source of keys can drive it
+pub struct PickerState {
+    items: Vec<String>,
+    query: String,
+    visible: Vec<usize>,     // indices of items matching the query, in order
+    cursor: usize,           // position within `visible`
+    chosen: BTreeSet<usize>, // items marked with Tab
+}
+
+impl PickerState {
+    pub fn new(items: Vec<String>) -> Self {
+        let visible = (0..items.len()).collect();
+        PickerState {
+            items,
+            query: String::new(),
+            visible,
+            cursor: 0,
+            chosen: BTreeSet::new(),
+        }
+    }
+
+    /// Apply one key press
+    pub fn handle(&mut self, key: Key) -> PickOutcome {
+        match key {
+            Key::Escape | Key::CtrlC => return PickOutcome::Cancel,
+            Key::Enter => {
+                let picked: Vec<String> = if self.chosen.is_empty() {
+                    self.current().into_iter().map(str::to_string).collect()
+                } else {
+                    self.chosen.iter().map(|&i| self.items[i].clone()).collect()
+                };
+                if !picked.is_empty() {
+                    return PickOutcome::Accept(picked);
+                }
+            }
+            Key::ArrowUp => self.cursor = self.cursor.saturating_sub(1),
+            Key::ArrowDown => self.move_down(),
+            Key::Tab => {
+                if let Some(&item) = self.visible.get(self.cursor)
+                    && !self.chosen.remove(&item)
+                {
+                    self.chosen.insert(item);
+                }
+                self.move_down();
+            }
+            Key::Backspace if self.query.pop().is_some() => self.refilter(),
+            Key::Char(c) if !c.is_control() => {
+                self.query.push(c);
+                self.refilter();
+            }
+            _ => {}
+        }
+        PickOutcome::Continue
+    }
+
+    /// The entry under the cursor
+    pub fn current(&self) -> Option<&str> {
+        self.visible
+            .get(self.cursor)
+            .map(|&i| self.items[i].as_str())
+    }
+
+    /// Entries matching the filter text
+    pub fn visible(&self) -> impl Iterator<Item = &str> {
+        self.visible.iter().map(|&i| self.items[i].as_str())
+    }
+
+    fn move_down(&mut self) {
+        if self.cursor + 1 < self.visible.len() {
+            self.cursor += 1;
+        }
+    }
+
+    fn refilter(&mut self) {
+        self.visible = (0..self.items.len())
+            .filter(|&i| fuzzy_match(&self.query, &self.items[i]))
+            .collect();
+        self.cursor = 0;
+    }
+
+    /// Screen lines for the prompt and a window of entries around the cursor
+    fn render(&self, rows: usize, width: usize) -> Vec<String> {
+        let start = self.cursor.saturating_sub(rows.saturating_sub(1));
+        let mut lines = vec![format!(
+            "> {}  ({}/{}, {} marked)",
+            self.query,
+            self.visible.len(),
+            self.items.len(),
+            self.chosen.len()
+        )];
+        for (pos, &item) in self.visible.iter().enumerate().skip(start).take(rows) {
+            let pointer = if pos == self.cursor { '>' } else { ' ' };
+            let mark = if self.chosen.contains(&item) {
+                '*'
+            } else {
+                ' '
+            };
+            let line = format!("{pointer}{mark} {}", self.items[item]);
+            lines.push(console::truncate_str(&line, width, "…").into_owned());
+        }
+        lines
+    }
+}
+
+/// Whether every character of `query` appears in `candidate` in order, ignoring case
+fn fuzzy_match(query: &str, candidate: &str) -> bool {
+    let mut chars = candidate.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| chars.any(|c| c == q))
+}
+
+/// Clears the picker and shows the cursor again when dropped, even during a panic
+struct ScreenGuard<'a> {
+    term: &'a Term,
+    drawn: usize,
+}
+
+impl Drop for ScreenGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.term.clear_last_lines(self.drawn);
+        let _ = self.term.show_cursor();
+    }
+}
+
+/// Let the user choose among `items` on the terminal, drawing on stderr so
+/// stdout stays free for the picked paths. Returns `None` if cancelled.
+pub fn pick(items: Vec<String>) -> io::Result<Option<Vec<String>>> {
+    let term = Term::stderr();
+    if !term.is_term() {
+        return Err(io::Error::other("--pick needs a terminal on stderr"));
+    }
+    let mut state = PickerState::new(items);
+    let mut guard = ScreenGuard {
+        term: &term,
+        drawn: 0,
+    };
+    term.hide_cursor()?;
+    loop {
+        let (height, width) = term.size();
+        let rows = usize::from(height).saturating_sub(2).clamp(1, MAX_ROWS);
+        let lines = state.render(rows, usize::from(width));
+        term.clear_last_lines(guard.drawn)?;
+        for line in &lines {
+            term.write_line(line)?;
+        }
+        guard.drawn = lines.len();
+
+        match state.handle(term.read_key_raw()?) {
+            PickOutcome::Continue => {}
+            PickOutcome::Accept(picked) => return Ok(Some(picked)),
+            PickOutcome::Cancel => return Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> PickerState {
+        PickerState::new(
+            ["src/main.rs", "src/lib.rs", "README.md", "Cargo.toml"]
+                .map(String::from)
+                .to_vec(),
+        )
+    }
+
+    fn type_text(state: &mut PickerState, text: &str) {
+        for c in text.chars() {
+            assert_eq!(state.handle(Key::Char(c)), PickOutcome::Continue);
+        }
+    }
+
+    #[test]
+    fn fuzzy_match_in_order_ignoring_case() {
+        assert!(fuzzy_match("smr", "src/main.rs"));
+        assert!(fuzzy_match("README", "readme.md"));
+        assert!(fuzzy_match("", "anything"));
+        assert!(!fuzzy_match("msr", "src/main.rs"));
+        assert!(!fuzzy_match("x", "src/main.rs"));
+    }
+
+    #[test]
+    fn typing_filters_and_backspace_widens() {
+        let mut state = state();
+        type_text(&mut state, "rs");
+        assert_eq!(
+            state.visible().collect::<Vec<_>>(),
+            ["src/main.rs", "src/lib.rs"]
+        );
+        state.handle(Key::Backspace);
+        type_text(&mut state, "li");
+        assert_eq!(state.visible().collect::<Vec<_>>(), ["src/lib.rs"]);
+        state.handle(Key::Backspace);
+        assert_eq!(
+            state.visible().collect::<Vec<_>>(),
+            ["src/lib.rs", "Cargo.toml"]
+        );
+    }
+
+    #[test]
+    fn enter_accepts_the_entry_under_the_cursor() {
+        let mut state = state();
+        state.handle(Key::ArrowDown);
+        state.handle(Key::ArrowDown);
+        state.handle(Key::ArrowUp);
+        assert_eq!(state.current(), Some("src/lib.rs"));
+        assert_eq!(
+            state.handle(Key::Enter),
+            PickOutcome::Accept(vec!["src/lib.rs".to_string()])
+        );
+    }
+
+    #[test]
+    fn cursor_stays_within_the_list() {
+        let mut state = state();
+        state.handle(Key::ArrowUp);
+        assert_eq!(state.current(), Some("src/main.rs"));
+        for _ in 0..10 {
+            state.handle(Key::ArrowDown);
+        }
+        assert_eq!(state.current(), Some("Cargo.toml"));
+    }
+
+    #[test]
+    fn tab_marks_entries_for_enter() {
+        let mut state = state();
+        state.handle(Key::Tab);
+        state.handle(Key::ArrowDown);
+        state.handle(Key::Tab);
+        // Marks survive filtering and come back in list order
+        type_text(&mut state, "src");
+        assert_eq!(
+            state.handle(Key::Enter),
+            PickOutcome::Accept(vec!["src/main.rs".to_string(), "README.md".to_string()])
+        );
+    }
+
+    #[test]
+    fn tab_again_unmarks() {
+        let mut state = state();
+        state.handle(Key::Tab);
+        state.handle(Key::ArrowUp);
+        state.handle(Key::Tab);
+        assert_eq!(
+            state.handle(Key::Enter),
+            PickOutcome::Accept(vec!["src/lib.rs".to_string()])
+        );
+    }
+
+    #[test]
+    fn nothing_to_accept_when_the_filter_hides_everything() {
+        let mut state = state();
+        type_text(&mut state, "zzz");
+        assert_eq!(state.current(), None);
+        assert_eq!(state.handle(Key::Enter), PickOutcome::Continue);
+        assert_eq!(state.handle(Key::Escape), PickOutcome::Cancel);
+    }
+
+    #[test]
+    fn render_windows_around_the_cursor() {
+        let mut state = state();
+        state.handle(Key::ArrowDown);
+        state.handle(Key::Tab);
+        state.handle(Key::ArrowDown);
+        assert_eq!(
+            state.render(3, 80),
+            [
+                ">   (4/4, 1 marked)",
+                " * src/lib.rs",
+                "   README.md",
+                ">  Cargo.toml",
+            ]
+        );
+    }
+}
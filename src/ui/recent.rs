@@ -0,0 +1,109 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::search::EntryKind;
+
+/// The `limit` most recently modified matches, kept in a bounded min-heap
+pub struct RecentMatches {
+    limit: usize,
+    heap: BinaryHeap<Reverse<(SystemTime, PathBuf, EntryKind)>>,
+}
+
+impl RecentMatches {
+    pub fn new(limit: usize) -> Self {
+        RecentMatches {
+            limit,
+            heap: BinaryHeap::with_capacity(limit.saturating_add(1).min(4096)),
+        }
+    }
+
+    /// Offer a match, evicting the oldest kept one once the heap is full
+    pub fn push(&mut self, path: &Path, kind: EntryKind) {
+        if self.limit == 0 {
+            return;
+        }
+        // Entries whose mtime can't be read sort as the oldest possible
+        let modified = path
+            .symlink_metadata()
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        if self.heap.len() < self.limit {
+            self.heap
+                .push(Reverse((modified, path.to_path_buf(), kind)));
+        } else if let Some(Reverse((oldest, _, _))) = self.heap.peek()
+            && modified > *oldest
+        {
+            self.heap.pop();
+            self.heap
+                .push(Reverse((modified, path.to_path_buf(), kind)));
+        }
+    }
+
+    /// The kept matches, newest first
+    pub fn into_newest_first(self) -> Vec<(PathBuf, EntryKind)> {
+        // Ascending order of `Reverse` is descending order of mtime
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, path, kind))| (path, kind))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    /// Files in `dir` modified `age` seconds after the epoch, in the order given
+    fn aged(dir: &Path, ages: &[(&str, u64)]) -> Vec<PathBuf> {
+        ages.iter()
+            .map(|(name, age)| {
+                let path = dir.join(name);
+                File::create(&path)
+                    .unwrap()
+                    .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(*age))
+                    .unwrap();
+                path
+            })
+            .collect()
+    }
+
+    /// Names of the `limit` newest of `paths`
+    fn newest(limit: usize, paths: &[PathBuf]) -> Vec<String> {
+        let mut recent = RecentMatches::new(limit);
+        for path in paths {
+            recent.push(path, EntryKind::File);
+        }
+        recent
+            .into_newest_first()
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_matches_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = aged(
+            dir.path(),
+            &[("b", 2_000), ("d", 4_000), ("a", 1_000), ("c", 3_000)],
+        );
+        assert_eq!(newest(2, &paths), ["d", "c"]);
+        assert_eq!(newest(9, &paths), ["d", "c", "b", "a"]);
+        assert!(newest(0, &paths).is_empty());
+    }
+
+    #[test]
+    fn unreadable_matches_sort_as_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = aged(dir.path(), &[("a", 1_000)]);
+        paths.insert(0, dir.path().join("gone"));
+        assert_eq!(newest(1, &paths), ["a"]);
+        assert_eq!(newest(2, &paths), ["a", "gone"]);
+    }
+}
@@ -0,0 +1,208 @@
+use console::Term;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Destination for live progress and the final summary
+pub trait Renderer {
+    /// Replace the live status line
+    fn status(&self, msg: &str);
+
+    /// Report how many entries have been scanned toward a known total
+    fn position(&self, _scanned: u64) {}
+
+    /// Run `print` with the status line out of the way so output doesn't interleave with it
+    fn suspend(&self, print: &mut dyn FnMut());
+
+    /// Columns available for the status message, when they are known
+    fn status_width(&self) -> Option<usize> {
+        None
+    }
+
+    /// Replace the status line with the final summary
+    fn finish(&self, summary: &str);
+
+    /// Remove the status line without a summary
+    fn clear(&self);
+}
+
+/// Renders progress on stderr with an indicatif spinner, or a bar when the total is known
+pub struct ProgressRenderer {
+    bar: ProgressBar,
+    visible: bool,
+    prefix_width: usize, // columns the template uses before the message
+}
+
+impl ProgressRenderer {
+    /// A hidden renderer still prints the summary, just without animation.
+    /// With `ascii` the spinner uses `| / - \\` instead of braille dots.
+    pub fn new(visible: bool, total: Option<usize>, ascii: bool) -> Self {
+        let bar = match (visible, total) {
+            (false, _) => ProgressBar::hidden(),
+            (true, Some(total)) => ProgressBar::new(total as u64),
+            (true, None) => ProgressBar::new_spinner(),
+        };
+        let template = if visible && total.is_some() {
+            "{spinner:.green} [{bar:30.cyan/blue}] {pos}/{len} (ETA {eta}) {msg}"
+        } else {
+            "{spinner:.green} {msg}"
+        };
+        let ticks: &[&str] = if ascii {
+            &["| ", "/ ", "- ", "\\ ", "  "]
+        } else {
+            &["⠋ ", "⠙ ", "⠹ ", "⠸ ", "⠼ ", "⠴ ", "⠦ ", "⠧ ", "⠇ ", "⠏ "]
+        };
+        bar.set_style(
+            ProgressStyle::default_spinner()
+                .template(template)
+                .unwrap()
+                .progress_chars("=> ")
+                .tick_strings(ticks),
+        );
+        bar.enable_steady_tick(Duration::from_millis(80));
+        // The spinner and a space, plus for the bar "[30 cols] pos/len (ETA 10s) "
+        let prefix_width = match total {
+            Some(total) => 2 + 33 + 2 * total.to_string().len() + 12,
+            None => 2,
+        };
+        ProgressRenderer {
+            bar,
+            visible,
+            prefix_width,
+        }
+    }
+}
+
+impl Renderer for ProgressRenderer {
+    fn status(&self, msg: &str) {
+        self.bar.set_message(msg.to_string());
+    }
+
+    fn position(&self, scanned: u64) {
+        self.bar.set_position(scanned);
+    }
+
+    fn suspend(&self, print: &mut dyn FnMut()) {
+        self.bar.suspend(print);
+    }
+
+    fn status_width(&self) -> Option<usize> {
+        let (_, columns) = Term::stderr().size_checked().filter(|_| self.visible)?;
+        Some(usize::from(columns).saturating_sub(self.prefix_width))
+    }
+
+    fn finish(&self, summary: &str) {
+        if self.visible {
+            self.bar.finish_with_message(summary.to_string());
+        } else {
+            eprintln!("{summary}");
+        }
+    }
+
+    fn clear(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Shows nothing at all, for when progress is reported some other way
+pub struct SilentRenderer;
+
+impl Renderer for SilentRenderer {
+    fn status(&self, _msg: &str) {}
+
+    fn suspend(&self, print: &mut dyn FnMut()) {
+        print();
+    }
+
+    fn finish(&self, _summary: &str) {}
+
+    fn clear(&self) {}
+}
+
+/// Records everything rendered, for inspecting display behavior without a terminal
+#[derive(Default)]
+pub struct MemoryRenderer {
+    statuses: Mutex<Vec<String>>,
+    positions: Mutex<Vec<u64>>,
+    summary: Mutex<Option<String>>,
+}
+
+impl MemoryRenderer {
+    /// Every status line shown so far, oldest first
+    pub fn statuses(&self) -> Vec<String> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    /// Every progress bar position reported so far, oldest first
+    pub fn positions(&self) -> Vec<u64> {
+        self.positions.lock().unwrap().clone()
+    }
+
+    /// The final summary, once the display has finished
+    pub fn summary(&self) -> Option<String> {
+        self.summary.lock().unwrap().clone()
+    }
+}
+
+impl Renderer for MemoryRenderer {
+    fn status(&self, msg: &str) {
+        self.statuses.lock().unwrap().push(msg.to_string());
+    }
+
+    fn position(&self, scanned: u64) {
+        self.positions.lock().unwrap().push(scanned);
+    }
+
+    fn suspend(&self, print: &mut dyn FnMut()) {
+        print();
+    }
+
+    fn finish(&self, summary: &str) {
+        *self.summary.lock().unwrap() = Some(summary.to_string());
+    }
+
+    fn clear(&self) {}
+}
+
+/// A shared renderer, so a [`MemoryRenderer`] can still be inspected after
+/// it's handed to a display
+impl<R: Renderer + ?Sized> Renderer for Arc<R> {
+    fn status(&self, msg: &str) {
+        (**self).status(msg);
+    }
+
+    fn position(&self, scanned: u64) {
+        (**self).position(scanned);
+    }
+
+    fn suspend(&self, print: &mut dyn FnMut()) {
+        (**self).suspend(print);
+    }
+
+    fn status_width(&self) -> Option<usize> {
+        (**self).status_width()
+    }
+
+    fn finish(&self, summary: &str) {
+        (**self).finish(summary);
+    }
+
+    fn clear(&self) {
+        (**self).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_leave_less_room_for_the_message() {
+        assert_eq!(ProgressRenderer::new(false, None, false).prefix_width, 2);
+        // Spinner, the bar, "1000/1000", and the ETA
+        assert_eq!(
+            ProgressRenderer::new(false, Some(1000), false).prefix_width,
+            2 + 33 + 8 + 12
+        );
+    }
+}
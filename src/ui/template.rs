@@ -0,0 +1,283 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::writer::{Output, Record, ResultWriter};
+
+/// A value substituted into an output template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Path,
+    Name,
+    Dir,
+    Ext,
+    Size,
+    Mtime,
+    Depth,
+    Type,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "path" => Field::Path,
+            "name" => Field::Name,
+            "dir" => Field::Dir,
+            "ext" => Field::Ext,
+            "size" => Field::Size,
+            "mtime" => Field::Mtime,
+            "depth" => Field::Depth,
+            "type" => Field::Type,
+            _ => return None,
+        })
+    }
+
+    /// Whether rendering this field needs the entry's metadata
+    fn needs_record(self) -> bool {
+        matches!(
+            self,
+            Field::Size | Field::Mtime | Field::Depth | Field::Type
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(Field),
+}
+
+/// A parsed `--format` template such as `{path}\t{size}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Parse a template, rejecting unknown placeholders and unbalanced braces
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('t') => literal.push('\t'),
+                    Some('n') => literal.push('\n'),
+                    Some('0') => literal.push('\0'),
+                    Some('\\') => literal.push('\\'),
+                    Some(other) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("unclosed placeholder `{{{name}`")),
+                        }
+                    }
+                    let field = Field::from_name(&name)
+                        .ok_or_else(|| format!("unknown placeholder `{{{name}}}`"))?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(field));
+                }
+                '}' => return Err("unmatched `}` (use `}}` for a literal brace)".to_string()),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { parts })
+    }
+
+    /// Render the template for one result, where `shown` is its display path
+    pub fn render(&self, path: &Path, shown: &str, base_path: &Path) -> String {
+        let record = self
+            .parts
+            .iter()
+            .any(|part| matches!(part, Part::Field(field) if field.needs_record()))
+            .then(|| Record::read(path, base_path));
+        let shown_path = Path::new(shown);
+
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Field(Field::Path) => out.push_str(shown),
+                Part::Field(Field::Name) => {
+                    if let Some(name) = shown_path.file_name() {
+                        out.push_str(&name.to_string_lossy());
+                    }
+                }
+                Part::Field(Field::Dir) => {
+                    if let Some(dir) = shown_path.parent() {
+                        out.push_str(&dir.to_string_lossy());
+                    }
+                }
+                Part::Field(Field::Ext) => {
+                    if let Some(ext) = shown_path.extension() {
+                        out.push_str(&ext.to_string_lossy());
+                    }
+                }
+                Part::Field(field) => {
+                    if let Some(record) = &record {
+                        out.push_str(&match field {
+                            Field::Size => record.size.map(|s| s.to_string()).unwrap_or_default(),
+                            Field::Mtime => record.mtime.clone().unwrap_or_default(),
+                            Field::Depth => record.depth.to_string(),
+                            _ => record.file_type.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Writes one rendered template line per result
+pub struct TemplateWriter {
+    template: Template,
+    base_path: PathBuf,
+    out: Output,
+}
+
+impl TemplateWriter {
+    pub fn new(template: Template, base_path: PathBuf, out: Output) -> Self {
+        TemplateWriter {
+            template,
+            base_path,
+            out,
+        }
+    }
+}
+
+impl ResultWriter for TemplateWriter {
+    fn write_result(&mut self, path: &Path, shown: &str, _marker: Option<&str>) {
+        let line = self.template.render(path, shown, &self.base_path);
+        let _ = writeln!(self.out, "{line}");
+        let _ = self.out.flush();
+    }
+
+    fn write_note(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        self.out.finish()
+    }
+
+    fn failed(&self) -> bool {
+        self.out.failed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_escapes_and_literal_braces() {
+        let template = Template::parse(r"{{{name}}}\t{ext}\n\\{path}").unwrap();
+        assert_eq!(
+            template.parts,
+            [
+                Part::Literal("{".to_string()),
+                Part::Field(Field::Name),
+                Part::Literal("}\t".to_string()),
+                Part::Field(Field::Ext),
+                Part::Literal("\n\\".to_string()),
+                Part::Field(Field::Path),
+            ]
+        );
+        // Unknown escapes are kept as written
+        let template = Template::parse(r"a\qb").unwrap();
+        assert_eq!(template.parts, [Part::Literal(r"a\qb".to_string())]);
+    }
+
+    #[test]
+    fn rejects_bad_templates() {
+        assert_eq!(
+            Template::parse("{path} {bogus}"),
+            Err("unknown placeholder `{bogus}`".to_string())
+        );
+        assert_eq!(
+            Template::parse("{path"),
+            Err("unclosed placeholder `{path`".to_string())
+        );
+        assert_eq!(
+            Template::parse("path}"),
+            Err("unmatched `}` (use `}}` for a literal brace)".to_string())
+        );
+    }
+
+    #[test]
+    fn renders_path_parts_from_the_shown_path() {
+        let template = Template::parse("{dir}|{name}|{ext}|{path}").unwrap();
+        let rendered = template.render(Path::new("/gone/x"), "src/ui/mod.rs", Path::new("/"));
+        assert_eq!(rendered, "src/ui|mod.rs|rs|src/ui/mod.rs");
+        // Missing parts render as nothing
+        let rendered = template.render(Path::new("/gone/x"), "Makefile", Path::new("/"));
+        assert_eq!(rendered, "|Makefile||Makefile");
+    }
+
+    #[test]
+    fn renders_metadata_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        let file = sub.join("data.bin");
+        std::fs::write(&file, [0u8; 42]).unwrap();
+
+        let template = Template::parse("{type} {size} {depth}").unwrap();
+        assert_eq!(
+            template.render(&file, "sub/data.bin", dir.path()),
+            "file 42 2"
+        );
+        assert!(template.render(&sub, "sub", dir.path()).starts_with("dir "));
+        // A result that's gone since it matched leaves its metadata blank
+        assert_eq!(
+            template.render(&dir.path().join("gone"), "gone", dir.path()),
+            "unknown  1"
+        );
+
+        let mtime = Template::parse("{mtime}")
+            .unwrap()
+            .render(&file, "", dir.path());
+        assert_eq!(mtime.len(), "2026-01-01T00:00:00Z".len());
+        assert!(mtime.ends_with('Z'));
+    }
+
+    #[test]
+    fn writes_a_line_per_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out.txt");
+        let template = Template::parse("<{name}>").unwrap();
+        let mut writer = TemplateWriter::new(
+            template,
+            dir.path().to_path_buf(),
+            Output::create(&file).unwrap(),
+        );
+        writer.write_result(&dir.path().join("a"), "a", None);
+        writer.write_result(&dir.path().join("b/c"), "b/c", Some("*"));
+        assert!(writer.finish());
+        assert!(!writer.failed());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "<a>\n<c>\n");
+    }
+}
@@ -0,0 +1,174 @@
+use colored::*;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Component, Path};
+
+/// Line-drawing pieces for one tree style
+struct Connectors {
+    branch: &'static str,
+    last: &'static str,
+    pipe: &'static str,
+}
+
+const BOX_DRAWING: Connectors = Connectors {
+    branch: "├── ",
+    last: "└── ",
+    pipe: "│   ",
+};
+
+const ASCII: Connectors = Connectors {
+    branch: "|-- ",
+    last: "`-- ",
+    pipe: "|   ",
+};
+
+/// Matched paths arranged as a trie below the search root
+#[derive(Default)]
+pub struct PathTree {
+    root: Node,
+    len: usize,
+}
+
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<OsString, Node>,
+    matched: bool,
+    is_dir: bool,
+}
+
+impl PathTree {
+    /// Add a match, given relative to the search root
+    pub fn insert(&mut self, relative: &Path, is_dir: bool) {
+        let mut node = &mut self.root;
+        for component in relative.components() {
+            if let Component::Normal(name) = component {
+                node.is_dir = true;
+                node = node.children.entry(name.to_os_string()).or_default();
+            }
+        }
+        node.matched = true;
+        node.is_dir |= is_dir;
+        self.len += 1;
+    }
+
+    /// Number of matches inserted so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Render the tree with box-drawing connectors, or `|--` style ones when
+    /// `ascii` is set, one line per node
+    pub fn render(&self, root_label: &str, ascii: bool) -> Vec<String> {
+        let mut lines = vec![root_label.blue().bold().to_string()];
+        let connectors = if ascii { &ASCII } else { &BOX_DRAWING };
+        Self::render_children(&self.root, "", connectors, &mut lines);
+        lines
+    }
+
+    fn render_children(
+        node: &Node,
+        prefix: &str,
+        connectors: &Connectors,
+        lines: &mut Vec<String>,
+    ) {
+        let count = node.children.len();
+        for (i, (name, child)) in node.children.iter().enumerate() {
+            let last = i + 1 == count;
+            let name = name.to_string_lossy();
+            // Ancestors shown only to reach a match are left unbolded
+            let label = match (child.is_dir, child.matched) {
+                (true, true) => name.blue().bold(),
+                (true, false) => name.blue(),
+                (false, _) => name.green(),
+            };
+            let connector = if last {
+                connectors.last
+            } else {
+                connectors.branch
+            };
+            lines.push(format!("{prefix}{connector}{label}"));
+
+            let child_prefix = format!("{prefix}{}", if last { "    " } else { connectors.pipe });
+            Self::render_children(child, &child_prefix, connectors, lines);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(matches: &[(&str, bool)]) -> PathTree {
+        let mut tree = PathTree::default();
+        for (path, is_dir) in matches {
+            tree.insert(Path::new(path), *is_dir);
+        }
+        tree
+    }
+
+    fn rendered(tree: &PathTree, ascii: bool) -> Vec<String> {
+        colored::control::set_override(false);
+        tree.render("root", ascii)
+    }
+
+    #[test]
+    fn renders_sorted_branches_below_the_root() {
+        let tree = tree(&[
+            ("src/ui/mod.rs", false),
+            ("README.md", false),
+            ("src/main.rs", false),
+            ("docs", true),
+        ]);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(
+            rendered(&tree, false),
+            [
+                "root",
+                "├── README.md",
+                "├── docs",
+                "└── src",
+                "    ├── main.rs",
+                "    └── ui",
+                "        └── mod.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn ascii_connectors_keep_the_same_shape() {
+        let tree = tree(&[("a/b.txt", false), ("a/c/d.txt", false), ("e.txt", false)]);
+        assert_eq!(
+            rendered(&tree, true),
+            [
+                "root",
+                "|-- a",
+                "|   |-- b.txt",
+                "|   `-- c",
+                "|       `-- d.txt",
+                "`-- e.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn ancestors_are_directories_but_not_matches() {
+        let tree = tree(&[("a/b/file", false), ("a", true)]);
+        let a = &tree.root.children[&OsString::from("a")];
+        let b = &a.children[&OsString::from("b")];
+        let file = &b.children[&OsString::from("file")];
+        assert!(a.is_dir && a.matched);
+        assert!(b.is_dir && !b.matched);
+        assert!(!file.is_dir && file.matched);
+    }
+
+    #[test]
+    fn empty_tree_is_only_the_root() {
+        let tree = PathTree::default();
+        assert!(tree.is_empty());
+        assert_eq!(rendered(&tree, false), ["root"]);
+    }
+}
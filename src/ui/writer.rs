@@ -0,0 +1,342 @@
+use serde::Serialize;
+use std::cell::Cell;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Destination for result paths printed by the display manager
+pub trait ResultWriter {
+    /// Write one result, where `shown` is the path as formatted for display
+    /// and `marker` an optional tag such as `[new]` to put in front
+    fn write_result(&mut self, path: &Path, shown: &str, marker: Option<&str>);
+
+    /// Write an informational line that accompanies results but isn't one
+    fn write_note(&mut self, line: &str);
+
+    /// Complete any outstanding work, returning false if some of it failed
+    fn finish(&mut self) -> bool {
+        true
+    }
+
+    /// Whether writing has failed, so any further results would be lost
+    fn failed(&self) -> bool {
+        false
+    }
+}
+
+/// Where results are written: stdout, or a file given with `--output`.
+///
+/// Writes stop at the first failure, which `finish` reports.
+pub struct Output {
+    file: Option<(PathBuf, BufWriter<File>)>,
+    error: Option<io::Error>,
+    terminator: u8,
+    pending: usize, // lines buffered but not yet flushed
+    lines: Rc<Cell<usize>>,
+}
+
+impl Output {
+    /// Write to stdout
+    pub fn stdout() -> Self {
+        Output {
+            file: None,
+            error: None,
+            terminator: b'\n',
+            pending: 0,
+            lines: Rc::default(),
+        }
+    }
+
+    /// Create `path`, truncating any existing file, and write to it
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Output {
+            file: Some((path.to_path_buf(), BufWriter::new(file))),
+            ..Self::stdout()
+        })
+    }
+
+    /// The file being written, if not stdout
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    /// Counter of lines flushed so far, which stays readable after the output moves into a writer
+    pub fn line_count(&self) -> Rc<Cell<usize>> {
+        Rc::clone(&self.lines)
+    }
+
+    /// Flush what's buffered, returning false after reporting any write error
+    pub(super) fn finish(&mut self) -> bool {
+        let _ = self.flush();
+        self.report()
+    }
+
+    /// Whether a write has failed
+    pub(super) fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Report the first failed write, returning false if there was one
+    fn report(&self) -> bool {
+        match (&self.error, &self.file) {
+            (Some(e), Some((path, _))) => {
+                eprintln!("Error: Failed to write {}: {e}", path.display());
+                false
+            }
+            // Whatever was reading stdout has seen all it wanted
+            (Some(e), None) if e.kind() == io::ErrorKind::BrokenPipe => false,
+            (Some(e), None) => {
+                eprintln!("Error: Failed to write to stdout: {e}");
+                false
+            }
+            (None, _) => true,
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(e) = &self.error {
+            return Err(e.kind().into());
+        }
+        let result = match &mut self.file {
+            Some((_, file)) => file.write(buf),
+            None => io::stdout().write(buf),
+        };
+        match result {
+            Ok(written) => {
+                let ended = buf[..written].iter().filter(|&&b| b == self.terminator);
+                self.pending += ended.count();
+                Ok(written)
+            }
+            Err(e) => {
+                let kind = e.kind();
+                self.error = Some(e);
+                Err(kind.into())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(e) = &self.error {
+            return Err(e.kind().into());
+        }
+        let result = match &mut self.file {
+            Some((_, file)) => file.flush(),
+            None => io::stdout().flush(),
+        };
+        match result {
+            Ok(()) => {
+                self.lines.set(self.lines.get() + self.pending);
+                self.pending = 0;
+                Ok(())
+            }
+            Err(e) => {
+                let kind = e.kind();
+                self.error = Some(e);
+                Err(kind.into())
+            }
+        }
+    }
+}
+
+/// Writes one result per line
+pub struct LineWriter {
+    out: Output,
+}
+
+impl LineWriter {
+    pub fn new(out: Output) -> Self {
+        LineWriter { out }
+    }
+}
+
+impl ResultWriter for LineWriter {
+    fn write_result(&mut self, _path: &Path, shown: &str, marker: Option<&str>) {
+        let _ = match marker {
+            Some(marker) => writeln!(self.out, "{marker} {shown}"),
+            None => writeln!(self.out, "{shown}"),
+        };
+        let _ = self.out.flush();
+    }
+
+    fn write_note(&mut self, line: &str) {
+        let _ = writeln!(self.out, "{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        self.out.finish()
+    }
+
+    fn failed(&self) -> bool {
+        self.out.failed()
+    }
+}
+
+/// Writes NUL-terminated results, keeping everything else on stderr
+pub struct NulWriter {
+    out: Output,
+}
+
+impl NulWriter {
+    pub fn new(mut out: Output) -> Self {
+        out.terminator = b'\0';
+        NulWriter { out }
+    }
+}
+
+impl ResultWriter for NulWriter {
+    fn write_result(&mut self, _path: &Path, shown: &str, _marker: Option<&str>) {
+        let _ = self.out.write_all(shown.as_bytes());
+        let _ = self.out.write_all(b"\0");
+        let _ = self.out.flush();
+    }
+
+    fn write_note(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        self.out.finish()
+    }
+
+    fn failed(&self) -> bool {
+        self.out.failed()
+    }
+}
+
+/// Writes results as CSV or TSV rows with file metadata
+pub struct DelimitedWriter {
+    writer: csv::Writer<Output>,
+    base_path: PathBuf,
+}
+
+impl DelimitedWriter {
+    /// Create a writer using `delimiter` between fields and emit the header row
+    pub fn new(delimiter: u8, base_path: PathBuf, out: Output) -> Self {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(out);
+        let _ = writer.write_record(["path", "type", "size", "mtime", "depth"]);
+        let _ = writer.flush();
+        DelimitedWriter { writer, base_path }
+    }
+}
+
+/// Metadata columns shared by the delimited, JSON, and template writers
+pub(super) struct Record {
+    pub file_type: &'static str,
+    pub size: Option<u64>,
+    pub mtime: Option<String>,
+    pub depth: usize,
+}
+
+impl Record {
+    /// Read a result's metadata, leaving fields empty when it can't be read
+    pub fn read(path: &Path, base_path: &Path) -> Self {
+        let metadata = fs::symlink_metadata(path).ok();
+        let file_type = match &metadata {
+            Some(m) if m.is_dir() => "dir",
+            Some(m) if m.file_type().is_symlink() => "symlink",
+            Some(_) => "file",
+            None => "unknown",
+        };
+        let size = metadata.as_ref().map(|m| m.len());
+        let mtime = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .map(|t| humantime::format_rfc3339_seconds(t).to_string());
+        let depth = path
+            .strip_prefix(base_path)
+            .map(|rel| rel.components().count())
+            .unwrap_or(0);
+        Record {
+            file_type,
+            size,
+            mtime,
+            depth,
+        }
+    }
+}
+
+impl ResultWriter for DelimitedWriter {
+    fn write_result(&mut self, path: &Path, shown: &str, _marker: Option<&str>) {
+        let record = Record::read(path, &self.base_path);
+        let _ = self.writer.write_record([
+            shown,
+            record.file_type,
+            &record.size.map(|s| s.to_string()).unwrap_or_default(),
+            record.mtime.as_deref().unwrap_or_default(),
+            &record.depth.to_string(),
+        ]);
+        let _ = self.writer.flush();
+    }
+
+    fn write_note(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        let _ = self.writer.flush();
+        self.writer.get_ref().report()
+    }
+
+    fn failed(&self) -> bool {
+        self.writer.get_ref().failed()
+    }
+}
+
+/// One result as emitted by the JSON lines writer
+#[derive(Serialize)]
+pub struct Match<'a> {
+    pub path: &'a str,
+    #[serde(rename = "type")]
+    pub file_type: &'static str,
+    pub size: Option<u64>,
+    pub mtime: Option<String>,
+    pub depth: usize,
+}
+
+/// Writes one compact JSON object per result, flushing after every line
+pub struct JsonLinesWriter {
+    base_path: PathBuf,
+    out: Output,
+}
+
+impl JsonLinesWriter {
+    /// Create a writer that reports depth relative to `base_path`
+    pub fn new(base_path: PathBuf, out: Output) -> Self {
+        JsonLinesWriter { base_path, out }
+    }
+}
+
+impl ResultWriter for JsonLinesWriter {
+    fn write_result(&mut self, path: &Path, shown: &str, _marker: Option<&str>) {
+        let record = Record::read(path, &self.base_path);
+        let entry = Match {
+            path: shown,
+            file_type: record.file_type,
+            size: record.size,
+            mtime: record.mtime,
+            depth: record.depth,
+        };
+        if serde_json::to_writer(&mut self.out, &entry).is_ok() {
+            let _ = self.out.write_all(b"\n");
+        }
+        let _ = self.out.flush();
+    }
+
+    fn write_note(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn finish(&mut self) -> bool {
+        self.out.finish()
+    }
+
+    fn failed(&self) -> bool {
+        self.out.failed()
+    }
+}
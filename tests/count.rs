@@ -0,0 +1,52 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::{Command, Output};
+
+fn seek(dir: &tempfile::TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .args(args)
+        .args(["--no-config", "--no-history", "--no-progress"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap()
+}
+
+fn tree() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.rs", "b.rs", "src/c.rs", "src/deeper/d.rs", "notes.txt"] {
+        let path = dir.path().join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+    fs::create_dir(dir.path().join("dir.rs")).unwrap();
+    dir
+}
+
+#[test]
+fn count_agrees_with_the_listed_results() {
+    let dir = tree();
+    for args in [
+        &["*.rs"][..],
+        &["*.rs", "--files-only"],
+        &["*.rs", "--dirs-only"],
+        &["*.rs", "--max-depth", "2"],
+    ] {
+        let listed = seek(&dir, args);
+        let lines = String::from_utf8(listed.stdout).unwrap().lines().count();
+
+        let counted = seek(&dir, &[args, &["-c"]].concat());
+        assert_eq!(counted.status.code(), Some(0), "{args:?}: {counted:?}");
+        let stdout = String::from_utf8(counted.stdout).unwrap();
+        assert_eq!(stdout, format!("{lines}\n"), "{args:?}");
+    }
+}
+
+#[test]
+fn no_matches_count_zero() {
+    let dir = tree();
+    let output = seek(&dir, &["*.md", "--count"]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0\n");
+}
@@ -0,0 +1,59 @@
+#![cfg(all(feature = "cli", unix))]
+
+use std::fs;
+use std::process::Command;
+
+/// Run with `--format format`, reading the rows back with `delimiter`
+fn rows(dir: &tempfile::TempDir, format: &str, delimiter: u8) -> Vec<csv::StringRecord> {
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .args(["*", "--format", format, "--relative", "--files-only"])
+        .args(["--no-config", "--no-history", "--no-progress"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Found 3 matches"), "{stderr}");
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(output.stdout.as_slice());
+    assert_eq!(
+        reader.headers().unwrap(),
+        vec!["path", "type", "size", "mtime", "depth"]
+    );
+    let mut rows: Vec<_> = reader.records().map(Result::unwrap).collect();
+    rows.sort_by(|a, b| a[0].cmp(&b[0]));
+    rows
+}
+
+#[test]
+fn rows_round_trip_with_awkward_names() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("plain.txt"), "12345").unwrap();
+    fs::write(dir.path().join("a,b \"quoted\".txt"), "").unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub").join("tab\there.txt"), "xy").unwrap();
+
+    for (format, delimiter) in [("csv", b','), ("tsv", b'\t')] {
+        let rows = rows(&dir, format, delimiter);
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|row| (&row[0], &row[1], &row[2], &row[4]))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("a,b \"quoted\".txt", "file", "0", "1"),
+                ("plain.txt", "file", "5", "1"),
+                ("sub/tab\there.txt", "file", "2", "2"),
+            ],
+            "{format}"
+        );
+        // Modification times are RFC 3339 in UTC
+        for row in &rows {
+            assert!(humantime::parse_rfc3339(&row[3]).is_ok(), "{:?}", &row[3]);
+        }
+    }
+}
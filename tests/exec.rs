@@ -0,0 +1,58 @@
+#![cfg(all(feature = "cli", unix))]
+
+use std::fs;
+use std::process::Command;
+
+fn seek(dir: &tempfile::TempDir) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_seek"));
+    command
+        .arg(dir.path())
+        .arg("*.txt")
+        .args(["--no-config", "--no-history", "--no-progress"])
+        .env_remove("SEEK_DEFAULT_OPTS");
+    command
+}
+
+fn three_files() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(dir.path().join(name), "").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn failing_exec_command_is_an_error_exit() {
+    let dir = three_files();
+    let status = seek(&dir).args(["--exec", "true"]).status().unwrap();
+    assert_eq!(status.code(), Some(0));
+    let status = seek(&dir).args(["--exec", "false"]).status().unwrap();
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn batch_note_counts_the_runs() {
+    let dir = three_files();
+    let path_len = dir.path().join("a.txt").as_os_str().len();
+    // Room for `true` and one path per run, each with its terminator and pointer
+    let cost = |len: usize| len + 1 + std::mem::size_of::<usize>();
+    let limit = cost("true".len()) + cost(path_len);
+    let output = seek(&dir)
+        .args(["--exec-arg-max", &limit.to_string(), "--exec-batch", "true"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Ran 3 batch commands over 3 paths"),
+        "{stderr}"
+    );
+
+    let output = seek(&dir).args(["--exec-batch", "false"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Ran 1 batch command over 3 paths, 1 failed:"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn seek(path: &Path, pattern: &str) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_seek"));
+    command
+        .arg(path)
+        .arg(pattern)
+        .args(["--no-config", "--no-history", "--no-progress"])
+        .env_remove("SEEK_DEFAULT_OPTS");
+    command
+}
+
+fn tree() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..20 {
+        fs::write(dir.path().join(format!("file{i:02}.txt")), "").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn matches_exit_zero_and_no_matches_exit_one() {
+    let dir = tree();
+    let status = seek(dir.path(), "*.txt").output().unwrap().status;
+    assert_eq!(status.code(), Some(0));
+    let status = seek(dir.path(), "*.none").output().unwrap().status;
+    assert_eq!(status.code(), Some(1));
+    let status = seek(dir.path(), "*.none")
+        .arg("--no-exit-code")
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(0));
+}
+
+#[test]
+fn errors_exit_two() {
+    let dir = tree();
+    let missing = dir.path().join("missing");
+    let status = seek(&missing, "*.txt").output().unwrap().status;
+    assert_eq!(status.code(), Some(2));
+    let status = seek(dir.path(), "(").arg("-r").output().unwrap().status;
+    assert_eq!(status.code(), Some(2));
+}
+
+#[cfg(unix)]
+#[test]
+fn ctrl_c_exits_130() {
+    let dir = tree();
+    // Each match runs a slow command, so the search is still going when interrupted
+    let mut child = seek(dir.path(), "*.txt")
+        .args([
+            "--channel-capacity",
+            "1",
+            "--exec",
+            "sh",
+            "-c",
+            "sleep 0.2",
+            "sh",
+        ])
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let killed = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130), "{status}");
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::Command;
+
+fn seek(dir: &tempfile::TempDir, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .args(args)
+        .args(["--no-config", "--no-history", "--no-progress", "--relative"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Bold red, as matched text is shown
+fn highlighted(text: &str) -> String {
+    format!("\x1b[1;31m{text}\x1b[0m")
+}
+
+#[test]
+fn matched_text_is_highlighted_within_the_name() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a_needle_b.txt"), "").unwrap();
+
+    let shown = seek(&dir, &["*needle*", "--color", "always"]);
+    assert!(shown.contains(&highlighted("needle")), "{shown:?}");
+    assert!(!shown.contains(&highlighted("a_")), "{shown:?}");
+
+    let shown = seek(&dir, &["needle|txt", "--regex", "--color", "always"]);
+    assert!(shown.contains(&highlighted("needle")), "{shown:?}");
+    assert!(shown.contains(&highlighted("txt")), "{shown:?}");
+}
+
+#[test]
+fn nothing_is_highlighted_without_color() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a_needle_b.txt"), "").unwrap();
+    assert_eq!(
+        seek(&dir, &["*needle*", "--color", "never"]),
+        "a_needle_b.txt\n"
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn seek(cwd: &Path, state: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_seek"));
+    command
+        .current_dir(cwd)
+        .env("XDG_STATE_HOME", state)
+        .env_remove("SEEK_DEFAULT_OPTS");
+    command
+}
+
+#[cfg(unix)]
+#[test]
+fn again_replays_the_arguments_in_the_original_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let tree = dir.path().join("tree");
+    let elsewhere = dir.path().join("elsewhere");
+    let state = dir.path().join("state");
+    fs::create_dir_all(&tree).unwrap();
+    fs::create_dir_all(&elsewhere).unwrap();
+    fs::write(tree.join("a.txt"), "").unwrap();
+    fs::write(tree.join("b.log"), "").unwrap();
+    // The same name in the other directory must not be found by the replay
+    fs::write(elsewhere.join("c.txt"), "").unwrap();
+
+    let first = seek(&tree, &state)
+        .args([".", "*.txt", "--no-config", "--no-progress"])
+        .output()
+        .unwrap();
+    assert_eq!(first.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&first.stdout), "./a.txt\n");
+
+    let again = seek(&elsewhere, &state).arg("--again").output().unwrap();
+    assert_eq!(again.status.code(), Some(0));
+    assert_eq!(again.stdout, first.stdout);
+    let stderr = String::from_utf8_lossy(&again.stderr);
+    assert!(
+        stderr.starts_with("seek . '*.txt' --no-config --no-progress\n"),
+        "{stderr}"
+    );
+
+    // Both runs were recorded; --no-history leaves the file alone
+    let history = || fs::read_to_string(state.join("seek").join("history.jsonl")).unwrap();
+    assert_eq!(history().lines().count(), 2);
+    seek(&tree, &state)
+        .args([".", "*.txt", "--no-config", "--no-progress", "--no-history"])
+        .output()
+        .unwrap();
+    assert_eq!(history().lines().count(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn again_without_history_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = seek(dir.path(), &dir.path().join("state"))
+        .arg("--again")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("no searches in the history to run again")
+    );
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::Command;
+
+const RUST_ICON: char = '\u{e7a8}';
+
+fn seek_with_icons(dir: &tempfile::TempDir, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .arg("*.rs")
+        .args(["--icons", "--no-config", "--no-history", "--no-progress"])
+        .args(args)
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .env_remove("LC_ALL")
+        .env_remove("LC_CTYPE")
+        .env("LANG", "en_US.UTF-8")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn icons_only_decorate_plain_text() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.rs"), "").unwrap();
+
+    let text = seek_with_icons(&dir, &[]);
+    assert!(text.starts_with(&format!("{RUST_ICON} ")), "{text:?}");
+
+    for args in [
+        &["-0"][..],
+        &["--format", "jsonl"],
+        &["--format", "csv"],
+        &["--format", "tsv"],
+        &["--format", "{name}"],
+    ] {
+        let output = seek_with_icons(&dir, args);
+        assert!(output.contains("main.rs"), "{args:?}: {output:?}");
+        assert!(!output.contains(RUST_ICON), "{args:?}: {output:?}");
+    }
+}
+
+#[test]
+fn trees_fall_back_to_ascii_outside_utf8_locales() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/main.rs"), "").unwrap();
+
+    let tree = |lang: &str, args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+            .arg(dir.path())
+            .arg("*.rs")
+            .args(["--tree", "--no-config", "--no-history", "--no-progress"])
+            .args(["--color", "never"])
+            .args(args)
+            .env_remove("SEEK_DEFAULT_OPTS")
+            .env_remove("LC_ALL")
+            .env_remove("LC_CTYPE")
+            .env("LANG", lang)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert!(tree("en_US.UTF-8", &[]).contains("└── src"));
+    assert!(tree("C", &[]).contains("`-- src"));
+    assert!(tree("en_US.UTF-8", &["--ascii"]).contains("`-- src"));
+}
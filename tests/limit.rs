@@ -0,0 +1,78 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::{Command, Output};
+
+fn seek(dir: &tempfile::TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .arg("*.txt")
+        .args(args)
+        .args([
+            "--no-config",
+            "--no-history",
+            "--no-progress",
+            "--color",
+            "never",
+        ])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap()
+}
+
+fn five_files() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..5 {
+        fs::write(dir.path().join(format!("{i}.txt")), "").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn limit_prints_only_the_first_matches() {
+    let dir = five_files();
+    let output = seek(&dir, &["--limit", "2"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 2);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Found 2 matches (0B, match limit reached)"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn counting_past_the_limit_reports_the_true_total() {
+    let dir = five_files();
+    let output = seek(&dir, &["--limit", "2", "--count-all-with-limit"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 2);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Found 5 matches (0B, showing 2)"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn counting_past_the_limit_needs_a_limit() {
+    let dir = five_files();
+    let output = seek(&dir, &["--count-all-with-limit"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn head_keeps_searching_past_the_printed_matches() {
+    let dir = five_files();
+    let output = seek(&dir, &["--head", "2"]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 3, "{stdout}");
+    assert!(stdout.ends_with("... and 3 more matches (use --head 0 to show all)\n"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 5 matches"), "{stderr}");
+
+    let output = seek(&dir, &["--head", "0"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 5);
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::{Command, Stdio};
+
+/// A tree with enough matches that a search outlives a failed first write
+fn many_files() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..2000 {
+        fs::write(dir.path().join(format!("file{i:04}.txt")), "").unwrap();
+    }
+    dir
+}
+
+fn seek(dir: &tempfile::TempDir) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_seek"));
+    command
+        .arg(dir.path())
+        .arg("*.txt")
+        .args([
+            "--no-config",
+            "--no-history",
+            "--no-progress",
+            "--timeout",
+            "20",
+        ])
+        .env_remove("SEEK_DEFAULT_OPTS");
+    command
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn full_output_disk_fails_the_search() {
+    let dir = many_files();
+    let output = seek(&dir).args(["--output", "/dev/full"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to write /dev/full"), "{stderr}");
+    // The search is abandoned rather than finished and summarized
+    assert!(!stderr.contains("Search complete"), "{stderr}");
+}
+
+#[cfg(unix)]
+#[test]
+fn closed_stdout_fails_the_search_quietly() {
+    let dir = many_files();
+    let mut child = seek(&dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Failed to write"), "{stderr}");
+}
+
+#[test]
+fn piped_results_are_only_linked_when_forced() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "").unwrap();
+    let linked = |args: &[&str]| {
+        let output = seek(&dir).args(args).output().unwrap();
+        assert_eq!(output.status.code(), Some(0));
+        String::from_utf8_lossy(&output.stdout).contains("\x1b]8;;file://")
+    };
+    assert!(!linked(&[]));
+    assert!(!linked(&["--hyperlink"]));
+    assert!(linked(&["--hyperlink=always"]));
+}
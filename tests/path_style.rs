@@ -0,0 +1,70 @@
+#![cfg(all(feature = "cli", unix))]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Sorted results of searching `base` from `cwd`
+fn seek(cwd: &Path, base: &str, args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .current_dir(cwd)
+        .arg(base)
+        .args(args)
+        .args(["--no-config", "--no-history", "--no-progress"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let mut lines: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    lines.sort();
+    lines
+}
+
+fn sample() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+    dir
+}
+
+#[test]
+fn relative_paths_drop_the_base_however_it_was_written() {
+    let dir = sample();
+    let cwd = dir.path();
+    let absolute = cwd.display().to_string();
+
+    assert_eq!(seek(cwd, ".", &["*.rs"]), ["./src/lib.rs"]);
+    for base in [".", "./", &absolute, &format!("{absolute}/")] {
+        assert_eq!(
+            seek(cwd, base, &["*.rs", "--relative"]),
+            ["src/lib.rs"],
+            "{base}"
+        );
+    }
+    // The root itself, when it's a result, is `.`
+    assert_eq!(
+        seek(cwd, &absolute, &["*", "--include-root", "--relative"]),
+        [".", "src", "src/lib.rs"]
+    );
+}
+
+#[test]
+fn absolute_paths_resolve_the_base_once() {
+    let dir = sample();
+    let cwd = dir.path();
+    let canonical = cwd.canonicalize().unwrap().join("src/lib.rs");
+    let expected = [canonical.display().to_string()];
+
+    for base in [".", "./", "src/.."] {
+        assert_eq!(seek(cwd, base, &["*.rs", "--absolute"]), expected, "{base}");
+    }
+
+    // A symlinked base resolves to where it points
+    let link = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(cwd, link.path().join("proj")).unwrap();
+    assert_eq!(seek(link.path(), "proj", &["*.rs", "--absolute"]), expected);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::{Command, Output};
+
+/// Run with stdout and stderr both captured, so neither is a terminal
+fn seek(dir: &tempfile::TempDir, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .arg("*.rs")
+        .args(["--no-config", "--no-history", "--relative"])
+        .args(args)
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    output
+}
+
+fn sample() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.rs"), "").unwrap();
+    fs::write(dir.path().join("lib.rs"), "").unwrap();
+    dir
+}
+
+#[test]
+fn piped_output_is_plain_paths() {
+    let dir = sample();
+    let output = seek(&dir, &[]);
+    let mut lines: Vec<_> = std::str::from_utf8(&output.stdout)
+        .unwrap()
+        .lines()
+        .collect();
+    lines.sort();
+    assert_eq!(lines, ["lib.rs", "main.rs"]);
+    // No colors anywhere, and no spinner redraws on stderr
+    assert!(!output.stdout.contains(&0x1b), "{output:?}");
+    assert!(!output.stderr.contains(&0x1b), "{output:?}");
+    assert!(!output.stderr.contains(&b'\r'), "{output:?}");
+}
+
+#[test]
+fn color_can_be_forced_into_a_pipe() {
+    let dir = sample();
+    let output = seek(&dir, &["--color", "always", "--no-progress"]);
+    assert!(output.stdout.contains(&0x1b), "{output:?}");
+
+    let output = seek(&dir, &["--color", "never"]);
+    assert!(!output.stdout.contains(&0x1b), "{output:?}");
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn matched_names(dir: &tempfile::TempDir, args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .args(args)
+        .args([
+            "--no-config",
+            "--no-history",
+            "--no-progress",
+            "--color",
+            "never",
+        ])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.code().is_some_and(|code| code <= 1),
+        "{output:?}"
+    );
+    let mut names: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter_map(|line| Path::new(line).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn presets_narrow_the_pattern_and_add_to_ext() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["cat.png", "cat.JPG", "cat.txt", "dog.png", "notes.md"] {
+        fs::write(dir.path().join(name), "").unwrap();
+    }
+    fs::create_dir(dir.path().join("cats.png")).unwrap();
+
+    assert_eq!(
+        matched_names(&dir, &["--preset", "images"]),
+        ["cat.JPG", "cat.png", "dog.png"]
+    );
+    assert_eq!(
+        matched_names(&dir, &["cat*", "--preset", "images"]),
+        ["cat.JPG", "cat.png"]
+    );
+    assert_eq!(
+        matched_names(&dir, &["cat*", "--preset", "images", "--ext", "txt"]),
+        ["cat.JPG", "cat.png", "cat.txt"]
+    );
+}
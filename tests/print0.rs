@@ -0,0 +1,32 @@
+#![cfg(all(feature = "cli", unix))]
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn names_with_newlines_survive_nul_separation() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("two\nlines.txt"), "").unwrap();
+    fs::write(dir.path().join("with space.txt"), "").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .args(["*.txt", "-0", "--relative"])
+        .args(["--no-config", "--no-history", "--no-progress"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+
+    // Every result ends in NUL and nothing else reaches stdout
+    assert_eq!(output.stdout.last(), Some(&0));
+    let mut names: Vec<&str> = output.stdout[..output.stdout.len() - 1]
+        .split(|&b| b == 0)
+        .map(|name| std::str::from_utf8(name).unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["two\nlines.txt", "with space.txt"]);
+    assert!(!output.stdout.contains(&0x1b), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Found 2 matches"), "{stderr}");
+}
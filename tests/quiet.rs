@@ -0,0 +1,44 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn seek_quiet(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_seek"))
+        .current_dir(dir)
+        .args(args)
+        .args(["-q", "--no-config", "--no-history"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap()
+}
+
+fn assert_silent(output: &Output, code: i32) {
+    assert_eq!(output.status.code(), Some(code));
+    assert!(output.stdout.is_empty(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
+
+#[test]
+fn quiet_prints_nothing_whatever_the_outcome() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub/a.txt"), "").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("sub/loop")).unwrap();
+
+    // Warnings about the arguments and errors from the walk are all held back
+    let noisy = ["--threads", "1000", "-L"];
+    assert_silent(
+        &seek_quiet(dir.path(), &[&[".", "*.txt"][..], &noisy].concat()),
+        0,
+    );
+    assert_silent(
+        &seek_quiet(dir.path(), &[&[".", "*.rs"][..], &noisy].concat()),
+        1,
+    );
+    // A lone missing path would otherwise be warned about as a likely typo
+    let missing = Path::new("missing").join("a.txt");
+    assert_silent(&seek_quiet(dir.path(), &[missing.to_str().unwrap()]), 1);
+}
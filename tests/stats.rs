@@ -0,0 +1,93 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn stats_by_dir_prints_the_largest_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    for (sub, count) in [("logs", 3), ("logs/old", 2), ("tmp", 1)] {
+        fs::create_dir_all(dir.path().join(sub)).unwrap();
+        for i in 0..count {
+            fs::write(dir.path().join(sub).join(format!("{i}.log")), "").unwrap();
+        }
+    }
+    fs::write(dir.path().join("top.log"), "").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .arg("*.log")
+        .args(["--stats-by", "dir", "--top", "2", "--relative"])
+        .args([
+            "--no-config",
+            "--no-history",
+            "--no-progress",
+            "--color",
+            "never",
+        ])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let table = &stderr[stderr.find("Matches by directory:").expect(&stderr)..];
+    assert_eq!(
+        table,
+        "Matches by directory:\n  logs      3\n  logs/old  2\n  ... 2 more\n"
+    );
+}
+
+#[test]
+fn stats_only_summarizes_the_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("a.txt"), "hello").unwrap();
+    fs::write(dir.path().join("sub/big.bin"), vec![0; 2048]).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .args(["--stats-only", "--relative"])
+        .args([
+            "--no-config",
+            "--no-history",
+            "--no-progress",
+            "--color",
+            "never",
+        ])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Files:        2\n\
+         Directories:  1\n\
+         Total size:   2.0K (2053 bytes)\n\
+         Largest file: sub/big.bin (2.0K)\n"
+    );
+}
+
+#[test]
+fn stats_say_how_many_threads_were_requested() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.log"), "").unwrap();
+    let threads = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+            .arg(dir.path())
+            .arg("*.log")
+            .args(["--stats", "--no-config", "--no-history", "--no-progress"])
+            .args(["--color", "never"])
+            .args(args)
+            .env_remove("SEEK_DEFAULT_OPTS")
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let line = stderr.lines().find(|line| line.contains("Threads:"));
+        line.expect(&stderr).trim().to_string()
+    };
+    assert_eq!(threads(&["-j", "1"]), "Threads:          1");
+    assert_eq!(
+        threads(&["-j", "4"]),
+        "Threads:          1 (4 requested; the walk is single-threaded)"
+    );
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn status_json_writes_only_json_lines_ending_in_done() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.txt", "b.txt", "c.md"] {
+        fs::write(dir.path().join(name), "").unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_seek"))
+        .arg(dir.path())
+        .arg("*.txt")
+        .args(["--status-json", "--no-config", "--no-history"])
+        .env_remove("SEEK_DEFAULT_OPTS")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 2);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{line}: {e}")))
+        .collect();
+    assert_eq!(events.last().unwrap()["event"], "done");
+    let totals = &events[events.len() - 2];
+    assert_eq!(totals["event"], "heartbeat");
+    assert_eq!(totals["data"]["found"], 2);
+}